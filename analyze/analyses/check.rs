@@ -0,0 +1,368 @@
+use std::io;
+
+use serde_derive::Serialize;
+
+use super::crates::{crate_name, UNKNOWN};
+use super::group::pattern_to_regex;
+use crate::formats::html;
+use crate::formats::json;
+use crate::formats::table::{Align, Table};
+use twiggy_ir as ir;
+use twiggy_opt as opt;
+use twiggy_traits as traits;
+
+#[derive(Debug)]
+struct Check {
+    results: Vec<BudgetResult>,
+}
+
+#[derive(Debug)]
+struct BudgetResult {
+    /// The budget's name, as it was written in the budget file.
+    name: String,
+    /// The size that the budget limits. This is a delta for growth budgets.
+    size: i64,
+    /// The limit, which is fractional when it is a percentage of the
+    /// baseline's size.
+    limit: f64,
+    /// Whether `size` is a delta, and should be shown with its sign.
+    delta: bool,
+}
+
+impl BudgetResult {
+    fn exceeded(&self) -> bool {
+        self.size as f64 > self.limit
+    }
+
+    fn status(&self) -> &'static str {
+        if self.exceeded() {
+            "exceeded"
+        } else {
+            "ok"
+        }
+    }
+
+    fn size_string(&self) -> String {
+        if self.delta {
+            format!("{:+}", self.size)
+        } else {
+            self.size.to_string()
+        }
+    }
+
+    fn limit_string(&self) -> String {
+        if self.delta {
+            format!("{:+}", self.limit)
+        } else {
+            self.limit.to_string()
+        }
+    }
+}
+
+impl traits::Emit for Check {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Bytes".to_string()),
+            (Align::Right, "Limit".to_string()),
+            (Align::Left, "Status".to_string()),
+            (Align::Left, "Budget".to_string()),
+        ]);
+
+        for result in &self.results {
+            table.add_row(vec![
+                result.size_string(),
+                result.limit_string(),
+                result.status().to_string(),
+                result.name.clone(),
+            ]);
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut arr = json::array(dest)?;
+        for result in &self.results {
+            let mut obj = arr.object()?;
+            obj.field("name", result.name.as_str())?;
+            obj.field("bytes", result.size as f64)?;
+            obj.field("limit", result.limit)?;
+            obj.field("status", result.status())?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord<'a> {
+            name: &'a str,
+            bytes: i64,
+            limit: String,
+            status: &'a str,
+        }
+
+        for result in &self.results {
+            wtr.serialize(CsvRecord {
+                name: &result.name,
+                bytes: result.size,
+                limit: result.limit.to_string(),
+                status: result.status(),
+            })?;
+            wtr.flush()?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, _items: &ir::Items, _dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        Err(traits::Error::with_msg(
+            "the `dot` output format is not supported by this analysis",
+        ))
+    }
+
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut page = html::page(dest, "twiggy check")?;
+        let mut table = page.table(&["Bytes", "Limit", "Status", "Budget"])?;
+
+        for result in &self.results {
+            let size = if result.delta {
+                html::Cell::Delta(result.size)
+            } else {
+                html::Cell::Bytes(result.size)
+            };
+            table.row(&[
+                size,
+                html::Cell::Limit {
+                    bytes: result.limit,
+                    delta: result.delta,
+                },
+                html::Cell::Text(result.status()),
+                html::Cell::Name(&result.name),
+            ])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A limit on some part of a binary's size.
+#[derive(Debug)]
+enum Budget {
+    /// The size of the whole binary.
+    Total,
+    /// How much larger the binary is than the baseline, either in bytes or as
+    /// a percentage of the baseline's size.
+    Growth { percent: bool },
+    /// The size of the functions that belong to a crate.
+    Crate(String),
+    /// The size of the items of one kind: code, data, debug, or misc.
+    Kind(String),
+    /// The size of the items carved out of a section of the binary, such as
+    /// `.text` or a wasm `code` section.
+    Section(String),
+    /// The size of the items whose names match a pattern.
+    Items(regex::Regex),
+}
+
+/// Check a binary against the budgets in a budget file, optionally comparing
+/// it with a baseline version of the binary. Returns the report, and how many
+/// budgets were exceeded.
+pub fn check(
+    items: &ir::Items,
+    baseline: Option<&ir::Items>,
+    _opts: &opt::Check,
+    budgets: &str,
+) -> Result<(Box<dyn traits::Emit>, usize), traits::Error> {
+    let mut results = vec![];
+    for (name, budget, limit) in parse_budgets(budgets)? {
+        let result = match budget {
+            Budget::Total => BudgetResult {
+                name,
+                size: i64::from(items.size()),
+                limit,
+                delta: false,
+            },
+            Budget::Growth { percent } => {
+                let baseline = baseline.ok_or_else(|| {
+                    traits::Error::with_msg(
+                        "the budget file limits growth, so a baseline must be given with \
+                         `--baseline`",
+                    )
+                })?;
+                let old_size = i64::from(baseline.size());
+                BudgetResult {
+                    name,
+                    size: i64::from(items.size()) - old_size,
+                    limit: if percent {
+                        old_size as f64 * limit / 100.0
+                    } else {
+                        limit
+                    },
+                    delta: true,
+                }
+            }
+            Budget::Crate(ref krate) => BudgetResult {
+                size: size_of(items, |item| match item.kind() {
+                    ir::ItemKind::Code(code) => {
+                        let name = code.demangled().unwrap_or_else(|| item.name());
+                        crate_name(name).unwrap_or(UNKNOWN) == krate
+                    }
+                    _ => false,
+                }),
+                name,
+                limit,
+                delta: false,
+            },
+            Budget::Kind(ref kind) => BudgetResult {
+                size: size_of(items, |item| kind_name(item) == kind),
+                name,
+                limit,
+                delta: false,
+            },
+            Budget::Section(ref section) => BudgetResult {
+                size: items
+                    .iter()
+                    .filter(|item| item.id() != items.meta_root())
+                    .flat_map(|item| items.sections(item.id()))
+                    .filter(|(name, _)| name == section)
+                    .map(|&(_, size)| i64::from(size))
+                    .sum(),
+                name,
+                limit,
+                delta: false,
+            },
+            Budget::Items(ref regex) => BudgetResult {
+                size: size_of(items, |item| regex.is_match(item.name())),
+                name,
+                limit,
+                delta: false,
+            },
+        };
+        results.push(result);
+    }
+
+    let exceeded = results.iter().filter(|result| result.exceeded()).count();
+    Ok((Box::new(Check { results }) as Box<_>, exceeded))
+}
+
+/// Sum up the shallow sizes of the items that match the given predicate.
+fn size_of<F>(items: &ir::Items, predicate: F) -> i64
+where
+    F: Fn(&ir::Item) -> bool,
+{
+    items
+        .iter()
+        .filter(|item| item.id() != items.meta_root())
+        .filter(|item| predicate(item))
+        .map(|item| i64::from(item.size()))
+        .sum()
+}
+
+fn kind_name(item: &ir::Item) -> &'static str {
+    match item.kind() {
+        ir::ItemKind::Code(_) => "code",
+        ir::ItemKind::Data(_) => "data",
+        ir::ItemKind::Debug(_) => "debug",
+        ir::ItemKind::Misc(_) => "misc",
+    }
+}
+
+/// Parse the budgets in a budget file, and return each budget's name, what it
+/// limits, and its limit. Each line holds a budget, like `crate core = 40KiB`.
+/// Blank lines and lines starting with `#` are ignored.
+fn parse_budgets(budgets: &str) -> Result<Vec<(String, Budget, f64)>, traits::Error> {
+    let mut parsed = vec![];
+    for (i, line) in budgets.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid = |what: &str| {
+            traits::Error::with_msg(format!(
+                "invalid budget on line {} of the budget file, {}: {}",
+                i + 1,
+                what,
+                line
+            ))
+        };
+
+        let eq = line
+            .find('=')
+            .ok_or_else(|| invalid("expected `<budget> = <limit>`"))?;
+        let name = line[..eq].trim();
+        let limit = line[eq + 1..].trim();
+
+        let (kind, arg) = match name.find(char::is_whitespace) {
+            Some(space) => (&name[..space], Some(name[space..].trim())),
+            None => (name, None),
+        };
+        let budget = match (kind, arg) {
+            ("total", None) => Budget::Total,
+            ("growth", None) => Budget::Growth {
+                percent: limit.ends_with('%'),
+            },
+            ("crate", Some(krate)) => Budget::Crate(krate.to_string()),
+            ("kind", Some(item_kind)) => match item_kind {
+                "code" | "data" | "debug" | "misc" => Budget::Kind(item_kind.to_string()),
+                _ => {
+                    return Err(invalid(
+                        "expected a kind of `code`, `data`, `debug`, or `misc`",
+                    ))
+                }
+            },
+            ("section", Some(section)) => Budget::Section(section.to_string()),
+            ("items", Some(pattern)) => {
+                Budget::Items(regex::Regex::new(&pattern_to_regex(pattern))?)
+            }
+            _ => {
+                return Err(invalid(
+                    "expected `total`, `growth`, `crate <name>`, `kind <name>`, \
+                     `section <name>`, or `items <pattern>`",
+                ))
+            }
+        };
+
+        let limit = match budget {
+            Budget::Growth { percent: true } => limit[..limit.len() - 1]
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|percent| percent.is_finite())
+                .ok_or_else(|| invalid("expected a percentage like `5%` or `2.5%`"))?,
+            _ => parse_size(limit)
+                .ok_or_else(|| invalid("expected a size like `1024`, `4KiB`, or `1MB`"))?
+                as f64,
+        };
+        parsed.push((name.to_string(), budget, limit));
+    }
+    Ok(parsed)
+}
+
+/// Parse a size in bytes, with an optional unit: `B`, `KB`, `MB` or `GB` for
+/// powers of 1000, or `KiB`, `MiB` or `GiB` for powers of 1024.
+fn parse_size(size: &str) -> Option<i64> {
+    let digits = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let number: i64 = size[..digits].parse().ok()?;
+    let multiplier = match size[digits..].trim().to_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1000,
+        "kib" => 1024,
+        "mb" => 1000 * 1000,
+        "mib" => 1024 * 1024,
+        "gb" => 1000 * 1000 * 1000,
+        "gib" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.checked_mul(multiplier)
+}
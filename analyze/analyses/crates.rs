@@ -0,0 +1,371 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+
+use petgraph::visit::Walker;
+use serde_derive::Serialize;
+
+use crate::formats::dot;
+use crate::formats::html;
+use crate::formats::json;
+use crate::formats::table::{Align, Table};
+use twiggy_ir as ir;
+use twiggy_opt as opt;
+use twiggy_traits as traits;
+
+/// The name of the group for functions whose names aren't Rust paths.
+pub(crate) const UNKNOWN: &str = "<unknown>";
+
+#[derive(Debug)]
+struct Crates {
+    crates: Vec<CrateEntry>,
+
+    // The edges between crates, wherever a function in one crate references
+    // a function in another.
+    edges: BTreeSet<(usize, usize)>,
+
+    limit: usize,
+}
+
+#[derive(Debug)]
+struct CrateEntry {
+    name: String,
+    size: u32,
+    retained_size: u32,
+}
+
+impl Crates {
+    fn percent(items: &ir::Items, size: u32) -> f64 {
+        f64::from(size) / f64::from(items.size()) * 100.0
+    }
+
+    /// The summary rows: the crates that didn't fit within the limit, if any,
+    /// and the total. Retained sizes overlap one another, so they can't be
+    /// summed up.
+    fn summaries(&self) -> Vec<(String, u32)> {
+        let mut summaries = vec![];
+        if self.crates.len() > self.limit {
+            let remaining = &self.crates[self.limit..];
+            let size = remaining.iter().map(|entry| entry.size).sum();
+            summaries.push((format!("... and {} more.", remaining.len()), size));
+        }
+        let size = self.crates.iter().map(|entry| entry.size).sum();
+        summaries.push((format!("Σ [{} Total Rows]", self.crates.len()), size));
+        summaries
+    }
+}
+
+impl traits::Emit for Crates {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Shallow Bytes".to_string()),
+            (Align::Right, "Shallow %".to_string()),
+            (Align::Right, "Retained Bytes".to_string()),
+            (Align::Right, "Retained %".to_string()),
+            (Align::Left, "Crate".to_string()),
+        ]);
+
+        for entry in self.crates.iter().take(self.limit) {
+            table.add_row(vec![
+                entry.size.to_string(),
+                format!("{:.2}%", Self::percent(items, entry.size)),
+                entry.retained_size.to_string(),
+                format!("{:.2}%", Self::percent(items, entry.retained_size)),
+                entry.name.clone(),
+            ]);
+        }
+
+        for (name, size) in self.summaries() {
+            table.add_row(vec![
+                size.to_string(),
+                format!("{:.2}%", Self::percent(items, size)),
+                "...".to_string(),
+                "...".to_string(),
+                name,
+            ]);
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut arr = json::array(dest)?;
+        for entry in self.crates.iter().take(self.limit) {
+            let mut obj = arr.object()?;
+            obj.field("name", entry.name.as_str())?;
+            obj.field("shallow_size", entry.size)?;
+            obj.field("shallow_size_percent", Self::percent(items, entry.size))?;
+            obj.field("retained_size", entry.retained_size)?;
+            obj.field(
+                "retained_size_percent",
+                Self::percent(items, entry.retained_size),
+            )?;
+        }
+
+        for (name, size) in self.summaries() {
+            let mut obj = arr.object()?;
+            obj.field("name", name.as_str())?;
+            obj.field("shallow_size", size)?;
+            obj.field("shallow_size_percent", Self::percent(items, size))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord<'a> {
+            name: &'a str,
+            shallow_size: u32,
+            shallow_size_percent: f64,
+            retained_size: u32,
+            retained_size_percent: f64,
+        }
+
+        for entry in self.crates.iter().take(self.limit) {
+            wtr.serialize(CsvRecord {
+                name: &entry.name,
+                shallow_size: entry.size,
+                shallow_size_percent: Self::percent(items, entry.size),
+                retained_size: entry.retained_size,
+                retained_size_percent: Self::percent(items, entry.retained_size),
+            })?;
+            wtr.flush()?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        // Draw the crates, with an edge from each crate to the crates whose
+        // functions it references.
+        let mut graph = dot::digraph(dest, "crates")?;
+        for (i, entry) in self.crates.iter().enumerate().take(self.limit) {
+            let label = format!(
+                "{}\nshallow: {} bytes ({:.2}%)\nretained: {} bytes ({:.2}%)",
+                entry.name,
+                entry.size,
+                Self::percent(items, entry.size),
+                entry.retained_size,
+                Self::percent(items, entry.retained_size)
+            );
+            graph.node(&format!("crate{}", i), &label)?;
+        }
+
+        for &(from, to) in &self.edges {
+            if from < self.limit && to < self.limit {
+                graph.edge(&format!("crate{}", from), &format!("crate{}", to))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut page = html::page(dest, "twiggy crates")?;
+        let mut table = page.table(&[
+            "Shallow Bytes",
+            "Shallow %",
+            "Retained Bytes",
+            "Retained %",
+            "Crate",
+        ])?;
+
+        for entry in self.crates.iter().take(self.limit) {
+            table.row(&[
+                html::Cell::Bytes(i64::from(entry.size)),
+                html::Cell::Percent(Self::percent(items, entry.size)),
+                html::Cell::Bytes(i64::from(entry.retained_size)),
+                html::Cell::Percent(Self::percent(items, entry.retained_size)),
+                html::Cell::Name(&entry.name),
+            ])?;
+        }
+
+        for (name, size) in self.summaries() {
+            table.summary_row(&[
+                html::Cell::Bytes(i64::from(size)),
+                html::Cell::Percent(Self::percent(items, size)),
+                html::Cell::Text("..."),
+                html::Cell::Text("..."),
+                html::Cell::Text(&name),
+            ])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Group the functions in a binary by the crate that their demangled names
+/// say they belong to, and find the size of each crate.
+pub fn crates(
+    items: &ir::Items,
+    opts: &opt::Crates,
+) -> Result<Box<dyn traits::Emit>, traits::Error> {
+    let mut members: BTreeMap<String, BTreeSet<ir::Id>> = BTreeMap::new();
+    for item in items.iter() {
+        if let ir::ItemKind::Code(ref code) = item.kind() {
+            let name = code.demangled().unwrap_or_else(|| item.name());
+            let krate = crate_name(name).unwrap_or(UNKNOWN);
+            members
+                .entry(krate.to_string())
+                .or_default()
+                .insert(item.id());
+        }
+    }
+
+    // A crate retains everything that would become unreachable if all of its
+    // functions were removed, including things that are only reachable
+    // through several of its functions, unlike any one function's retained
+    // size.
+    let reachable = reachable_without(items, &BTreeSet::new());
+    let mut crates: Vec<_> = members
+        .iter()
+        .map(|(name, ids)| {
+            let size = ids.iter().map(|&id| items[id].size()).sum();
+            let still_reachable = reachable_without(items, ids);
+            let retained_size = reachable
+                .difference(&still_reachable)
+                .map(|&id| items[id].size())
+                .sum();
+            CrateEntry {
+                name: name.clone(),
+                size,
+                retained_size,
+            }
+        })
+        .collect();
+
+    if opts.retained() {
+        crates.sort_by(|a, b| {
+            b.retained_size
+                .cmp(&a.retained_size)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+    } else {
+        crates.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    }
+
+    let mut crate_of: BTreeMap<ir::Id, usize> = BTreeMap::new();
+    for (i, entry) in crates.iter().enumerate() {
+        for &id in &members[&entry.name] {
+            crate_of.insert(id, i);
+        }
+    }
+    let mut edges = BTreeSet::new();
+    for (&id, &from) in &crate_of {
+        for neighbor in items.neighbors(id) {
+            match crate_of.get(&neighbor) {
+                Some(&to) if to != from => {
+                    edges.insert((from, to));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(Box::new(Crates {
+        crates,
+        edges,
+        limit: opts.max_items() as usize,
+    }) as Box<_>)
+}
+
+/// Find the items that are reachable from the roots without going through
+/// any of the given items.
+fn reachable_without(items: &ir::Items, removed: &BTreeSet<ir::Id>) -> BTreeSet<ir::Id> {
+    let graph = petgraph::visit::NodeFiltered::from_fn(items, |id| !removed.contains(&id));
+    petgraph::visit::Dfs::new(&graph, items.meta_root())
+        .iter(&graph)
+        .collect()
+}
+
+/// Get the crate that a demangled Rust function name belongs to, which is the
+/// first segment of its path. For methods of trait implementations, such as
+/// `<alloc::vec::Vec<T> as core::ops::Drop>::drop` or `<impl
+/// core::fmt::Debug for hello::Foo>::fmt`, this is the crate of the type that
+/// implements the trait, or that of the trait if the type is a primitive.
+pub(crate) fn crate_name(name: &str) -> Option<&str> {
+    if !name.starts_with('<') {
+        return path_crate(name);
+    }
+
+    let end = closing_bracket(name)?;
+    let inner = &name[1..end];
+    let (ty, trait_) = match inner.strip_prefix("impl ") {
+        Some(inner) => {
+            let i = find_top_level(inner, " for ")?;
+            (&inner[i + " for ".len()..], &inner[..i])
+        }
+        None => match find_top_level(inner, " as ") {
+            Some(i) => (&inner[..i], &inner[i + " as ".len()..]),
+            None => (inner, ""),
+        },
+    };
+    type_crate(ty).or_else(|| path_crate(trait_))
+}
+
+/// Get the crate of a type, looking through references and pointers.
+fn type_crate(ty: &str) -> Option<&str> {
+    let prefixes = ["&mut ", "&", "*const ", "*mut ", "dyn "];
+    match prefixes.iter().find(|prefix| ty.starts_with(*prefix)) {
+        Some(prefix) => type_crate(&ty[prefix.len()..]),
+        None if ty.starts_with('<') => crate_name(ty),
+        None => path_crate(ty),
+    }
+}
+
+/// Get the first segment of a path, if it has more than one.
+fn path_crate(path: &str) -> Option<&str> {
+    let end = path
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|&end| end > 0)?;
+    if path[end..].starts_with("::") {
+        Some(&path[..end])
+    } else {
+        None
+    }
+}
+
+/// Find the `>` that closes the `<` at the start of the given name. Arrows in
+/// function types, as in `fn() -> T`, don't close anything.
+fn closing_bracket(name: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut previous = None;
+    for (i, c) in name.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if previous != Some('-') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        previous = Some(c);
+    }
+    None
+}
+
+/// Find the given separator in a name, outside of any angle brackets.
+fn find_top_level(name: &str, separator: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut previous = None;
+    for (i, c) in name.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if previous != Some('-') => depth -= 1,
+            _ if depth == 0 && name[i..].starts_with(separator) => return Some(i),
+            _ => {}
+        }
+        previous = Some(c);
+    }
+    None
+}
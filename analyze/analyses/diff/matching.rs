@@ -0,0 +1,304 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use regex::Regex;
+use twiggy_ir as ir;
+
+/// An item in the old version of a binary, and the same item in the new
+/// version. Either is `None` when the item was added or removed.
+#[derive(Debug)]
+pub(crate) struct Match {
+    pub(crate) old: Option<ir::Id>,
+    pub(crate) new: Option<ir::Id>,
+    /// Whether the item's name changed by more than its hashes. Functions
+    /// without names don't count as renamed when only their index changed.
+    pub(crate) renamed: bool,
+}
+
+/// Pair up the items in the old and new versions of a binary that are the same
+/// item, even when its name changed between builds. The meta roots are left
+/// out.
+///
+/// Items are matched in stages, and each stage only looks at the items that
+/// earlier stages couldn't match:
+///
+/// 1. Items with the same name.
+/// 2. Items whose names are the same once Rust symbol hashes, and the suffixes
+///    that LLVM gives duplicated symbols, are removed.
+/// 3. Functions whose names are the same once their generic arguments are
+///    removed as well, as long as they have a similar size and the same number
+///    of outgoing edges. Other monomorphizations of the same function are
+///    different functions.
+/// 4. Functions without names, such as `code[12]`, that are referenced by a
+///    matched pair of items. Their names come from their index, which changes
+///    whenever a function is added or removed before them. When both items of
+///    the pair reference the same number of such functions, similar ones are
+///    paired up in order.
+/// 5. Functions without names with the same size and number of outgoing
+///    edges, that are referenced by a matched item, after which stage 4 is
+///    repeated.
+pub(crate) fn match_items(old_items: &ir::Items, new_items: &ir::Items) -> Vec<Match> {
+    let unmatched = |items: &ir::Items| -> BTreeSet<ir::Id> {
+        items
+            .iter()
+            .map(|item| item.id())
+            .filter(|&id| id != items.meta_root())
+            .collect()
+    };
+
+    let mut matcher = Matcher {
+        old_items,
+        new_items,
+        old_unmatched: unmatched(old_items),
+        new_unmatched: unmatched(new_items),
+        matches: vec![],
+    };
+
+    let hashes = Regex::new(r"::h[0-9a-f]{16}|\[[0-9a-f]{8,16}\]|\.llvm\.\d+|\.\d+$").unwrap();
+    let strip_hashes = |name: &str| hashes.replace_all(name, "").into_owned();
+
+    matcher.pair_by(false, |_, item| {
+        if is_anonymous(item) {
+            None
+        } else {
+            Some(item.name().to_string())
+        }
+    });
+    matcher.pair_by(false, |_, item| {
+        if is_anonymous(item) {
+            None
+        } else {
+            Some(strip_hashes(item.name()))
+        }
+    });
+    matcher.pair_by(true, |_, item| match item.kind() {
+        ir::ItemKind::Code(_) if !is_anonymous(item) => {
+            Some(strip_generics(&strip_hashes(item.name())))
+        }
+        _ => None,
+    });
+    matcher.pair_neighbors();
+    let (old_referenced, new_referenced) = matcher.referenced_by_matches();
+    matcher.pair_among(&old_referenced, &new_referenced, false, |items, item| {
+        if is_anonymous(item) {
+            Some(format!(
+                "{}:{}",
+                item.size(),
+                items.neighbors(item.id()).count()
+            ))
+        } else {
+            None
+        }
+    });
+    matcher.pair_neighbors();
+
+    let Matcher {
+        old_unmatched,
+        new_unmatched,
+        mut matches,
+        ..
+    } = matcher;
+    let removed = old_unmatched.into_iter().map(|id| Match {
+        old: Some(id),
+        new: None,
+        renamed: false,
+    });
+    let added = new_unmatched.into_iter().map(|id| Match {
+        old: None,
+        new: Some(id),
+        renamed: false,
+    });
+    matches.extend(removed.chain(added));
+    matches
+}
+
+struct Matcher<'a> {
+    old_items: &'a ir::Items,
+    new_items: &'a ir::Items,
+    old_unmatched: BTreeSet<ir::Id>,
+    new_unmatched: BTreeSet<ir::Id>,
+    matches: Vec<Match>,
+}
+
+impl<'a> Matcher<'a> {
+    /// Pair up the unmatched items that have the same key. Several items can
+    /// have the same key, such as the monomorphizations of a generic function
+    /// once their generic arguments are removed. Those with the same size are
+    /// paired first, and then the rest in order of size. `renamed` is whether
+    /// the items' names may differ by more than their hashes, in which case
+    /// only similar items are paired.
+    fn pair_by<F>(&mut self, renamed: bool, key: F)
+    where
+        F: Fn(&ir::Items, &ir::Item) -> Option<String>,
+    {
+        let olds = self.old_unmatched.clone();
+        let news = self.new_unmatched.clone();
+        self.pair_among(&olds, &news, renamed, key);
+    }
+
+    /// Like `pair_by`, but only pair up the given unmatched old and new items.
+    fn pair_among<F>(
+        &mut self,
+        olds: &BTreeSet<ir::Id>,
+        news: &BTreeSet<ir::Id>,
+        renamed: bool,
+        key: F,
+    ) where
+        F: Fn(&ir::Items, &ir::Item) -> Option<String>,
+    {
+        let mut groups: BTreeMap<String, (Vec<ir::Id>, Vec<ir::Id>)> = BTreeMap::new();
+        for &id in olds {
+            if let Some(key) = key(self.old_items, &self.old_items[id]) {
+                groups.entry(key).or_default().0.push(id);
+            }
+        }
+        for &id in news {
+            if let Some(key) = key(self.new_items, &self.new_items[id]) {
+                groups.entry(key).or_default().1.push(id);
+            }
+        }
+
+        for (_, (mut olds, mut news)) in groups {
+            if olds.is_empty() || news.is_empty() {
+                continue;
+            }
+
+            olds.sort_by_key(|&id| (self.old_items[id].size(), id));
+            news.sort_by_key(|&id| (self.new_items[id].size(), id));
+
+            let mut same_size = vec![];
+            let (mut i, mut j) = (0, 0);
+            while i < olds.len() && j < news.len() {
+                let old_size = self.old_items[olds[i]].size();
+                let new_size = self.new_items[news[j]].size();
+                if old_size < new_size {
+                    i += 1;
+                } else if new_size < old_size {
+                    j += 1;
+                } else {
+                    same_size.push((olds.remove(i), news.remove(j)));
+                }
+            }
+
+            for (old, new) in same_size.into_iter().chain(olds.into_iter().zip(news)) {
+                if !renamed || self.similar(old, new) {
+                    self.pair(old, new, renamed);
+                }
+            }
+        }
+    }
+
+    /// Pair up the unmatched functions without names that are referenced by a
+    /// matched pair of items, until there are no more to pair up. When both
+    /// items reference the same number of them, they are paired in the order
+    /// of their indices, which functions rarely change, as long as they are
+    /// similar.
+    fn pair_neighbors(&mut self) {
+        loop {
+            let unmatched_neighbors = |items: &ir::Items, unmatched: &BTreeSet<ir::Id>, id| {
+                items
+                    .neighbors(id)
+                    .filter(|neighbor| unmatched.contains(neighbor))
+                    .filter(|&neighbor| is_anonymous(&items[neighbor]))
+                    .collect::<Vec<_>>()
+            };
+
+            let mut found = vec![];
+            for m in &self.matches {
+                if let (Some(old), Some(new)) = (m.old, m.new) {
+                    let olds = unmatched_neighbors(self.old_items, &self.old_unmatched, old);
+                    let news = unmatched_neighbors(self.new_items, &self.new_unmatched, new);
+                    if olds.len() == news.len() {
+                        found.extend(
+                            olds.into_iter()
+                                .zip(news)
+                                .filter(|&(old, new)| self.similar(old, new)),
+                        );
+                    }
+                }
+            }
+            if found.is_empty() {
+                return;
+            }
+
+            for (old, new) in found {
+                if self.old_unmatched.contains(&old) && self.new_unmatched.contains(&new) {
+                    self.pair(old, new, false);
+                }
+            }
+        }
+    }
+
+    /// Get the unmatched items in the old and new versions of the binary that
+    /// are referenced by a matched item.
+    fn referenced_by_matches(&self) -> (BTreeSet<ir::Id>, BTreeSet<ir::Id>) {
+        let mut old_referenced = BTreeSet::new();
+        let mut new_referenced = BTreeSet::new();
+        for m in &self.matches {
+            if let (Some(old), Some(new)) = (m.old, m.new) {
+                old_referenced.extend(
+                    self.old_items
+                        .neighbors(old)
+                        .filter(|id| self.old_unmatched.contains(id)),
+                );
+                new_referenced.extend(
+                    self.new_items
+                        .neighbors(new)
+                        .filter(|id| self.new_unmatched.contains(id)),
+                );
+            }
+        }
+        (old_referenced, new_referenced)
+    }
+
+    /// Are these items similar enough to be the same item, even though their
+    /// names don't say so? They must reference the same number of items, and
+    /// their sizes may differ by at most a fifth.
+    fn similar(&self, old: ir::Id, new: ir::Id) -> bool {
+        let old_size = self.old_items[old].size();
+        let new_size = self.new_items[new].size();
+        let difference = old_size.max(new_size) - old_size.min(new_size);
+        self.old_items.neighbors(old).count() == self.new_items.neighbors(new).count()
+            && difference * 5 <= old_size.max(new_size)
+    }
+
+    fn pair(&mut self, old: ir::Id, new: ir::Id, renamed: bool) {
+        self.old_unmatched.remove(&old);
+        self.new_unmatched.remove(&new);
+        self.matches.push(Match {
+            old: Some(old),
+            new: Some(new),
+            renamed: renamed && self.old_items[old].name() != self.new_items[new].name(),
+        });
+    }
+}
+
+/// Is this a function without a name, which is named after its index instead?
+fn is_anonymous(item: &ir::Item) -> bool {
+    let name = item.name();
+    (name.starts_with("code[") || name.starts_with("func["))
+        && name.ends_with(']')
+        && name[5..name.len() - 1].chars().all(|c| c.is_ascii_digit())
+}
+
+/// Remove the generic arguments from a function's name, like the `<u8>` in
+/// `alloc::vec::Vec<u8>::push`. Angle brackets that don't follow a name, like
+/// those around `<T as Trait>`, are kept, and so are the arrows in function
+/// types.
+fn strip_generics(name: &str) -> String {
+    let mut stripped = String::with_capacity(name.len());
+    let mut depth = 0;
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        match c {
+            '<' if depth > 0 => depth += 1,
+            '<' if matches!(previous, Some(p) if p.is_alphanumeric() || p == '_') => {
+                depth = 1;
+            }
+            '>' if depth > 0 && previous != Some('-') => depth -= 1,
+            _ if depth == 0 => stripped.push(c),
+            _ => {}
+        }
+        previous = Some(c);
+    }
+    stripped
+}
@@ -0,0 +1,489 @@
+use std::cmp;
+use std::collections::{HashMap, HashSet};
+use std::io;
+
+use csv;
+use regex;
+use serde_derive::Serialize;
+
+use crate::formats::dot;
+use crate::formats::html;
+use crate::formats::json;
+use crate::formats::table::{Align, Table};
+use twiggy_ir as ir;
+use twiggy_opt as opt;
+use twiggy_traits as traits;
+
+pub(crate) mod matching;
+
+#[derive(Debug)]
+struct Diff {
+    deltas: Vec<DiffEntry>,
+    /// The number of entries at the end of `deltas` that summarize the
+    /// remaining and total rows, rather than describe a single item.
+    summary_rows: usize,
+    /// Whether the deltas are of retained sizes, rather than shallow sizes.
+    retained: bool,
+}
+
+/// How an item changed between the old and new versions of a binary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Status {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Status {
+    fn as_str(self) -> &'static str {
+        match self {
+            Status::Added => "added",
+            Status::Removed => "removed",
+            Status::Changed => "changed",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DiffEntry {
+    name: String,
+    /// The item's name in the old version of the binary, when it was renamed.
+    old_name: Option<String>,
+    /// How the item changed. This is `None` for summary rows.
+    status: Option<Status>,
+    /// The sizes in the old and new versions of the binary. Like `delta`, these
+    /// are `None` for a summary of retained sizes. An added item's old size
+    /// and a removed item's new size are zero.
+    old_size: Option<i64>,
+    new_size: Option<i64>,
+    /// The change in size. This is `None` for a summary of retained sizes,
+    /// which overlap one another and can't be summed up.
+    delta: Option<i64>,
+    /// The names of the item's old and new immediate dominators, when diffing
+    /// retained sizes and its immediate dominator changed.
+    dominators: Option<(String, String)>,
+}
+
+impl DiffEntry {
+    /// Create a summary row for the given old and new sizes.
+    fn summary(name: String, sizes: Option<(i64, i64)>) -> DiffEntry {
+        DiffEntry {
+            name,
+            old_name: None,
+            status: None,
+            old_size: sizes.map(|(old, _)| old),
+            new_size: sizes.map(|(_, new)| new),
+            delta: sizes.map(|(old, new)| new - old),
+            dominators: None,
+        }
+    }
+
+    fn delta_string(&self) -> String {
+        match self.delta {
+            Some(delta) => format!("{:+}", delta),
+            None => "...".to_string(),
+        }
+    }
+
+    /// Notes about how the item changed, other than its size.
+    fn notes(&self) -> Vec<String> {
+        let renamed = self
+            .old_name
+            .as_ref()
+            .map(|old| format!("renamed from: {}", old));
+        let dominators = self
+            .dominators
+            .as_ref()
+            .map(|(old, new)| format!("immediate dominator: {} → {}", old, new));
+        renamed.into_iter().chain(dominators).collect()
+    }
+}
+
+/// Format an old or new size, which is `None` for a summary of retained sizes.
+#[cfg(feature = "emit_text")]
+fn size_string(size: Option<i64>) -> String {
+    size.map_or_else(|| "...".to_string(), |size| size.to_string())
+}
+
+impl PartialOrd for DiffEntry {
+    fn partial_cmp(&self, rhs: &DiffEntry) -> Option<cmp::Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+
+impl Ord for DiffEntry {
+    fn cmp(&self, rhs: &DiffEntry) -> cmp::Ordering {
+        let abs = |entry: &DiffEntry| entry.delta.map_or(0, i64::abs);
+        abs(rhs).cmp(&abs(self)).then(self.name.cmp(&rhs.name))
+    }
+}
+
+impl traits::Emit for Diff {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let bytes = if self.retained {
+            "Retained Bytes"
+        } else {
+            "Bytes"
+        };
+        let mut table = Table::with_header(vec![
+            (Align::Right, format!("Delta {}", bytes)),
+            (Align::Right, format!("Old {}", bytes)),
+            (Align::Right, format!("New {}", bytes)),
+            (Align::Left, "Status".to_string()),
+            (Align::Left, "Item".to_string()),
+        ]);
+
+        for entry in &self.deltas {
+            table.add_row(vec![
+                entry.delta_string(),
+                size_string(entry.old_size),
+                size_string(entry.new_size),
+                entry.status.map_or("", Status::as_str).to_string(),
+                entry.name.clone(),
+            ]);
+            for note in entry.notes() {
+                table.add_row(vec![
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    format!("    {}", note),
+                ]);
+            }
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut arr = json::array(dest)?;
+
+        for entry in &self.deltas {
+            let mut obj = arr.object()?;
+            if let Some(delta) = entry.delta {
+                obj.field("delta_bytes", delta as f64)?;
+            }
+            if let (Some(old_size), Some(new_size)) = (entry.old_size, entry.new_size) {
+                obj.field("old_bytes", old_size as f64)?;
+                obj.field("new_bytes", new_size as f64)?;
+            }
+            if let Some(status) = entry.status {
+                obj.field("status", status.as_str())?;
+            }
+            obj.field("name", entry.name.as_str())?;
+            if let Some(ref old_name) = entry.old_name {
+                obj.field("old_name", old_name.as_str())?;
+            }
+            if let Some((ref old, ref new)) = entry.dominators {
+                obj.field("old_immediate_dominator", old.as_str())?;
+                obj.field("new_immediate_dominator", new.as_str())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord<'a> {
+            delta_bytes: String,
+            item: &'a str,
+            old_item: &'a str,
+            status: &'a str,
+            old_bytes: Option<i64>,
+            new_bytes: Option<i64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            old_immediate_dominator: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            new_immediate_dominator: Option<&'a str>,
+        }
+
+        for entry in &self.deltas {
+            // Every record needs the same columns, so these are only left out
+            // when not diffing retained sizes.
+            let (old, new) = match entry.dominators {
+                Some((ref old, ref new)) => (Some(old.as_str()), Some(new.as_str())),
+                None if self.retained => (Some(""), Some("")),
+                None => (None, None),
+            };
+            wtr.serialize(CsvRecord {
+                delta_bytes: entry.delta_string(),
+                item: &entry.name,
+                old_item: entry.old_name.as_ref().map_or("", |name| name.as_str()),
+                status: entry.status.map_or("", Status::as_str),
+                old_bytes: entry.old_size,
+                new_bytes: entry.new_size,
+                old_immediate_dominator: old,
+                new_immediate_dominator: new,
+            })?;
+            wtr.flush()?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut graph = dot::digraph(dest, "diff")?;
+        for (i, entry) in self.deltas.iter().enumerate() {
+            let mut label = format!("{}\ndelta: {} bytes", entry.name, entry.delta_string());
+            for note in entry.notes() {
+                label.push('\n');
+                label.push_str(&note);
+            }
+            graph.node(&format!("entry{}", i), &label)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut page = html::page(dest, "twiggy diff")?;
+        let bytes = if self.retained {
+            "Retained Bytes"
+        } else {
+            "Bytes"
+        };
+        let (delta, old, new) = (
+            format!("Delta {}", bytes),
+            format!("Old {}", bytes),
+            format!("New {}", bytes),
+        );
+        let mut table = page.table(&[&delta, &old, &new, "Status", "Item"])?;
+
+        let size_cell = |size: Option<i64>| size.map_or(html::Cell::Unknown, html::Cell::Bytes);
+        let (entries, summaries) = self.deltas.split_at(self.deltas.len() - self.summary_rows);
+        for entry in entries {
+            table.row(&[
+                html::Cell::Delta(entry.delta.unwrap_or(0)),
+                size_cell(entry.old_size),
+                size_cell(entry.new_size),
+                html::Cell::Text(entry.status.map_or("", Status::as_str)),
+                html::Cell::Name(&entry.name),
+            ])?;
+            for note in entry.notes() {
+                table.nested_row(&[
+                    html::Cell::Text(""),
+                    html::Cell::Text(""),
+                    html::Cell::Text(""),
+                    html::Cell::Text(""),
+                    html::Cell::Text(&note),
+                ])?;
+            }
+        }
+        for entry in summaries {
+            let delta = match entry.delta {
+                Some(delta) => html::Cell::Delta(delta),
+                None => html::Cell::Text("..."),
+            };
+            table.summary_row(&[
+                delta,
+                size_cell(entry.old_size),
+                size_cell(entry.new_size),
+                html::Cell::Text(""),
+                html::Cell::Text(&entry.name),
+            ])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compute the diff between two sets of items. Items are matched up by name,
+/// allowing for names that changed between builds, such as when their hashes
+/// changed.
+pub fn diff(
+    old_items: &mut ir::Items,
+    new_items: &mut ir::Items,
+    opts: &opt::Diff,
+) -> Result<Box<dyn traits::Emit>, traits::Error> {
+    let max_items = opts.max_items() as usize;
+    let retained = opts.retained();
+
+    if retained {
+        old_items.compute_retained_sizes();
+        old_items.compute_dominators();
+        new_items.compute_retained_sizes();
+        new_items.compute_dominators();
+    }
+
+    let size = |items: &ir::Items, id: Option<ir::Id>| -> i64 {
+        match id {
+            Some(id) if retained => i64::from(items.retained_size(id)),
+            Some(id) => i64::from(items[id].size()),
+            None => 0,
+        }
+    };
+
+    let matches = matching::match_items(old_items, new_items);
+
+    // Map each old item to the new item that it matched with, so that we can
+    // tell whether an item's immediate dominator is still the same item.
+    let mut old_to_new: HashMap<ir::Id, ir::Id> = matches
+        .iter()
+        .filter_map(|m| Some((m.old?, m.new?)))
+        .collect();
+    old_to_new.insert(old_items.meta_root(), new_items.meta_root());
+
+    // Given a matched old and new item, get the names of their immediate
+    // dominators if they aren't the same item. A new edge that makes a large
+    // subtree reachable, or keeps it reachable in a new way, shows up here
+    // even when the items in the subtree didn't change size.
+    let get_dominators = |old: ir::Id, new: ir::Id| -> Option<(String, String)> {
+        let old_idom = old_items.immediate_dominators().get(&old).cloned();
+        let new_idom = new_items.immediate_dominators().get(&new).cloned();
+        if old_idom.and_then(|idom| old_to_new.get(&idom).cloned()) == new_idom
+            && old_idom.is_some() == new_idom.is_some()
+        {
+            return None;
+        }
+        let name = |items: &ir::Items, idom: Option<ir::Id>| {
+            idom.map_or("<unreachable>", |idom| items[idom].name())
+                .to_string()
+        };
+        Some((name(old_items, old_idom), name(new_items, new_idom)))
+    };
+
+    // If arguments were given to the command, we should filter out items that
+    // do not match any of the given names or expressions, under either their
+    // old or new name.
+    let regexps = if opts.using_regexps() {
+        Some(regex::RegexSet::new(opts.items())?)
+    } else {
+        None
+    };
+    let item_names = opts
+        .items()
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<HashSet<_>>();
+    let is_selected = |name: &str| match regexps {
+        _ if opts.items().is_empty() => true,
+        Some(ref regexps) => regexps.is_match(name),
+        None => item_names.contains(name),
+    };
+
+    // The total shallow sizes of each kind of item, in the old and new
+    // versions of the binary.
+    let mut kinds = [
+        ("code", 0, 0),
+        ("data", 0, 0),
+        ("debug", 0, 0),
+        ("misc", 0, 0),
+    ];
+    let kind_index = |item: &ir::Item| match item.kind() {
+        ir::ItemKind::Code(_) => 0,
+        ir::ItemKind::Data(_) => 1,
+        ir::ItemKind::Debug(_) => 2,
+        ir::ItemKind::Misc(_) => 3,
+    };
+
+    let mut deltas = vec![];
+    for m in &matches {
+        let old_name = m.old.map(|id| old_items[id].name());
+        let new_name = m.new.map(|id| new_items[id].name());
+        if !old_name.into_iter().chain(new_name).any(&is_selected) {
+            continue;
+        }
+
+        if let Some(old) = m.old {
+            kinds[kind_index(&old_items[old])].1 += i64::from(old_items[old].size());
+        }
+        if let Some(new) = m.new {
+            kinds[kind_index(&new_items[new])].2 += i64::from(new_items[new].size());
+        }
+
+        let old_size = size(old_items, m.old);
+        let new_size = size(new_items, m.new);
+        let delta = new_size - old_size;
+        let dominators = match (m.old, m.new) {
+            (Some(old), Some(new)) if retained => get_dominators(old, new),
+            _ => None,
+        };
+        if delta == 0 && dominators.is_none() && !m.renamed {
+            continue;
+        }
+
+        let status = match (m.old, m.new) {
+            (None, _) => Status::Added,
+            (_, None) => Status::Removed,
+            _ => Status::Changed,
+        };
+        deltas.push(DiffEntry {
+            name: new_name.or(old_name).unwrap().to_string(),
+            old_name: if m.renamed {
+                old_name.map(|name| name.to_string())
+            } else {
+                None
+            },
+            status: Some(status),
+            old_size: Some(old_size),
+            new_size: Some(new_size),
+            delta: Some(delta),
+            dominators,
+        });
+    }
+    deltas.sort();
+
+    // Create an entry to summarize the diff rows that will be truncated.
+    // Retained sizes overlap one another, so they can't be summed up.
+    let sum = |entries: &[DiffEntry]| -> Option<(i64, i64)> {
+        if retained {
+            None
+        } else {
+            let old = entries.iter().filter_map(|entry| entry.old_size).sum();
+            let new = entries.iter().filter_map(|entry| entry.new_size).sum();
+            Some((old, new))
+        }
+    };
+    let rem_cnt = deltas.len().saturating_sub(max_items);
+    let remaining = DiffEntry::summary(
+        format!("... and {} more.", rem_cnt),
+        sum(&deltas[deltas.len() - rem_cnt..]),
+    );
+
+    // Create a `DiffEntry` representing the net change, and total row count.
+    // If specifying arguments were not given, calculate the total net changes,
+    // otherwise find the total values only for items in the the deltas collection.
+    let total_sizes = if opts.items().is_empty() {
+        Some((i64::from(old_items.size()), i64::from(new_items.size())))
+    } else {
+        sum(&deltas)
+    };
+    let total = DiffEntry::summary(format!("Σ [{} Total Rows]", deltas.len()), total_sizes);
+
+    // Now that the 'remaining' and 'total' summary entries have been created,
+    // truncate the vector of deltas before we box up the result, and push
+    // the remaining, per-kind, and total rows to the deltas vector. The
+    // per-kind rows sum up shallow sizes, so they are left out when diffing
+    // retained sizes.
+    deltas.truncate(max_items);
+    let mut summary_rows = 1;
+    if rem_cnt > 0 {
+        deltas.push(remaining);
+        summary_rows += 1;
+    }
+    if !retained {
+        for &(kind, old, new) in &kinds {
+            deltas.push(DiffEntry::summary(format!("Σ {}", kind), Some((old, new))));
+            summary_rows += 1;
+        }
+    }
+    deltas.push(total);
+
+    // Return the results so that they can be emitted.
+    let diff = Diff {
+        deltas,
+        summary_rows,
+        retained,
+    };
+    Ok(Box::new(diff) as Box<_>)
+}
@@ -1,3 +1,4 @@
+use std::cmp;
 use std::collections::BTreeMap;
 use std::io;
 
@@ -10,7 +11,10 @@ use twiggy_traits as traits;
 
 use super::UnreachableItemsSummary;
 use crate::analyses::dominators::DominatorTree;
+use crate::formats::dot;
+use crate::formats::html;
 use crate::formats::json;
+use crate::formats::svg;
 use crate::formats::table::{Align, Table};
 
 impl traits::Emit for DominatorTree {
@@ -144,6 +148,299 @@ impl traits::Emit for DominatorTree {
         Ok(())
     }
 
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        fn recursive_add_nodes(
+            graph: &mut dot::Graph,
+            items: &ir::Items,
+            dominator_tree: &BTreeMap<ir::Id, Vec<ir::Id>>,
+            depth: u32,
+            row: &mut u32,
+            opts: &opt::Dominators,
+            id: ir::Id,
+        ) -> Result<(), traits::Error> {
+            if *row > opts.max_rows() || depth > opts.max_depth() {
+                return Ok(());
+            }
+
+            // The meta root is not a real item, so leave it out of the graph
+            // and let its children be the roots of the drawn tree.
+            if id != items.meta_root() {
+                graph.node(&dot::item_node(id), &dot::item_label(items, id, true))?;
+            }
+
+            if let Some(children) = dominator_tree.get(&id) {
+                let mut children = children.to_vec();
+                children.sort_by_key(|&id| cmp::Reverse(items.retained_size(id)));
+                for child in children {
+                    *row += 1;
+                    if *row > opts.max_rows() || depth + 1 > opts.max_depth() {
+                        break;
+                    }
+                    if id != items.meta_root() {
+                        graph.edge(&dot::item_node(id), &dot::item_node(child))?;
+                    }
+                    recursive_add_nodes(graph, items, dominator_tree, depth + 1, row, opts, child)?;
+                }
+            }
+
+            Ok(())
+        }
+
+        let mut graph = dot::digraph(dest, "dominators")?;
+        let mut row = 0;
+        for id in &self.items {
+            let start_depth = if *id == items.meta_root() { 0 } else { 1 };
+            recursive_add_nodes(
+                &mut graph,
+                items,
+                &self.tree,
+                start_depth,
+                &mut row,
+                &self.opts,
+                *id,
+            )?;
+        }
+
+        if let Some(UnreachableItemsSummary {
+            count,
+            size,
+            size_percent,
+        }) = self.unreachable_items_summary
+        {
+            let label = format!(
+                "[{} Unreachable Items]\nretained: {} bytes ({:.2}%)",
+                count, size, size_percent
+            );
+            graph.node("unreachable", &label)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        fn recursive_add_nodes(
+            tree: &mut html::Tree,
+            items: &ir::Items,
+            dominator_tree: &BTreeMap<ir::Id, Vec<ir::Id>>,
+            depth: u32,
+            row: &mut u32,
+            opts: &opt::Dominators,
+            id: ir::Id,
+        ) -> Result<(), traits::Error> {
+            if *row > opts.max_rows() || depth > opts.max_depth() {
+                return Ok(());
+            }
+
+            let mut children = dominator_tree.get(&id).cloned().unwrap_or_default();
+            children.sort_by_key(|&id| cmp::Reverse(items.retained_size(id)));
+
+            // The meta root is not a real item, so leave it out of the tree
+            // and let its children be the roots of the drawn tree.
+            let is_root = id == items.meta_root();
+            if !is_root {
+                let size = items.retained_size(id);
+                let size_percent = f64::from(size) / f64::from(items.size()) * 100.0;
+                let cells = [
+                    html::Cell::Bytes(i64::from(size)),
+                    html::Cell::Percent(size_percent),
+                    html::Cell::Name(items[id].name()),
+                ];
+                if children.is_empty() || depth == opts.max_depth() {
+                    return Ok(tree.leaf(&cells)?);
+                }
+                tree.begin(&cells)?;
+            }
+
+            for child in children {
+                *row += 1;
+                recursive_add_nodes(tree, items, dominator_tree, depth + 1, row, opts, child)?;
+            }
+
+            if !is_root {
+                tree.end()?;
+            }
+            Ok(())
+        }
+
+        let mut page = html::page(dest, "twiggy dominators")?;
+        let mut tree = page.tree(&["Retained Bytes", "Retained %", "Dominator Tree"])?;
+        let mut row = 0;
+        for id in &self.items {
+            let start_depth = if *id == items.meta_root() { 0 } else { 1 };
+            recursive_add_nodes(
+                &mut tree,
+                items,
+                &self.tree,
+                start_depth,
+                &mut row,
+                &self.opts,
+                *id,
+            )?;
+        }
+
+        if let Some(UnreachableItemsSummary {
+            count,
+            size,
+            size_percent,
+        }) = self.unreachable_items_summary
+        {
+            tree.leaf(&[
+                html::Cell::Bytes(i64::from(size)),
+                html::Cell::Percent(size_percent),
+                html::Cell::Text(&format!("[{} Unreachable Items]", count)),
+            ])?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_svg")]
+    fn emit_svg(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        // Draw the dominator tree as an icicle chart: every item is a box
+        // directly beneath its immediate dominator, and its width is
+        // proportional to its retained size.
+        const WIDTH: f64 = 1200.0;
+        const ROW_HEIGHT: f64 = 20.0;
+        const MARGIN: f64 = 10.0;
+        const HEADER_HEIGHT: f64 = 30.0;
+        // Frames narrower than this are not visible, so skip them, and their
+        // children, entirely.
+        const MIN_WIDTH: f64 = 0.5;
+
+        struct Frame {
+            depth: u32,
+            x: f64,
+            width: f64,
+            id: ir::Id,
+        }
+
+        struct Layout<'a> {
+            items: &'a ir::Items,
+            dominator_tree: &'a BTreeMap<ir::Id, Vec<ir::Id>>,
+            opts: &'a opt::Dominators,
+            scale: f64,
+            row: u32,
+            frames: Vec<Frame>,
+        }
+
+        impl<'a> Layout<'a> {
+            fn add(&mut self, id: ir::Id, depth: u32, x: f64) {
+                let items = self.items;
+                let width = f64::from(items.retained_size(id)) * self.scale;
+                if self.row > self.opts.max_rows()
+                    || depth > self.opts.max_depth()
+                    || width < MIN_WIDTH
+                {
+                    return;
+                }
+
+                // The meta root is not a real item, so leave it out of the
+                // chart and let its children be the top row.
+                if id != items.meta_root() {
+                    self.frames.push(Frame {
+                        depth,
+                        x,
+                        width,
+                        id,
+                    });
+                }
+
+                if let Some(children) = self.dominator_tree.get(&id) {
+                    let mut children = children.to_vec();
+                    children.sort_by_key(|&id| cmp::Reverse(items.retained_size(id)));
+                    let mut x = x;
+                    for child in children {
+                        self.row += 1;
+                        self.add(child, depth + 1, x);
+                        x += f64::from(items.retained_size(child)) * self.scale;
+                    }
+                }
+            }
+        }
+
+        let unreachable_size = self
+            .unreachable_items_summary
+            .as_ref()
+            .map_or(0, |summary| summary.size);
+        let total_size: u32 = self
+            .items
+            .iter()
+            .map(|&id| items.retained_size(id))
+            .sum::<u32>()
+            + unreachable_size;
+        let scale = (WIDTH - 2.0 * MARGIN) / f64::from(total_size.max(1));
+
+        let mut layout = Layout {
+            items,
+            dominator_tree: &self.tree,
+            opts: &self.opts,
+            scale,
+            row: 0,
+            frames: vec![],
+        };
+        let mut x = MARGIN;
+        for &id in &self.items {
+            let start_depth = if id == items.meta_root() { 0 } else { 1 };
+            layout.add(id, start_depth, x);
+            x += f64::from(items.retained_size(id)) * scale;
+        }
+
+        let rows = layout.frames.iter().map(|f| f.depth).max().unwrap_or(1);
+        let height = HEADER_HEIGHT + f64::from(rows) * ROW_HEIGHT + MARGIN;
+        let percent = |size: u32| f64::from(size) / f64::from(items.size()) * 100.0;
+
+        let mut doc = svg::document(dest, "twiggy dominators", WIDTH, height)?;
+        doc.text(
+            MARGIN,
+            HEADER_HEIGHT - MARGIN,
+            &format!("Dominator tree, {} bytes retained", total_size),
+        )?;
+        for Frame {
+            depth,
+            x,
+            width,
+            id,
+        } in layout.frames
+        {
+            let item = &items[id];
+            let size = items.retained_size(id);
+            let rect = svg::Rect {
+                x,
+                y: HEADER_HEIGHT + f64::from(depth - 1) * ROW_HEIGHT,
+                width,
+                height: ROW_HEIGHT,
+            };
+            let tooltip = format!(
+                "{}\nretained: {} bytes ({:.2}%)",
+                item.name(),
+                size,
+                percent(size)
+            );
+            doc.labeled_rect(rect, &svg::warm_color(item.name()), item.name(), &tooltip)?;
+        }
+
+        if let Some(UnreachableItemsSummary {
+            count,
+            size,
+            size_percent,
+        }) = self.unreachable_items_summary
+        {
+            let name = format!("[{} Unreachable Items]", count);
+            let rect = svg::Rect {
+                x,
+                y: HEADER_HEIGHT,
+                width: f64::from(size) * scale,
+                height: ROW_HEIGHT,
+            };
+            let tooltip = format!("{}\nretained: {} bytes ({:.2}%)", name, size, size_percent);
+            doc.labeled_rect(rect, "rgb(200,200,200)", &name, &tooltip)?;
+        }
+
+        Ok(())
+    }
+
     #[cfg(feature = "emit_csv")]
     fn emit_csv(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
         fn recursive_add_children(
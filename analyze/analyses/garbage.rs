@@ -1,8 +1,10 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io;
 
 use petgraph::visit::Walker;
 
+use crate::formats::dot;
+use crate::formats::html;
 use crate::formats::json;
 use crate::formats::table::{Align, Table};
 use twiggy_ir as ir;
@@ -140,6 +142,114 @@ impl traits::Emit for Garbage {
     fn emit_csv(&self, _items: &ir::Items, _dest: &mut dyn io::Write) -> Result<(), traits::Error> {
         unimplemented!();
     }
+
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let shown: BTreeSet<ir::Id> = self.items.iter().take(self.limit).cloned().collect();
+
+        // Group the garbage items into clusters of items that reference each
+        // other, so that each cluster can be removed as a whole. Every item
+        // starts out in its own cluster, and clusters are merged whenever
+        // there is an edge between two of their items.
+        let mut cluster_of: BTreeMap<ir::Id, ir::Id> = shown.iter().map(|&id| (id, id)).collect();
+        fn find(cluster_of: &mut BTreeMap<ir::Id, ir::Id>, id: ir::Id) -> ir::Id {
+            let parent = cluster_of[&id];
+            if parent == id {
+                return id;
+            }
+            let root = find(cluster_of, parent);
+            cluster_of.insert(id, root);
+            root
+        }
+        for &from in &shown {
+            for to in items.neighbors(from).filter(|to| shown.contains(to)) {
+                let (a, b) = (find(&mut cluster_of, from), find(&mut cluster_of, to));
+                cluster_of.insert(a, b);
+            }
+        }
+
+        let mut clusters: BTreeMap<ir::Id, Vec<ir::Id>> = BTreeMap::new();
+        for &id in &shown {
+            let root = find(&mut cluster_of, id);
+            clusters.entry(root).or_default().push(id);
+        }
+
+        let mut graph = dot::digraph(dest, "garbage")?;
+        for (i, members) in clusters.values().enumerate() {
+            let size: u32 = members.iter().map(|&id| items[id].size()).sum();
+            let label = format!("{} bytes", size);
+            let mut cluster = graph.subgraph(&format!("cluster_{}", i), &label)?;
+            for &id in members {
+                cluster.node(&dot::item_node(id), &dot::item_label(items, id, false))?;
+            }
+        }
+
+        for &from in &shown {
+            for to in items.neighbors(from).filter(|to| shown.contains(to)) {
+                graph.edge(&dot::item_node(from), &dot::item_node(to))?;
+            }
+        }
+
+        if !self.data_segments.is_empty() {
+            let size: u32 = self.data_segments.iter().map(|&id| items[id].size()).sum();
+            let size_percent = f64::from(size) / f64::from(items.size()) * 100.0;
+            let label = format!(
+                "{} potential false-positive data segments\nshallow: {} bytes ({:.2}%)",
+                self.data_segments.len(),
+                size,
+                size_percent
+            );
+            graph.node("data_segments", &label)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let percent = |size: u32| f64::from(size) / f64::from(items.size()) * 100.0;
+        let total_size = |ids: &[ir::Id]| ids.iter().map(|&id| items[id].size()).sum::<u32>();
+
+        let mut page = html::page(dest, "twiggy garbage")?;
+        let mut table = page.table(&["Bytes", "Size %", "Garbage Item"])?;
+
+        for &id in self.items.iter().take(self.limit) {
+            let item = &items[id];
+            table.row(&[
+                html::Cell::Bytes(i64::from(item.size())),
+                html::Cell::Percent(percent(item.size())),
+                html::Cell::Name(item.name()),
+            ])?;
+        }
+
+        let mut summary_row = |ids: &[ir::Id], name: &str| {
+            let size = total_size(ids);
+            table.summary_row(&[
+                html::Cell::Bytes(i64::from(size)),
+                html::Cell::Percent(percent(size)),
+                html::Cell::Text(name),
+            ])
+        };
+
+        if self.items.len() > self.limit {
+            let remaining = &self.items[self.limit..];
+            summary_row(remaining, &format!("... and {} more", remaining.len()))?;
+        }
+
+        summary_row(&self.items, &format!("Σ [{} Total Rows]", self.items.len()))?;
+
+        if !self.data_segments.is_empty() {
+            summary_row(
+                &self.data_segments,
+                &format!(
+                    "{} potential false-positive data segments",
+                    self.data_segments.len()
+                ),
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 /// Find items that are not transitively referenced by any exports or public functions.
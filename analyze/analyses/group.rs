@@ -0,0 +1,250 @@
+use std::collections::BTreeMap;
+use std::io;
+
+use serde_derive::Serialize;
+
+use crate::formats::html;
+use crate::formats::json;
+use crate::formats::table::{Align, Table};
+use twiggy_ir as ir;
+use twiggy_opt as opt;
+use twiggy_traits as traits;
+
+/// The name of the group for items that don't match any rule.
+const OTHER: &str = "<other>";
+
+#[derive(Debug)]
+struct Groups {
+    groups: Vec<GroupEntry>,
+    limit: usize,
+}
+
+#[derive(Debug)]
+struct GroupEntry {
+    name: String,
+    size: u32,
+    count: u32,
+}
+
+impl Groups {
+    fn percent(items: &ir::Items, size: u32) -> f64 {
+        f64::from(size) / f64::from(items.size()) * 100.0
+    }
+
+    /// The summary rows: the groups that didn't fit within the limit, if any,
+    /// and the total.
+    fn summaries(&self) -> Vec<GroupEntry> {
+        let summarize = |name: String, groups: &[GroupEntry]| GroupEntry {
+            name,
+            size: groups.iter().map(|group| group.size).sum(),
+            count: groups.iter().map(|group| group.count).sum(),
+        };
+
+        let mut summaries = vec![];
+        if self.groups.len() > self.limit {
+            let remaining = &self.groups[self.limit..];
+            let name = format!("... and {} more.", remaining.len());
+            summaries.push(summarize(name, remaining));
+        }
+        let name = format!("Σ [{} Total Rows]", self.groups.len());
+        summaries.push(summarize(name, &self.groups));
+        summaries
+    }
+}
+
+impl traits::Emit for Groups {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Bytes".to_string()),
+            (Align::Right, "Size %".to_string()),
+            (Align::Right, "Items".to_string()),
+            (Align::Left, "Group".to_string()),
+        ]);
+
+        let shown = self.groups.iter().take(self.limit);
+        for group in shown.chain(self.summaries().iter()) {
+            table.add_row(vec![
+                group.size.to_string(),
+                format!("{:.2}%", Self::percent(items, group.size)),
+                group.count.to_string(),
+                group.name.clone(),
+            ]);
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut arr = json::array(dest)?;
+        let shown = self.groups.iter().take(self.limit);
+        for group in shown.chain(self.summaries().iter()) {
+            let mut obj = arr.object()?;
+            obj.field("name", group.name.as_str())?;
+            obj.field("bytes", group.size)?;
+            obj.field("size_percent", Self::percent(items, group.size))?;
+            obj.field("items", group.count)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord<'a> {
+            name: &'a str,
+            bytes: u32,
+            size_percent: f64,
+            items: u32,
+        }
+
+        for group in self.groups.iter().take(self.limit) {
+            wtr.serialize(CsvRecord {
+                name: &group.name,
+                bytes: group.size,
+                size_percent: Self::percent(items, group.size),
+                items: group.count,
+            })?;
+            wtr.flush()?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, _items: &ir::Items, _dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        Err(traits::Error::with_msg(
+            "the `dot` output format is not supported by this analysis",
+        ))
+    }
+
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut page = html::page(dest, "twiggy group")?;
+        let mut table = page.table(&["Bytes", "Size %", "Items", "Group"])?;
+
+        for group in self.groups.iter().take(self.limit) {
+            let count = group.count.to_string();
+            table.row(&[
+                html::Cell::Bytes(i64::from(group.size)),
+                html::Cell::Percent(Self::percent(items, group.size)),
+                html::Cell::Text(&count),
+                html::Cell::Name(&group.name),
+            ])?;
+        }
+
+        for group in self.summaries() {
+            let count = group.count.to_string();
+            table.summary_row(&[
+                html::Cell::Bytes(i64::from(group.size)),
+                html::Cell::Percent(Self::percent(items, group.size)),
+                html::Cell::Text(&count),
+                html::Cell::Text(&group.name),
+            ])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Put each item into the group of the first rule whose pattern matches its
+/// name, and find the size of each group. The rules are given in the format
+/// of the `group` sub-command's config file.
+pub fn group(
+    items: &ir::Items,
+    opts: &opt::Group,
+    config: &str,
+) -> Result<Box<dyn traits::Emit>, traits::Error> {
+    let (names, patterns) = parse_rules(config)?;
+    let regexps = regex::RegexSet::new(&patterns)?;
+
+    let mut groups: BTreeMap<&str, (u32, u32)> = BTreeMap::new();
+    for item in items.iter().filter(|item| item.id() != items.meta_root()) {
+        let name = regexps
+            .matches(item.name())
+            .iter()
+            .next()
+            .map_or(OTHER, |rule| names[rule].as_str());
+        let group = groups.entry(name).or_insert((0, 0));
+        group.0 += item.size();
+        group.1 += 1;
+    }
+
+    let mut groups: Vec<_> = groups
+        .into_iter()
+        .map(|(name, (size, count))| GroupEntry {
+            name: name.to_string(),
+            size,
+            count,
+        })
+        .collect();
+    groups.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(Box::new(Groups {
+        groups,
+        limit: opts.max_items() as usize,
+    }) as Box<_>)
+}
+
+/// Parse the rules in a config file, and return each rule's group and the
+/// regular expression for its pattern. Each line holds a rule, like
+/// `formatting = core::fmt::*`. Patterns are globs, where `*` matches any
+/// number of characters and `?` matches any one character, unless they are
+/// wrapped in slashes, like `/^serde(_json)?::/`, which makes them regular
+/// expressions instead. Blank lines and lines starting with `#` are ignored.
+fn parse_rules(config: &str) -> Result<(Vec<String>, Vec<String>), traits::Error> {
+    let mut names = vec![];
+    let mut patterns = vec![];
+    for (i, line) in config.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let rule = line
+            .find('=')
+            .map(|eq| (line[..eq].trim(), line[eq + 1..].trim()));
+        let (name, pattern) = match rule {
+            Some((name, pattern)) if !name.is_empty() && !pattern.is_empty() => (name, pattern),
+            _ => {
+                return Err(traits::Error::with_msg(format!(
+                    "invalid rule on line {} of the config, expected `<group> = <pattern>`: {}",
+                    i + 1,
+                    line
+                )))
+            }
+        };
+
+        names.push(name.to_string());
+        patterns.push(pattern_to_regex(pattern));
+    }
+    Ok((names, patterns))
+}
+
+/// Translate a rule's pattern into a regular expression. Patterns wrapped in
+/// slashes are regular expressions already, and any others are globs.
+pub(crate) fn pattern_to_regex(pattern: &str) -> String {
+    if pattern.len() >= 2 && pattern.starts_with('/') && pattern.ends_with('/') {
+        pattern[1..pattern.len() - 1].to_string()
+    } else {
+        glob_to_regex(pattern)
+    }
+}
+
+/// Translate a glob into a regular expression that matches whole names.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = "^".to_string();
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
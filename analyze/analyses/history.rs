@@ -0,0 +1,339 @@
+use std::cmp;
+use std::collections::HashMap;
+use std::io;
+
+use super::diff::matching;
+use crate::formats::html;
+use crate::formats::json;
+use crate::formats::table::{Align, Table};
+use twiggy_ir as ir;
+use twiggy_opt as opt;
+use twiggy_traits as traits;
+
+#[derive(Debug)]
+struct History {
+    builds: usize,
+    series: Vec<Series>,
+    /// The number of entries at the end of `series` that summarize the
+    /// remaining and total rows, rather than track a single item.
+    summary_rows: usize,
+}
+
+/// The size of an item in each build, or `None` in the builds that don't
+/// contain it.
+#[derive(Debug)]
+struct Series {
+    /// The item's name in the newest build that contains it.
+    name: String,
+    sizes: Vec<Option<u32>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Trend {
+    /// The item grew from one build to the next, without ever shrinking.
+    Growing,
+    /// The item shrank from one build to the next, without ever growing.
+    Shrinking,
+    /// The item both grew and shrank.
+    Mixed,
+}
+
+impl Trend {
+    fn as_str(self) -> &'static str {
+        match self {
+            Trend::Growing => "growing",
+            Trend::Shrinking => "shrinking",
+            Trend::Mixed => "",
+        }
+    }
+}
+
+impl Series {
+    fn first(&self) -> u32 {
+        self.sizes[0].unwrap_or(0)
+    }
+
+    fn last(&self) -> u32 {
+        self.sizes[self.sizes.len() - 1].unwrap_or(0)
+    }
+
+    fn delta(&self) -> i64 {
+        i64::from(self.last()) - i64::from(self.first())
+    }
+
+    /// How the item's size changed over the builds. Builds that don't contain
+    /// the item count as zero bytes.
+    fn trend(&self) -> Trend {
+        let sizes: Vec<_> = self.sizes.iter().map(|size| size.unwrap_or(0)).collect();
+        if sizes.windows(2).all(|w| w[0] <= w[1]) && self.delta() > 0 {
+            Trend::Growing
+        } else if sizes.windows(2).all(|w| w[0] >= w[1]) && self.delta() < 0 {
+            Trend::Shrinking
+        } else {
+            Trend::Mixed
+        }
+    }
+
+    fn changed(&self) -> bool {
+        self.sizes.windows(2).any(|w| w[0] != w[1])
+    }
+
+    fn size_strings(&self) -> impl Iterator<Item = String> + '_ {
+        self.sizes
+            .iter()
+            .map(|size| size.map_or("-".to_string(), |size| size.to_string()))
+    }
+}
+
+impl History {
+    fn header(&self) -> Vec<String> {
+        (1..=self.builds)
+            .map(|build| format!("#{}", build))
+            .collect()
+    }
+}
+
+impl traits::Emit for History {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut header: Vec<_> = self
+            .header()
+            .into_iter()
+            .map(|build| (Align::Right, build))
+            .collect();
+        header.push((Align::Right, "Delta".to_string()));
+        header.push((Align::Left, "Trend".to_string()));
+        header.push((Align::Left, "Item".to_string()));
+        let mut table = Table::with_header(header);
+
+        let (entries, summaries) = self.series.split_at(self.series.len() - self.summary_rows);
+        for series in entries {
+            let mut row: Vec<_> = series.size_strings().collect();
+            row.push(format!("{:+}", series.delta()));
+            row.push(series.trend().as_str().to_string());
+            row.push(series.name.clone());
+            table.add_row(row);
+        }
+        for series in summaries {
+            let mut row: Vec<_> = series.size_strings().collect();
+            row.push(format!("{:+}", series.delta()));
+            row.push(String::new());
+            row.push(series.name.clone());
+            table.add_row(row);
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut arr = json::array(dest)?;
+        let (entries, summaries) = self.series.split_at(self.series.len() - self.summary_rows);
+        for (series, summary) in entries
+            .iter()
+            .map(|series| (series, false))
+            .chain(summaries.iter().map(|series| (series, true)))
+        {
+            let mut obj = arr.object()?;
+            obj.field("name", series.name.as_str())?;
+            {
+                let mut sizes = obj.array("sizes")?;
+                for &size in &series.sizes {
+                    sizes.elem(size)?;
+                }
+            }
+            obj.field("delta_bytes", series.delta() as f64)?;
+            if !summary {
+                obj.field("growing", series.trend() == Trend::Growing)?;
+                obj.field("shrinking", series.trend() == Trend::Shrinking)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        // The number of columns depends on the number of builds, so these
+        // records are written by hand rather than serialized from a struct.
+        let mut header = vec!["Item".to_string()];
+        header.extend((1..=self.builds).map(|build| format!("Build{}", build)));
+        header.push("DeltaBytes".to_string());
+        header.push("Trend".to_string());
+        wtr.write_record(&header)?;
+
+        let (entries, summaries) = self.series.split_at(self.series.len() - self.summary_rows);
+        for (series, summary) in entries
+            .iter()
+            .map(|series| (series, false))
+            .chain(summaries.iter().map(|series| (series, true)))
+        {
+            let mut record = vec![series.name.clone()];
+            record.extend(
+                series
+                    .sizes
+                    .iter()
+                    .map(|size| size.map_or(String::new(), |size| size.to_string())),
+            );
+            record.push(format!("{:+}", series.delta()));
+            record.push(if summary {
+                String::new()
+            } else {
+                series.trend().as_str().to_string()
+            });
+            wtr.write_record(&record)?;
+            wtr.flush()?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, _items: &ir::Items, _dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        Err(traits::Error::with_msg(
+            "the `dot` output format is not supported by this analysis",
+        ))
+    }
+
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, _items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let mut page = html::page(dest, "twiggy history")?;
+        let mut header = self.header();
+        header.push("Delta".to_string());
+        header.push("Trend".to_string());
+        header.push("Item".to_string());
+        let header: Vec<_> = header.iter().map(|h| h.as_str()).collect();
+        let mut table = page.table(&header)?;
+
+        let sizes = |series: &Series| -> Vec<html::Cell> {
+            series
+                .sizes
+                .iter()
+                .map(|size| match *size {
+                    Some(size) => html::Cell::Bytes(i64::from(size)),
+                    None => html::Cell::Text("-"),
+                })
+                .collect()
+        };
+
+        let (entries, summaries) = self.series.split_at(self.series.len() - self.summary_rows);
+        for series in entries {
+            let mut row = sizes(series);
+            row.push(html::Cell::Delta(series.delta()));
+            row.push(html::Cell::Text(series.trend().as_str()));
+            row.push(html::Cell::Name(&series.name));
+            table.row(&row)?;
+        }
+        for series in summaries {
+            let mut row = sizes(series);
+            row.push(html::Cell::Delta(series.delta()));
+            row.push(html::Cell::Text(""));
+            row.push(html::Cell::Text(&series.name));
+            table.summary_row(&row)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Track the size of each item across a series of builds of a binary, from
+/// oldest to newest. Items are matched up between each pair of consecutive
+/// builds the same way that `diff` matches them, allowing for names that
+/// changed between builds.
+pub fn history(
+    builds: &[&ir::Items],
+    opts: &opt::History,
+) -> Result<Box<dyn traits::Emit>, traits::Error> {
+    if builds.len() < 2 {
+        return Err(traits::Error::with_msg(
+            "at least two builds are needed to track their history",
+        ));
+    }
+
+    let new_series = || Series {
+        name: String::new(),
+        sizes: vec![None; builds.len()],
+    };
+
+    // Map each item in the latest build so far to the series that tracks it.
+    let mut series: Vec<Series> = vec![];
+    let mut current: HashMap<ir::Id, usize> = HashMap::new();
+    for item in builds[0].iter() {
+        if item.id() == builds[0].meta_root() {
+            continue;
+        }
+        let mut first = new_series();
+        first.name = item.name().to_string();
+        first.sizes[0] = Some(item.size());
+        current.insert(item.id(), series.len());
+        series.push(first);
+    }
+
+    for (build, pair) in builds.windows(2).enumerate().map(|(i, pair)| (i + 1, pair)) {
+        let mut next = HashMap::new();
+        for m in matching::match_items(pair[0], pair[1]) {
+            let new = match m.new {
+                Some(new) => new,
+                None => continue,
+            };
+            let index = match m.old.and_then(|old| current.get(&old)) {
+                Some(&index) => index,
+                None => {
+                    series.push(new_series());
+                    series.len() - 1
+                }
+            };
+            let item = &pair[1][new];
+            series[index].name = item.name().to_string();
+            series[index].sizes[build] = Some(item.size());
+            next.insert(new, index);
+        }
+        current = next;
+    }
+
+    let mut series: Vec<_> = series
+        .into_iter()
+        .filter(|s| s.changed())
+        .filter(|s| !opts.growing() || s.trend() == Trend::Growing)
+        .collect();
+    series.sort_by(|a, b| {
+        b.delta()
+            .abs()
+            .cmp(&a.delta().abs())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    // Create rows to summarize the series that will be truncated, and the
+    // size of each build.
+    let max_items = opts.max_items() as usize;
+    let sum = |name: String, series: &[Series]| Series {
+        name,
+        sizes: (0..builds.len())
+            .map(|build| Some(series.iter().map(|s| s.sizes[build].unwrap_or(0)).sum()))
+            .collect(),
+    };
+    let rem_cnt = series.len().saturating_sub(max_items);
+    let remaining = sum(
+        format!("... and {} more.", rem_cnt),
+        &series[cmp::min(max_items, series.len())..],
+    );
+    let total = Series {
+        name: format!("Σ [{} Total Rows]", series.len()),
+        sizes: builds.iter().map(|items| Some(items.size())).collect(),
+    };
+
+    series.truncate(max_items);
+    let mut summary_rows = 1;
+    if rem_cnt > 0 {
+        series.push(remaining);
+        summary_rows += 1;
+    }
+    series.push(total);
+
+    Ok(Box::new(History {
+        builds: builds.len(),
+        series,
+        summary_rows,
+    }) as Box<_>)
+}
@@ -1,6 +1,11 @@
+pub mod check;
+pub mod crates;
 pub mod diff;
 pub mod dominators;
 pub mod garbage;
+pub mod group;
+pub mod history;
 pub mod monos;
 pub mod paths;
+pub mod sources;
 pub mod top;
@@ -4,6 +4,8 @@ use std::iter;
 use csv;
 use serde_derive::Serialize;
 
+use crate::formats::dot;
+use crate::formats::html;
 use crate::formats::json;
 use crate::formats::table::{Align, Table};
 use twiggy_ir as ir;
@@ -179,4 +181,94 @@ impl traits::Emit for Monos {
         }
         Ok(())
     }
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let get_size_percent = |size: u32| f64::from(size) / f64::from(items.size()) * 100.0;
+
+        // Draw each generic function with an edge to each of its
+        // monomorphizations.
+        let mut graph = dot::digraph(dest, "monos")?;
+        for (i, entry) in self.monos.iter().enumerate() {
+            let generic = format!("generic{}", i);
+            let label = format!(
+                "{}\napprx. bloat: {} bytes ({:.2}%)\ntotal: {} bytes ({:.2}%)",
+                entry.name,
+                entry.bloat,
+                get_size_percent(entry.bloat),
+                entry.size,
+                get_size_percent(entry.size)
+            );
+            graph.node(&generic, &label)?;
+
+            for (j, (name, size)) in entry.insts.iter().enumerate() {
+                let mono = format!("mono{}_{}", i, j);
+                let label = format!(
+                    "{}\nshallow: {} bytes ({:.2}%)",
+                    name,
+                    size,
+                    get_size_percent(*size)
+                );
+                graph.node(&mono, &label)?;
+                graph.edge(&generic, &mono)?;
+            }
+        }
+
+        Ok(())
+    }
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let get_size_percent = |size: u32| f64::from(size) / f64::from(items.size()) * 100.0;
+        let cells = |entry: &'_ MonosEntry| {
+            [
+                html::Cell::Bytes(i64::from(entry.bloat)),
+                html::Cell::Percent(get_size_percent(entry.bloat)),
+                html::Cell::Bytes(i64::from(entry.size)),
+                html::Cell::Percent(get_size_percent(entry.size)),
+            ]
+        };
+
+        let mut page = html::page(dest, "twiggy monos")?;
+        let mut table = page.table(&[
+            "Apprx. Bloat Bytes",
+            "Apprx. Bloat %",
+            "Bytes",
+            "%",
+            "Monomorphizations",
+        ])?;
+
+        // Each generic function is followed by its monomorphizations, which
+        // stay nested beneath it when the table is sorted.
+        let (entries, summaries) = self.monos.split_at(self.monos.len() - self.summary_rows);
+        for entry in entries {
+            let [bloat, bloat_percent, size, size_percent] = cells(entry);
+            table.row(&[
+                bloat,
+                bloat_percent,
+                size,
+                size_percent,
+                html::Cell::Name(&entry.name),
+            ])?;
+            for (name, size) in &entry.insts {
+                table.nested_row(&[
+                    html::Cell::Text(""),
+                    html::Cell::Text(""),
+                    html::Cell::Bytes(i64::from(*size)),
+                    html::Cell::Percent(get_size_percent(*size)),
+                    html::Cell::Name(name),
+                ])?;
+            }
+        }
+        for entry in summaries {
+            let [bloat, bloat_percent, size, size_percent] = cells(entry);
+            table.summary_row(&[
+                bloat,
+                bloat_percent,
+                size,
+                size_percent,
+                html::Cell::Text(&entry.name),
+            ])?;
+        }
+
+        Ok(())
+    }
 }
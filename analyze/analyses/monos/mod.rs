@@ -14,6 +14,9 @@ use self::entry::MonosEntry;
 #[derive(Debug)]
 struct Monos {
     monos: Vec<MonosEntry>,
+    /// The number of entries at the end of `monos` that summarize the
+    /// remaining and total rows, rather than describe a generic function.
+    summary_rows: usize,
 }
 
 /// Type alias used to represent a map of generic function names and instantiations.
@@ -142,8 +145,9 @@ fn process_monomorphizations(monos_map: MonosMap, opts: &opt::Monos) -> Vec<Mono
 }
 
 /// Adds entries to summarize remaining rows that will be truncated, and
-/// totals for the entire set of monomorphizations.
-fn add_stats(mut monos: Vec<MonosEntry>, opts: &opt::Monos) -> Vec<MonosEntry> {
+/// totals for the entire set of monomorphizations. Returns the entries along
+/// with the number of summary entries that were added.
+fn add_stats(mut monos: Vec<MonosEntry>, opts: &opt::Monos) -> (Vec<MonosEntry>, usize) {
     let max_generics = opts.max_generics() as usize;
 
     // Create an entry to represent the remaining rows that will be truncated,
@@ -176,11 +180,13 @@ fn add_stats(mut monos: Vec<MonosEntry>, opts: &opt::Monos) -> Vec<MonosEntry> {
 
     // Truncate the vector, and add the 'remaining' and 'total' summary entries.
     monos.truncate(max_generics);
+    let mut summary_rows = 1;
     if let Some(remaining) = remaining {
         monos.push(remaining);
+        summary_rows += 1;
     }
     monos.push(total);
-    monos
+    (monos, summary_rows)
 }
 
 /// Find bloaty monomorphizations of generic functions.
@@ -189,7 +195,10 @@ pub fn monos(
     opts: &opt::Monos,
 ) -> Result<Box<dyn traits::Emit>, traits::Error> {
     let monos_map = collect_monomorphizations(&items, &opts)?;
-    let mut monos = process_monomorphizations(monos_map, &opts);
-    monos = add_stats(monos, &opts);
-    Ok(Box::new(Monos { monos }) as Box<_>)
+    let monos = process_monomorphizations(monos_map, &opts);
+    let (monos, summary_rows) = add_stats(monos, &opts);
+    Ok(Box::new(Monos {
+        monos,
+        summary_rows,
+    }) as Box<_>)
 }
@@ -143,6 +143,7 @@ fn create_entry(
     seen.remove(&id);
 
     PathsEntry {
+        id,
         name,
         size,
         children,
@@ -3,6 +3,8 @@ use std::io;
 use csv;
 
 use crate::analyses::paths::Paths;
+use crate::formats::dot;
+use crate::formats::html;
 use crate::formats::json;
 use crate::formats::table::{Align, Table};
 use twiggy_ir as ir;
@@ -93,6 +95,57 @@ impl traits::Emit for Paths {
 
         Ok(())
     }
+
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        use self::emit_dot_helpers::process_entry;
+        use std::collections::BTreeSet;
+
+        // Collect the nodes and edges of every entry's call paths first, so
+        // that items shared between paths are only drawn once.
+        let mut nodes = BTreeSet::new();
+        let mut edges = BTreeSet::new();
+        for entry in &self.entries {
+            process_entry(
+                entry,
+                0,
+                self.opts.max_paths() as usize,
+                &self.opts,
+                &mut nodes,
+                &mut edges,
+            );
+        }
+
+        let mut graph = dot::digraph(dest, "paths")?;
+        for id in nodes {
+            graph.node(&dot::item_node(id), &dot::item_label(items, id, false))?;
+        }
+        for (from, to) in edges {
+            graph.edge(&dot::item_node(from), &dot::item_node(to))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        use self::emit_html_helpers::process_entry;
+
+        let mut page = html::page(dest, "twiggy paths")?;
+        let mut tree = page.tree(&["Shallow Bytes", "Shallow %", "Retaining Paths"])?;
+        for entry in &self.entries {
+            process_entry(
+                entry,
+                &mut tree,
+                0,
+                self.opts.max_paths() as usize,
+                items,
+                &self.opts,
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 /// This module contains helper functions and structs used by the `emit_text`
@@ -204,6 +257,7 @@ mod emit_json_helpers {
             name,
             size,
             children,
+            ..
         } = entry;
         obj.field("name", name.as_str())?;
         obj.field("shallow_size", *size)?;
@@ -299,3 +353,78 @@ mod emit_csv_helpers {
         }
     }
 }
+
+/// This module contains helper functions used by the `emit_dot` method in
+/// Path's implementation of the `traits::Emit` trait.
+#[cfg(feature = "emit_dot")]
+mod emit_dot_helpers {
+    use crate::analyses::paths::paths_entry::PathsEntry;
+    use std::collections::BTreeSet;
+    use twiggy_ir::Id;
+    use twiggy_opt::Paths;
+
+    // Process a given entry and its children, adding the items they contain
+    // to `nodes`, and the calls between them to `edges`.
+    pub(super) fn process_entry(
+        entry: &PathsEntry,
+        depth: u32,
+        paths: usize,
+        opts: &Paths,
+        nodes: &mut BTreeSet<Id>,
+        edges: &mut BTreeSet<(Id, Id)>,
+    ) {
+        nodes.insert(entry.id);
+
+        if depth < opts.max_depth() {
+            for child in entry.children.iter().take(paths) {
+                // Edges always point from the caller to the callee, regardless
+                // of the direction we traversed the call graph in.
+                if opts.descending() {
+                    edges.insert((entry.id, child.id));
+                } else {
+                    edges.insert((child.id, entry.id));
+                }
+                process_entry(child, depth + 1, paths, opts, nodes, edges);
+            }
+        }
+    }
+}
+
+/// This module contains helper functions used by the `emit_html` method in
+/// Path's implementation of the `traits::Emit` trait.
+#[cfg(feature = "emit_html")]
+mod emit_html_helpers {
+    use crate::analyses::paths::paths_entry::PathsEntry;
+    use crate::formats::html::{Cell, Tree};
+    use std::io;
+    use twiggy_ir::Items;
+    use twiggy_opt::Paths;
+
+    // Process a given entry, adding it to the tree with its children nested
+    // beneath it.
+    pub(super) fn process_entry(
+        entry: &PathsEntry,
+        tree: &mut Tree,
+        depth: u32,
+        paths: usize,
+        items: &Items,
+        opts: &Paths,
+    ) -> io::Result<()> {
+        let size_percent = f64::from(entry.size) / f64::from(items.size()) * 100.0;
+        let cells = [
+            Cell::Bytes(i64::from(entry.size)),
+            Cell::Percent(size_percent),
+            Cell::Name(&entry.name),
+        ];
+
+        if depth >= opts.max_depth() || entry.children.is_empty() {
+            return tree.leaf(&cells);
+        }
+
+        tree.begin(&cells)?;
+        for child in entry.children.iter().take(paths) {
+            process_entry(child, tree, depth + 1, paths, items, opts)?;
+        }
+        tree.end()
+    }
+}
@@ -1,7 +1,10 @@
 use std::cmp;
 
+use twiggy_ir as ir;
+
 #[derive(Debug, PartialEq, Eq)]
 pub(super) struct PathsEntry {
+    pub id: ir::Id,
     pub name: String,
     pub size: u32,
     pub children: Vec<PathsEntry>,
@@ -0,0 +1,353 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+
+use serde_derive::Serialize;
+
+use crate::formats::dot;
+use crate::formats::html;
+use crate::formats::json;
+use crate::formats::table::{Align, Table};
+use twiggy_ir as ir;
+use twiggy_opt::{self as opt, SourceGrouping};
+use twiggy_traits as traits;
+
+/// The name of the group for code that we can't tell the source of.
+const UNKNOWN: &str = "<unknown>";
+
+#[derive(Debug)]
+struct Sources {
+    group_by: SourceGrouping,
+    groups: Vec<SourceGroup>,
+    limit: usize,
+}
+
+#[derive(Debug)]
+struct SourceGroup {
+    name: String,
+    size: u32,
+    functions: usize,
+
+    // The files in this group, and how many bytes of code each contributed.
+    // Empty when grouping by file.
+    files: Vec<(String, u32)>,
+}
+
+impl Sources {
+    fn heading(&self) -> &'static str {
+        match self.group_by {
+            SourceGrouping::Crate => "Crate",
+            SourceGrouping::Directory => "Directory",
+            SourceGrouping::File => "File",
+        }
+    }
+
+    fn remaining(&self) -> Option<(String, u32)> {
+        let remaining = self.groups.get(self.limit..)?;
+        if remaining.is_empty() {
+            return None;
+        }
+        let size = remaining.iter().map(|group| group.size).sum();
+        Some((format!("... and {} more", remaining.len()), size))
+    }
+
+    fn total(&self) -> (String, u32) {
+        let size = self.groups.iter().map(|group| group.size).sum();
+        (format!("Σ [{} Total Rows]", self.groups.len()), size)
+    }
+}
+
+impl traits::Emit for Sources {
+    #[cfg(feature = "emit_text")]
+    fn emit_text(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let percent = |size: u32| f64::from(size) / f64::from(items.size()) * 100.0;
+
+        let mut table = Table::with_header(vec![
+            (Align::Right, "Bytes".to_string()),
+            (Align::Right, "Size %".to_string()),
+            (Align::Right, "Functions".to_string()),
+            (Align::Left, self.heading().to_string()),
+        ]);
+
+        for group in self.groups.iter().take(self.limit) {
+            table.add_row(vec![
+                group.size.to_string(),
+                format!("{:.2}%", percent(group.size)),
+                group.functions.to_string(),
+                group.name.clone(),
+            ]);
+        }
+
+        for (name, size) in self.remaining().into_iter().chain(Some(self.total())) {
+            table.add_row(vec![
+                size.to_string(),
+                format!("{:.2}%", percent(size)),
+                "".to_string(),
+                name,
+            ]);
+        }
+
+        write!(dest, "{}", &table)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_json")]
+    fn emit_json(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let percent = |size: u32| f64::from(size) / f64::from(items.size()) * 100.0;
+
+        let mut arr = json::array(dest)?;
+        for group in self.groups.iter().take(self.limit) {
+            let mut obj = arr.object()?;
+            obj.field("name", group.name.as_str())?;
+            obj.field("bytes", group.size)?;
+            obj.field("size_percent", percent(group.size))?;
+            obj.field("functions", group.functions as u32)?;
+
+            if self.group_by != SourceGrouping::File {
+                let mut files = obj.array("files")?;
+                for (name, size) in &group.files {
+                    let mut file = files.object()?;
+                    file.field("name", name.as_str())?;
+                    file.field("bytes", *size)?;
+                    file.field("size_percent", percent(*size))?;
+                }
+            }
+        }
+
+        for (name, size) in self.remaining().into_iter().chain(Some(self.total())) {
+            let mut obj = arr.object()?;
+            obj.field("name", name.as_str())?;
+            obj.field("bytes", size)?;
+            obj.field("size_percent", percent(size))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_csv")]
+    fn emit_csv(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let percent = |size: u32| f64::from(size) / f64::from(items.size()) * 100.0;
+
+        let mut wtr = csv::Writer::from_writer(dest);
+
+        #[derive(Serialize, Debug)]
+        #[serde(rename_all = "PascalCase")]
+        struct CsvRecord<'a> {
+            name: &'a str,
+            bytes: u32,
+            size_percent: f64,
+            functions: usize,
+        }
+
+        for group in self.groups.iter().take(self.limit) {
+            wtr.serialize(CsvRecord {
+                name: &group.name,
+                bytes: group.size,
+                size_percent: percent(group.size),
+                functions: group.functions,
+            })?;
+            wtr.flush()?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let percent = |size: u32| f64::from(size) / f64::from(items.size()) * 100.0;
+
+        // Draw each group with an edge to each of its files.
+        let mut graph = dot::digraph(dest, "sources")?;
+        for (i, group) in self.groups.iter().take(self.limit).enumerate() {
+            let node = format!("group{}", i);
+            let label = format!(
+                "{}\n{} bytes ({:.2}%)",
+                group.name,
+                group.size,
+                percent(group.size)
+            );
+            graph.node(&node, &label)?;
+
+            for (j, (name, size)) in group.files.iter().enumerate() {
+                let file = format!("file{}_{}", i, j);
+                let label = format!("{}\n{} bytes ({:.2}%)", name, size, percent(*size));
+                graph.node(&file, &label)?;
+                graph.edge(&node, &file)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let percent = |size: u32| f64::from(size) / f64::from(items.size()) * 100.0;
+
+        let mut page = html::page(dest, "twiggy sources")?;
+        let mut table = page.table(&["Bytes", "Size %", "Functions", self.heading()])?;
+
+        // Each group is followed by its files, which stay nested beneath it
+        // when the table is sorted.
+        for group in self.groups.iter().take(self.limit) {
+            let functions = group.functions.to_string();
+            table.row(&[
+                html::Cell::Bytes(i64::from(group.size)),
+                html::Cell::Percent(percent(group.size)),
+                html::Cell::Text(&functions),
+                html::Cell::Name(&group.name),
+            ])?;
+            for (name, size) in &group.files {
+                table.nested_row(&[
+                    html::Cell::Bytes(i64::from(*size)),
+                    html::Cell::Percent(percent(*size)),
+                    html::Cell::Text(""),
+                    html::Cell::Name(name),
+                ])?;
+            }
+        }
+
+        for (name, size) in self.remaining().into_iter().chain(Some(self.total())) {
+            table.summary_row(&[
+                html::Cell::Bytes(i64::from(size)),
+                html::Cell::Percent(percent(size)),
+                html::Cell::Text(""),
+                html::Cell::Text(&name),
+            ])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Aggregate the size of the code in a binary by the crates, directories, or
+/// source files that it was compiled from.
+pub fn sources(
+    items: &ir::Items,
+    opts: &opt::Sources,
+) -> Result<Box<dyn traits::Emit>, traits::Error> {
+    #[derive(Default)]
+    struct Group<'a> {
+        size: u32,
+        functions: BTreeSet<ir::Id>,
+        files: BTreeMap<&'a str, u32>,
+    }
+
+    let group_by = opts.group_by();
+    let mut groups: BTreeMap<&str, Group> = BTreeMap::new();
+    for item in items.iter() {
+        if let ir::ItemKind::Code(_) = item.kind() {
+        } else {
+            continue;
+        }
+
+        let sources = items.sources(item.id());
+        if sources.is_empty() {
+            let group = groups.entry(UNKNOWN).or_default();
+            group.size += item.size();
+            group.functions.insert(item.id());
+            continue;
+        }
+
+        // The bytes of a function that the line number information doesn't
+        // cover, such as its header, belong to the file that most of the
+        // function was compiled from.
+        let covered: u32 = sources.iter().map(|&(_, size)| size).sum();
+        let uncovered = item.size().saturating_sub(covered);
+        let main = sources
+            .iter()
+            .enumerate()
+            .max_by_key(|&(_, &(_, size))| size)
+            .map(|(i, _)| i);
+
+        for (i, (file, size)) in sources.iter().enumerate() {
+            let size = if Some(i) == main {
+                size + uncovered
+            } else {
+                *size
+            };
+            let group = groups.entry(group_of(items, group_by, file)).or_default();
+            group.size += size;
+            group.functions.insert(item.id());
+            if group_by != SourceGrouping::File {
+                *group.files.entry(file).or_insert(0) += size;
+            }
+        }
+    }
+
+    let mut groups: Vec<_> = groups
+        .into_iter()
+        .map(|(name, group)| {
+            let mut files: Vec<_> = group
+                .files
+                .into_iter()
+                .map(|(file, size)| (file.to_string(), size))
+                .collect();
+            files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            SourceGroup {
+                name: name.to_string(),
+                size: group.size,
+                functions: group.functions.len(),
+                files,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(Box::new(Sources {
+        group_by,
+        groups,
+        limit: opts.max_items() as usize,
+    }) as Box<_>)
+}
+
+/// Get the name of the group that the source file at the given path belongs to.
+fn group_of<'a>(items: &'a ir::Items, group_by: SourceGrouping, file: &'a str) -> &'a str {
+    match group_by {
+        SourceGrouping::Crate => items
+            .source_crate(file)
+            .or_else(|| crate_name(file))
+            .unwrap_or(UNKNOWN),
+        SourceGrouping::Directory => directory(file),
+        SourceGrouping::File => file,
+    }
+}
+
+/// Get the directory that the source file at the given path is in.
+fn directory(file: &str) -> &str {
+    match file.rfind(&['/', '\\'][..]) {
+        Some(0) => &file[..1],
+        Some(i) => &file[..i],
+        None => ".",
+    }
+}
+
+/// Get the name of the Rust crate that the source file at the given path
+/// belongs to, when the debug info doesn't say, from where Rust toolchains and
+/// Cargo keep crates' sources: `registry/src/<index>/<name>-<version>` for
+/// crates that Cargo downloads, `library/<name>` for the standard library's
+/// crates in newer toolchains, and `src/lib<name>` in older ones. The sources
+/// of any other crate could be anywhere, so those aren't guessed at.
+fn crate_name(file: &str) -> Option<&str> {
+    let components: Vec<&str> = file.split(&['/', '\\'][..]).collect();
+    // Every layout names the crate in a directory, so the file's own name is
+    // never considered.
+    let dirs = &components[..components.len().saturating_sub(1)];
+
+    if let Some(i) = dirs.windows(2).position(|w| w == ["registry", "src"]) {
+        let package = dirs.get(i + 3)?;
+        let version = package
+            .char_indices()
+            .find(|&(i, c)| c == '-' && package[i + 1..].starts_with(|c: char| c.is_ascii_digit()))?
+            .0;
+        return Some(&package[..version]);
+    }
+
+    if dirs.get(1) == Some(&"rustc") {
+        if let Some(i) = dirs.iter().position(|&dir| dir == "library") {
+            return dirs.get(i + 1).copied();
+        }
+    }
+
+    dirs.windows(2)
+        .rev()
+        .find(|w| w[0] == "src" && w[1].len() > "lib".len() && w[1].starts_with("lib"))
+        .map(|w| &w[1]["lib".len()..])
+}
@@ -1,8 +1,11 @@
+use std::collections::BTreeSet;
 use std::io;
 
 use csv;
 use serde_derive::Serialize;
 
+use crate::formats::dot;
+use crate::formats::html;
 use crate::formats::json;
 use crate::formats::table::{Align, Table};
 use twiggy_ir as ir;
@@ -17,6 +20,8 @@ struct Top {
 impl traits::Emit for Top {
     #[cfg(feature = "emit_text")]
     fn emit_text(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        self.check_retaining_paths()?;
+
         // A struct used to represent a row in the table that will be emitted.
         struct TableRow {
             size: u32,
@@ -135,6 +140,8 @@ impl traits::Emit for Top {
 
     #[cfg(feature = "emit_json")]
     fn emit_json(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        self.check_retaining_paths()?;
+
         let mut arr = json::array(dest)?;
 
         let max_items = self.opts.max_items() as usize;
@@ -164,6 +171,8 @@ impl traits::Emit for Top {
 
     #[cfg(feature = "emit_csv")]
     fn emit_csv(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        self.check_retaining_paths()?;
+
         let mut wtr = csv::Writer::from_writer(dest);
 
         #[derive(Serialize, Debug)]
@@ -206,14 +215,126 @@ impl traits::Emit for Top {
         }
         Ok(())
     }
+
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        let max_items = self.opts.max_items() as usize;
+        let retained = self.opts.retained();
+
+        let mut graph = dot::digraph(dest, "top")?;
+        let mut seen: BTreeSet<ir::Id> = self.items.iter().take(max_items).cloned().collect();
+        for &id in &seen {
+            graph.node(&dot::item_node(id), &dot::item_label(items, id, retained))?;
+        }
+
+        if !self.opts.retaining_paths() {
+            return Ok(());
+        }
+
+        // Walk the predecessors of each of the top items all the way up to the
+        // roots, drawing every item and edge along the way. This gives the
+        // graph of everything that is keeping the top items alive.
+        let mut stack: Vec<ir::Id> = seen.iter().cloned().collect();
+        while let Some(id) = stack.pop() {
+            for pred in items.predecessors(id) {
+                if pred == items.meta_root() {
+                    continue;
+                }
+                if seen.insert(pred) {
+                    graph.node(
+                        &dot::item_node(pred),
+                        &dot::item_label(items, pred, retained),
+                    )?;
+                    stack.push(pred);
+                }
+                graph.edge(&dot::item_node(pred), &dot::item_node(id))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+        self.check_retaining_paths()?;
+
+        let max_items = self.opts.max_items() as usize;
+        let retained = self.opts.retained();
+        let size_of = |id: ir::Id| {
+            if retained {
+                items.retained_size(id)
+            } else {
+                items[id].size()
+            }
+        };
+        let percent = |size: u32| f64::from(size) / f64::from(items.size()) * 100.0;
+
+        let mut page = html::page(dest, "twiggy top")?;
+        let mut table = if retained {
+            page.table(&["Retained Bytes", "Retained %", "Item"])?
+        } else {
+            page.table(&["Shallow Bytes", "Shallow %", "Item"])?
+        };
+
+        for &id in self.items.iter().take(max_items) {
+            let size = size_of(id);
+            table.row(&[
+                html::Cell::Bytes(i64::from(size)),
+                html::Cell::Percent(percent(size)),
+                html::Cell::Name(items[id].name()),
+            ])?;
+        }
+
+        // Retained sizes overlap, so they cannot be summed up for the summary
+        // rows.
+        let summary_row = |table: &mut html::Table, ids: &[ir::Id], name: &str| {
+            if retained {
+                table.summary_row(&[
+                    html::Cell::Unknown,
+                    html::Cell::Unknown,
+                    html::Cell::Text(name),
+                ])
+            } else {
+                let size: u32 = ids.iter().map(|&id| size_of(id)).sum();
+                table.summary_row(&[
+                    html::Cell::Bytes(i64::from(size)),
+                    html::Cell::Percent(percent(size)),
+                    html::Cell::Text(name),
+                ])
+            }
+        };
+
+        if self.items.len() > max_items {
+            let remaining = &self.items[max_items..];
+            let name = format!("... and {} more.", remaining.len());
+            summary_row(&mut table, remaining, &name)?;
+        }
+
+        let name = format!("Σ [{} Total Rows]", self.items.len());
+        summary_row(&mut table, &self.items, &name)?;
+
+        Ok(())
+    }
+}
+
+impl Top {
+    /// Retaining paths can only be drawn as a graph, so return an error if
+    /// they were requested for any of the tabular output formats.
+    fn check_retaining_paths(&self) -> Result<(), traits::Error> {
+        if self.opts.retaining_paths() {
+            Err(traits::Error::with_msg(
+                "retaining paths are only supported with the `dot` output format",
+            ))
+        } else {
+            Ok(())
+        }
+    }
 }
 
 /// Run the `top` analysis on the given IR items.
 pub fn top(items: &mut ir::Items, opts: &opt::Top) -> Result<Box<dyn traits::Emit>, traits::Error> {
     if opts.retaining_paths() {
-        return Err(traits::Error::with_msg(
-            "retaining paths are not yet implemented",
-        ));
+        items.compute_predecessors();
     }
 
     if opts.retained() {
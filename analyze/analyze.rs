@@ -7,5 +7,6 @@ mod analyses;
 mod formats;
 
 pub use analyses::{
-    diff::diff, dominators::dominators, garbage::garbage, monos::monos, paths::paths, top::top,
+    check::check, crates::crates, diff::diff, dominators::dominators, garbage::garbage,
+    group::group, history::history, monos::monos, paths::paths, sources::sources, top::top,
 };
@@ -0,0 +1,113 @@
+// A couple methods are dead, but removing them would make the API oddly
+// imbalanced and we might want to use them in some future analysis.
+#![allow(dead_code)]
+
+use std::fmt;
+use std::io;
+
+use twiggy_ir as ir;
+
+/// Quote and escape the given string so it can be used as a dot identifier or
+/// attribute value.
+struct Quoted<'a>(&'a str);
+
+impl<'a> fmt::Display for Quoted<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => write!(f, "\\\"")?,
+                '\\' => write!(f, "\\\\")?,
+                '\n' => write!(f, "\\n")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+/// Get the dot node identifier for the given IR item.
+pub fn item_node(id: ir::Id) -> String {
+    format!("item{}", id.serializable())
+}
+
+/// Get a label for the given IR item, containing its name and shallow size,
+/// and its retained size if that has been computed.
+pub fn item_label(items: &ir::Items, id: ir::Id, retained: bool) -> String {
+    let item = &items[id];
+    let percent = |size: u32| f64::from(size) / f64::from(items.size()) * 100.0;
+
+    let mut label = format!(
+        "{}\nshallow: {} bytes ({:.2}%)",
+        item.name(),
+        item.size(),
+        percent(item.size())
+    );
+    if retained {
+        let size = items.retained_size(id);
+        label.push_str(&format!(
+            "\nretained: {} bytes ({:.2}%)",
+            size,
+            percent(size)
+        ));
+    }
+    label
+}
+
+pub fn digraph<'a>(w: &'a mut dyn io::Write, name: &str) -> io::Result<Graph<'a>> {
+    writeln!(w, "digraph {} {{", Quoted(name))?;
+    writeln!(w, "  node [shape=box, fontname=\"monospace\"];")?;
+    Ok(Graph { w, indent: 1 })
+}
+
+pub struct Graph<'a> {
+    w: &'a mut dyn io::Write,
+    indent: usize,
+}
+
+impl<'a> Drop for Graph<'a> {
+    fn drop(&mut self) {
+        let _ = self.write_indent(self.indent - 1);
+        let _ = writeln!(self.w, "}}");
+    }
+}
+
+impl<'a> Graph<'a> {
+    fn write_indent(&mut self, indent: usize) -> io::Result<()> {
+        for _ in 0..indent {
+            write!(self.w, "  ")?;
+        }
+        Ok(())
+    }
+
+    /// Start a subgraph. If the name begins with `cluster`, Graphviz will draw
+    /// it as a box around its nodes.
+    pub fn subgraph<'b>(&'b mut self, name: &str, label: &str) -> io::Result<Graph<'b>> {
+        self.write_indent(self.indent)?;
+        writeln!(self.w, "subgraph {} {{", Quoted(name))?;
+        let mut graph = Graph {
+            w: &mut *self.w,
+            indent: self.indent + 1,
+        };
+        graph.attr("label", label)?;
+        Ok(graph)
+    }
+
+    /// Set an attribute of this graph.
+    pub fn attr(&mut self, key: &str, value: &str) -> io::Result<()> {
+        self.write_indent(self.indent)?;
+        writeln!(self.w, "{}={};", key, Quoted(value))
+    }
+
+    /// Add a node with the given identifier and label.
+    pub fn node(&mut self, id: &str, label: &str) -> io::Result<()> {
+        self.write_indent(self.indent)?;
+        writeln!(self.w, "{} [label={}];", Quoted(id), Quoted(label))
+    }
+
+    /// Add an edge between the nodes with the given identifiers.
+    pub fn edge(&mut self, from: &str, to: &str) -> io::Result<()> {
+        self.write_indent(self.indent)?;
+        writeln!(self.w, "{} -> {};", Quoted(from), Quoted(to))
+    }
+}
@@ -0,0 +1,343 @@
+// A couple methods are dead, but removing them would make the API oddly
+// imbalanced and we might want to use them in some future analysis.
+#![allow(dead_code)]
+
+use std::fmt;
+use std::io;
+
+/// The styles for the report. Everything is inlined into the page so that it
+/// can be opened straight from the file system without any network access.
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h1 { font-size: 1.4em; }
+input.search { width: 30em; padding: 0.3em; margin-bottom: 1em; font-size: 1em; }
+table { border-collapse: collapse; font-family: monospace; }
+th { cursor: pointer; user-select: none; background: #eee; }
+th.sorted-asc::after { content: " \25B2"; }
+th.sorted-desc::after { content: " \25BC"; }
+th, td { padding: 0.2em 0.8em; border-bottom: 1px solid #ddd; }
+td.num, th.num { text-align: right; }
+tr.nested td.name { padding-left: 2.5em; }
+tfoot td { font-weight: bold; }
+.tree { font-family: monospace; }
+.tree .header { font-weight: bold; background: #eee; }
+.tree ul { list-style: none; margin: 0; padding-left: 1.5em; }
+.tree > ul { padding-left: 0; }
+.tree summary, .tree .leaf, .tree .header { padding: 0.1em 0; }
+.tree .leaf { padding-left: 1.1em; }
+.tree span.num { display: inline-block; width: 7em; text-align: right; margin-right: 1em; }
+.hidden { display: none; }
+"#;
+
+/// The script for the report, which makes tables sortable by clicking on
+/// their headers and filters tables and trees by item name.
+const SCRIPT: &str = r#"
+function cellValue(row, column) {
+  var cell = row.cells[column];
+  if (!cell) return "";
+  var value = cell.getAttribute("data-value");
+  return value === null ? cell.textContent : parseFloat(value);
+}
+
+document.querySelectorAll("table.sortable th").forEach(function (th) {
+  th.addEventListener("click", function () {
+    var table = th.closest("table");
+    var column = th.cellIndex;
+    var asc = !th.classList.contains("sorted-asc");
+    table.querySelectorAll("th").forEach(function (other) {
+      other.classList.remove("sorted-asc", "sorted-desc");
+    });
+    th.classList.add(asc ? "sorted-asc" : "sorted-desc");
+    var groups = Array.prototype.slice.call(table.tBodies);
+    groups.sort(function (a, b) {
+      var x = cellValue(a.rows[0], column);
+      var y = cellValue(b.rows[0], column);
+      var order = x < y ? -1 : x > y ? 1 : 0;
+      return asc ? order : -order;
+    });
+    groups.forEach(function (group) {
+      table.insertBefore(group, table.tFoot);
+    });
+  });
+});
+
+function filterTree(li, query) {
+  var children = li.querySelectorAll(":scope > details > ul > li");
+  var anyChild = false;
+  children.forEach(function (child) {
+    anyChild = filterTree(child, query) || anyChild;
+  });
+  var name = li.querySelector(".name");
+  var matches = query === "" ||
+    (name !== null && name.textContent.toLowerCase().indexOf(query) !== -1);
+  li.classList.toggle("hidden", !matches && !anyChild);
+  var details = li.querySelector(":scope > details");
+  if (details !== null && query !== "") details.open = anyChild;
+  return matches || anyChild;
+}
+
+document.querySelectorAll("input.search").forEach(function (input) {
+  input.addEventListener("input", function () {
+    var query = input.value.toLowerCase();
+    document.querySelectorAll("table.sortable tbody").forEach(function (group) {
+      var matches = Array.prototype.some.call(group.querySelectorAll("td.name"), function (td) {
+        return td.textContent.toLowerCase().indexOf(query) !== -1;
+      });
+      group.classList.toggle("hidden", !matches);
+    });
+    document.querySelectorAll(".tree > ul > li").forEach(function (li) {
+      filterTree(li, query);
+    });
+  });
+});
+"#;
+
+/// Escape the given string so it can be used as HTML text or attribute value.
+struct Escaped<'a>(&'a str);
+
+impl<'a> fmt::Display for Escaped<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => write!(f, "&amp;")?,
+                '<' => write!(f, "&lt;")?,
+                '>' => write!(f, "&gt;")?,
+                '"' => write!(f, "&quot;")?,
+                '\'' => write!(f, "&#39;")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single cell in a table row or tree node.
+#[derive(Debug, Clone)]
+pub enum Cell<'a> {
+    /// An item name. These are what the search box filters on.
+    Name(&'a str),
+    /// Some other text, such as the name of a summary row.
+    Text(&'a str),
+    /// A placeholder for a number that is not known.
+    Unknown,
+    /// A size in bytes.
+    Bytes(i64),
+    /// A size delta in bytes, which is always displayed with its sign.
+    Delta(i64),
+    /// A percentage.
+    Percent(f64),
+    /// A limit on a size in bytes, which may be fractional when it is a
+    /// percentage of another size. Limits on deltas are displayed with their
+    /// sign.
+    Limit { bytes: f64, delta: bool },
+}
+
+impl<'a> Cell<'a> {
+    fn write(&self, w: &mut dyn io::Write, tag: &str) -> io::Result<()> {
+        match *self {
+            Cell::Name(name) => write!(w, "<{} class=\"name\">{}</{0}>", tag, Escaped(name)),
+            Cell::Text(text) => write!(w, "<{}>{}</{0}>", tag, Escaped(text)),
+            Cell::Unknown => write!(w, "<{} class=\"num\">...</{0}>", tag),
+            Cell::Bytes(size) => write!(
+                w,
+                "<{} class=\"num\" data-value=\"{}\">{}</{0}>",
+                tag, size, size
+            ),
+            Cell::Delta(delta) => write!(
+                w,
+                "<{} class=\"num\" data-value=\"{}\">{:+}</{0}>",
+                tag, delta, delta
+            ),
+            Cell::Percent(percent) => write!(
+                w,
+                "<{} class=\"num\" data-value=\"{}\">{:.2}%</{0}>",
+                tag, percent, percent
+            ),
+            Cell::Limit { bytes, delta: true } => write!(
+                w,
+                "<{} class=\"num\" data-value=\"{}\">{:+}</{0}>",
+                tag, bytes, bytes
+            ),
+            Cell::Limit {
+                bytes,
+                delta: false,
+            } => write!(
+                w,
+                "<{} class=\"num\" data-value=\"{}\">{}</{0}>",
+                tag, bytes, bytes
+            ),
+        }
+    }
+}
+
+/// Start a new self-contained HTML page with the given title. The page is
+/// finished when the returned `Page` is dropped.
+pub fn page<'a>(w: &'a mut dyn io::Write, title: &str) -> io::Result<Page<'a>> {
+    writeln!(w, "<!DOCTYPE html>")?;
+    writeln!(w, "<html>")?;
+    writeln!(w, "<head>")?;
+    writeln!(w, "<meta charset=\"utf-8\">")?;
+    writeln!(w, "<title>{}</title>", Escaped(title))?;
+    writeln!(w, "<style>{}</style>", STYLE)?;
+    writeln!(w, "</head>")?;
+    writeln!(w, "<body>")?;
+    writeln!(w, "<h1>{}</h1>", Escaped(title))?;
+    writeln!(
+        w,
+        "<input class=\"search\" type=\"search\" placeholder=\"Search item names\">"
+    )?;
+    Ok(Page { w })
+}
+
+pub struct Page<'a> {
+    w: &'a mut dyn io::Write,
+}
+
+impl<'a> Drop for Page<'a> {
+    fn drop(&mut self) {
+        let _ = writeln!(self.w, "<script>{}</script>", SCRIPT);
+        let _ = writeln!(self.w, "</body>");
+        let _ = writeln!(self.w, "</html>");
+    }
+}
+
+impl<'a> Page<'a> {
+    /// Add a paragraph of text to the page.
+    pub fn paragraph(&mut self, text: &str) -> io::Result<()> {
+        writeln!(self.w, "<p>{}</p>", Escaped(text))
+    }
+
+    /// Start a sortable table with the given column headers.
+    pub fn table<'b>(&'b mut self, header: &[&str]) -> io::Result<Table<'b>> {
+        writeln!(self.w, "<table class=\"sortable\">")?;
+        write!(self.w, "<thead><tr>")?;
+        for h in header {
+            write!(self.w, "<th>{}</th>", Escaped(h))?;
+        }
+        writeln!(self.w, "</tr></thead>")?;
+        Ok(Table {
+            w: &mut *self.w,
+            in_group: false,
+            in_footer: false,
+        })
+    }
+
+    /// Start a collapsible tree, with the given column headers.
+    pub fn tree<'b>(&'b mut self, header: &[&str]) -> io::Result<Tree<'b>> {
+        writeln!(self.w, "<div class=\"tree\">")?;
+        write!(self.w, "<div class=\"header\">")?;
+        for (i, h) in header.iter().enumerate() {
+            if i + 1 == header.len() {
+                write!(self.w, "<span>{}</span>", Escaped(h))?;
+            } else {
+                write!(self.w, "<span class=\"num\">{}</span>", Escaped(h))?;
+            }
+        }
+        writeln!(self.w, "</div>")?;
+        writeln!(self.w, "<ul>")?;
+        Ok(Tree { w: &mut *self.w })
+    }
+}
+
+/// A sortable table. Rows are sorted together with their nested rows, and
+/// summary rows always stay at the bottom.
+pub struct Table<'a> {
+    w: &'a mut dyn io::Write,
+    in_group: bool,
+    in_footer: bool,
+}
+
+impl<'a> Drop for Table<'a> {
+    fn drop(&mut self) {
+        if self.in_group {
+            let _ = writeln!(self.w, "</tbody>");
+        }
+        if self.in_footer {
+            let _ = writeln!(self.w, "</tfoot>");
+        }
+        let _ = writeln!(self.w, "</table>");
+    }
+}
+
+impl<'a> Table<'a> {
+    fn write_row(&mut self, class: Option<&str>, cells: &[Cell]) -> io::Result<()> {
+        match class {
+            Some(class) => write!(self.w, "<tr class=\"{}\">", class)?,
+            None => write!(self.w, "<tr>")?,
+        }
+        for cell in cells {
+            cell.write(self.w, "td")?;
+        }
+        writeln!(self.w, "</tr>")
+    }
+
+    /// Add a row to the table.
+    pub fn row(&mut self, cells: &[Cell]) -> io::Result<()> {
+        assert!(!self.in_footer, "rows must come before summary rows");
+        if self.in_group {
+            writeln!(self.w, "</tbody>")?;
+        }
+        writeln!(self.w, "<tbody>")?;
+        self.in_group = true;
+        self.write_row(None, cells)
+    }
+
+    /// Add a row nested beneath the last row added with `row`. It is kept
+    /// together with that row when the table is sorted.
+    pub fn nested_row(&mut self, cells: &[Cell]) -> io::Result<()> {
+        assert!(self.in_group, "nested rows must follow a row");
+        self.write_row(Some("nested"), cells)
+    }
+
+    /// Add a summary row, such as the total, to the bottom of the table.
+    pub fn summary_row(&mut self, cells: &[Cell]) -> io::Result<()> {
+        if self.in_group {
+            writeln!(self.w, "</tbody>")?;
+            self.in_group = false;
+        }
+        if !self.in_footer {
+            writeln!(self.w, "<tfoot>")?;
+            self.in_footer = true;
+        }
+        self.write_row(None, cells)
+    }
+}
+
+/// A tree whose nodes can be expanded and collapsed.
+pub struct Tree<'a> {
+    w: &'a mut dyn io::Write,
+}
+
+impl<'a> Drop for Tree<'a> {
+    fn drop(&mut self) {
+        let _ = writeln!(self.w, "</ul>");
+        let _ = writeln!(self.w, "</div>");
+    }
+}
+
+impl<'a> Tree<'a> {
+    /// Add a node without any children.
+    pub fn leaf(&mut self, cells: &[Cell]) -> io::Result<()> {
+        write!(self.w, "<li><div class=\"leaf\">")?;
+        for cell in cells {
+            cell.write(self.w, "span")?;
+        }
+        writeln!(self.w, "</div></li>")
+    }
+
+    /// Start a node with children. Every node added until the matching call
+    /// to `end` is a child of this one.
+    pub fn begin(&mut self, cells: &[Cell]) -> io::Result<()> {
+        write!(self.w, "<li><details><summary>")?;
+        for cell in cells {
+            cell.write(self.w, "span")?;
+        }
+        writeln!(self.w, "</summary>")?;
+        writeln!(self.w, "<ul>")
+    }
+
+    /// Finish the node most recently started with `begin`.
+    pub fn end(&mut self) -> io::Result<()> {
+        writeln!(self.w, "</ul></details></li>")
+    }
+}
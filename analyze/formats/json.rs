@@ -35,6 +35,21 @@ impl JsonPrimitive for u32 {
     }
 }
 
+impl JsonPrimitive for bool {
+    fn json_primitive(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        write!(w, "{}", self)
+    }
+}
+
+impl<P: JsonPrimitive> JsonPrimitive for Option<P> {
+    fn json_primitive(&self, w: &mut dyn io::Write) -> io::Result<()> {
+        match *self {
+            Some(ref p) => p.json_primitive(w),
+            None => write!(w, "null"),
+        }
+    }
+}
+
 pub fn array(w: &mut dyn io::Write) -> io::Result<Array> {
     write!(w, "[")?;
     Ok(Array {
@@ -1,2 +1,5 @@
+pub mod dot;
+pub mod html;
 pub mod json;
+pub mod svg;
 pub mod table;
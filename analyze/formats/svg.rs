@@ -0,0 +1,150 @@
+// A couple methods are dead, but removing them would make the API oddly
+// imbalanced and we might want to use them in some future analysis.
+#![allow(dead_code)]
+
+use std::fmt;
+use std::io;
+
+/// The approximate width of a character in the monospace font we use for
+/// labels, used to decide how much of a label fits inside of a box.
+const CHAR_WIDTH: f64 = 7.0;
+
+/// Escape the given string so it can be used as SVG text or attribute value.
+struct Escaped<'a>(&'a str);
+
+impl<'a> fmt::Display for Escaped<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '&' => write!(f, "&amp;")?,
+                '<' => write!(f, "&lt;")?,
+                '>' => write!(f, "&gt;")?,
+                '"' => write!(f, "&quot;")?,
+                '\'' => write!(f, "&#39;")?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Get a warm fill color for the box with the given name, like a flame graph.
+/// The color is derived from the name, so that the same item is drawn in the
+/// same color across runs.
+pub fn warm_color(name: &str) -> String {
+    // FNV-1a, since we only need something cheap and stable.
+    let hash = name.bytes().fold(0x811c_9dc5_u32, |hash, b| {
+        (hash ^ u32::from(b)).wrapping_mul(0x0100_0193)
+    });
+    let r = 205 + (hash % 50);
+    let g = 80 + ((hash >> 8) % 150);
+    let b = (hash >> 16) % 55;
+    format!("rgb({},{},{})", r, g, b)
+}
+
+/// Start a new SVG document with the given title and dimensions. The document
+/// is finished when the returned `Document` is dropped.
+pub fn document<'a>(
+    w: &'a mut dyn io::Write,
+    title: &str,
+    width: f64,
+    height: f64,
+) -> io::Result<Document<'a>> {
+    writeln!(w, "<?xml version=\"1.0\" standalone=\"no\"?>")?;
+    writeln!(
+        w,
+        "<svg version=\"1.1\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {0} {1}\" \
+         xmlns=\"http://www.w3.org/2000/svg\">",
+        width, height
+    )?;
+    writeln!(w, "<title>{}</title>", Escaped(title))?;
+    writeln!(
+        w,
+        "<style>text {{ font-family: monospace; font-size: 12px; }} \
+         rect {{ stroke: white; stroke-width: 0.5; }}</style>"
+    )?;
+    writeln!(
+        w,
+        "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"white\"/>",
+        width, height
+    )?;
+    Ok(Document { w })
+}
+
+/// The position and dimensions of a box.
+#[derive(Debug, Clone, Copy)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+pub struct Document<'a> {
+    w: &'a mut dyn io::Write,
+}
+
+impl<'a> Drop for Document<'a> {
+    fn drop(&mut self) {
+        let _ = writeln!(self.w, "</svg>");
+    }
+}
+
+impl<'a> Document<'a> {
+    /// Add a line of text at the given position.
+    pub fn text(&mut self, x: f64, y: f64, text: &str) -> io::Result<()> {
+        writeln!(
+            self.w,
+            "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+            x,
+            y,
+            Escaped(text)
+        )
+    }
+
+    /// Add a filled box with a label drawn inside of it, and a tooltip that is
+    /// shown when hovering over it. The label is truncated to fit the box, or
+    /// left out entirely if the box is too narrow.
+    pub fn labeled_rect(
+        &mut self,
+        rect: Rect,
+        fill: &str,
+        label: &str,
+        tooltip: &str,
+    ) -> io::Result<()> {
+        let Rect {
+            x,
+            y,
+            width,
+            height,
+        } = rect;
+        writeln!(self.w, "<g>")?;
+        writeln!(self.w, "<title>{}</title>", Escaped(tooltip))?;
+        writeln!(
+            self.w,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\"/>",
+            x, y, width, height, fill
+        )?;
+
+        let max_chars = ((width - 6.0) / CHAR_WIDTH).floor();
+        if max_chars >= 3.0 {
+            let max_chars = max_chars as usize;
+            let label = if label.chars().count() > max_chars {
+                let mut truncated: String = label.chars().take(max_chars - 2).collect();
+                truncated.push_str("..");
+                truncated
+            } else {
+                label.to_string()
+            };
+            writeln!(
+                self.w,
+                "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+                x + 3.0,
+                y + height - 5.0,
+                Escaped(&label)
+            )?;
+        }
+
+        writeln!(self.w, "</g>")
+    }
+}
@@ -6,6 +6,7 @@
 mod graph_impl;
 
 use frozen::Frozen;
+use serde_derive::{Deserialize, Serialize};
 use std::cmp;
 use std::collections::btree_map;
 use std::collections::{BTreeMap, BTreeSet};
@@ -26,6 +27,18 @@ pub struct ItemsBuilder {
     // Maps the offset some data begins at to its IR item's identifier, and the
     // byte length of the data.
     data: BTreeMap<u32, (Id, u32)>,
+
+    // Maps an item's identifier to the source files it was compiled from, and
+    // how many of its bytes came from each of them.
+    sources: BTreeMap<Id, BTreeMap<String, u32>>,
+
+    // Maps a source file to the name of the crate that it belongs to, when
+    // the debug info says so.
+    source_crates: BTreeMap<String, String>,
+
+    // Maps an item's identifier to the sections of the binary that its bytes
+    // were carved out of, and how many of its bytes came from each of them.
+    sections: BTreeMap<Id, BTreeMap<String, u32>>,
 }
 
 impl ItemsBuilder {
@@ -39,6 +52,9 @@ impl ItemsBuilder {
             edges: Default::default(),
             roots: Default::default(),
             data: Default::default(),
+            sources: Default::default(),
+            source_crates: Default::default(),
+            sections: Default::default(),
         }
     }
 
@@ -66,6 +82,12 @@ impl ItemsBuilder {
         id
     }
 
+    /// Make an item that has already been added to the graph a root.
+    pub fn mark_root(&mut self, id: Id) {
+        debug_assert!(self.items.contains_key(&id), "`id` is not known");
+        self.roots.insert(id);
+    }
+
     /// Add an edge between the given keys that have already been parsed into
     /// items.
     pub fn add_edge(&mut self, from: Id, to: Id) {
@@ -86,11 +108,13 @@ impl ItemsBuilder {
         }
     }
 
-    /// Locate the data section defining memory at the given offset.
+    /// Locate the data segment that contains the memory at the given offset:
+    /// the last one that starts at or before the offset, if it is long enough
+    /// to reach it.
     pub fn get_data(&self, offset: u32) -> Option<Id> {
         self.data
-            .range(offset..)
-            .next()
+            .range(..=offset)
+            .next_back()
             .and_then(
                 |(start, &(id, len))| {
                     if offset < start + len {
@@ -102,6 +126,122 @@ impl ItemsBuilder {
             )
     }
 
+    /// Attribute some of an item's bytes to the source file that they were
+    /// compiled from.
+    pub fn add_source(&mut self, id: Id, file: &str, size: u32) {
+        debug_assert!(self.items.contains_key(&id), "`id` is not known");
+        *self
+            .sources
+            .entry(id)
+            .or_default()
+            .entry(file.to_string())
+            .or_insert(0) += size;
+    }
+
+    /// Record that some of an item's bytes were carved out of the given section
+    /// of the binary.
+    pub fn add_to_section(&mut self, id: Id, section: &str, size: u32) {
+        debug_assert!(self.items.contains_key(&id), "`id` is not known");
+        if size == 0 {
+            return;
+        }
+        *self
+            .sections
+            .entry(id)
+            .or_default()
+            .entry(section.to_string())
+            .or_insert(0) += size;
+    }
+
+    /// Record that all of the bytes of each item in the given section of the
+    /// IR were carved out of the named section of the binary, unless some of
+    /// the item's bytes were already recorded to come from somewhere else.
+    pub fn add_entries_to_section(&mut self, section: usize, name: &str) {
+        let ids: Vec<_> = self
+            .items
+            .range(Id::entry(section, 0)..=Id::section(section))
+            .map(|(&id, item)| (id, item.size))
+            .filter(|(id, _)| !self.sections.contains_key(id))
+            .collect();
+        for (id, size) in ids {
+            self.add_to_section(id, name, size);
+        }
+    }
+
+    /// Record the name of the crate that a source file belongs to.
+    pub fn set_source_crate(&mut self, file: &str, krate: &str) {
+        self.source_crates
+            .insert(file.to_string(), krate.to_string());
+    }
+
+    /// Add the items of a part of the binary that was parsed on its own, such
+    /// as a member of a static archive, under a new item for the whole part,
+    /// and return the part item's `Id`. The part's items are given new `Id`s
+    /// that don't collide with those of the items added so far. The part
+    /// item's size should be that of whatever the part's items don't cover,
+    /// such as the part's header. The part item is not a root; use
+    /// `mark_root` if nothing else refers to it.
+    pub fn add_part<S: Into<String>>(&mut self, name: S, size: u32, part: &Items) -> Id {
+        self.insert_part(name.into(), size, part, false)
+    }
+
+    /// Like `add_part`, but also prefix the names of the part's items with the
+    /// part's name, as in `module "libc" / type[0]`. This is for parts such as
+    /// the modules nested in a component, whose items would otherwise have the
+    /// same names as each other's.
+    pub fn add_qualified_part<S: Into<String>>(&mut self, name: S, size: u32, part: &Items) -> Id {
+        self.insert_part(name.into(), size, part, true)
+    }
+
+    fn insert_part(&mut self, name: String, size: u32, part: &Items, qualify: bool) -> Id {
+        let base = self
+            .items
+            .keys()
+            .filter(|&&id| id != Id::root())
+            .map(|id| id.0 + 1)
+            .max()
+            .unwrap_or(0);
+        let part_id = Id::section(base as usize);
+        let remap = |id: Id| {
+            if id == part.meta_root {
+                part_id
+            } else {
+                Id(base + 1 + id.0, id.1)
+            }
+        };
+
+        let prefix = format!("{} / ", name);
+        self.add_item(Item::new(part_id, name, size, Misc::new()));
+        for item in part.iter().filter(|item| item.id != part.meta_root) {
+            let mut item = item.clone();
+            item.id = remap(item.id);
+            if qualify {
+                item.qualify(&prefix);
+            }
+            self.add_item(item);
+        }
+        for (&from, tos) in part.edges.iter() {
+            for &to in tos {
+                self.add_edge(remap(from), remap(to));
+            }
+        }
+        for (&id, files) in part.sources.iter() {
+            for (file, size) in files {
+                self.add_source(remap(id), file, *size);
+            }
+        }
+        for (file, krate) in part.source_crates.iter() {
+            self.set_source_crate(file, krate);
+        }
+        for (&id, sections) in part.sections.iter() {
+            for (section, size) in sections {
+                self.add_to_section(remap(id), section, *size);
+            }
+        }
+
+        part_id
+    }
+
     /// Return the size of all added items so far
     pub fn size_added(&self) -> u32 {
         self.size_added
@@ -128,6 +268,19 @@ impl ItemsBuilder {
                     .collect(),
             ),
             roots: Frozen::freeze(self.roots),
+            sources: Frozen::freeze(
+                self.sources
+                    .into_iter()
+                    .map(|(id, files)| (id, files.into_iter().collect::<Vec<_>>()))
+                    .collect(),
+            ),
+            source_crates: Frozen::freeze(self.source_crates),
+            sections: Frozen::freeze(
+                self.sections
+                    .into_iter()
+                    .map(|(id, sections)| (id, sections.into_iter().collect::<Vec<_>>()))
+                    .collect(),
+            ),
             meta_root: meta_root_id,
         }
     }
@@ -147,6 +300,9 @@ pub struct Items {
     items: Frozen<BTreeMap<Id, Item>>,
     edges: Frozen<BTreeMap<Id, Vec<Id>>>,
     roots: Frozen<BTreeSet<Id>>,
+    sources: Frozen<BTreeMap<Id, Vec<(String, u32)>>>,
+    source_crates: Frozen<BTreeMap<String, String>>,
+    sections: Frozen<BTreeMap<Id, Vec<(String, u32)>>>,
     meta_root: Id,
 }
 
@@ -188,6 +344,26 @@ impl Items {
         }
     }
 
+    /// Get the source files that an item was compiled from, and how many of
+    /// its bytes came from each of them. This is empty when the binary doesn't
+    /// have the debugging information to tell.
+    pub fn sources(&self, id: Id) -> &[(String, u32)] {
+        self.sources.get(&id).map_or(&[], |files| &files[..])
+    }
+
+    /// Get the name of the crate that a source file belongs to, if the debug
+    /// info says which crate that is.
+    pub fn source_crate(&self, file: &str) -> Option<&str> {
+        self.source_crates.get(file).map(|krate| &krate[..])
+    }
+
+    /// Get the sections of the binary that an item was carved out of, and how
+    /// many of its bytes came from each of them. This is empty for items that
+    /// aren't part of any section, such as headers.
+    pub fn sections(&self, id: Id) -> &[(String, u32)] {
+        self.sections.get(&id).map_or(&[], |sections| &sections[..])
+    }
+
     /// The size of the total binary, containing all items.
     pub fn size(&self) -> u32 {
         self.size
@@ -358,6 +534,89 @@ impl Items {
     }
 }
 
+// The parts of `Items` that are serialized. Predecessors, dominators, and
+// retained sizes are left out, since they can be computed again on demand.
+#[derive(Serialize)]
+struct SerializedItems<'a> {
+    size: u32,
+    items: Vec<&'a Item>,
+    edges: &'a BTreeMap<Id, Vec<Id>>,
+    roots: &'a BTreeSet<Id>,
+    sources: &'a BTreeMap<Id, Vec<(String, u32)>>,
+    source_crates: &'a BTreeMap<String, String>,
+    sections: &'a BTreeMap<Id, Vec<(String, u32)>>,
+}
+
+#[derive(Deserialize)]
+struct DeserializedItems {
+    size: u32,
+    items: Vec<Item>,
+    edges: BTreeMap<Id, Vec<Id>>,
+    roots: BTreeSet<Id>,
+    sources: BTreeMap<Id, Vec<(String, u32)>>,
+    source_crates: BTreeMap<String, String>,
+    sections: BTreeMap<Id, Vec<(String, u32)>>,
+}
+
+impl serde::Serialize for Items {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedItems {
+            size: self.size,
+            items: self.items.values().collect(),
+            edges: &self.edges,
+            roots: &self.roots,
+            sources: &self.sources,
+            source_crates: &self.source_crates,
+            sections: &self.sections,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Items {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Items, D::Error> {
+        use serde::de::Error;
+
+        let serialized = DeserializedItems::deserialize(deserializer)?;
+        let items: BTreeMap<Id, Item> = serialized
+            .items
+            .into_iter()
+            .map(|item| (item.id, item))
+            .collect();
+
+        // Everything else refers to items by their `Id`, and the analyses
+        // assume that those all exist, including the meta root.
+        let known = |id: &Id| items.contains_key(id);
+        let edges_known = serialized
+            .edges
+            .iter()
+            .all(|(from, tos)| known(from) && tos.iter().all(known));
+        if !known(&Id::root())
+            || !edges_known
+            || !serialized.roots.iter().all(known)
+            || !serialized.sources.keys().all(known)
+            || !serialized.sections.keys().all(known)
+        {
+            return Err(D::Error::custom("refers to an item that does not exist"));
+        }
+
+        Ok(Items {
+            size: serialized.size,
+            dominator_tree: None,
+            retained_sizes: None,
+            predecessors: None,
+            immediate_dominators: None,
+            items: Frozen::freeze(items),
+            edges: Frozen::freeze(serialized.edges),
+            roots: Frozen::freeze(serialized.roots),
+            sources: Frozen::freeze(serialized.sources),
+            source_crates: Frozen::freeze(serialized.source_crates),
+            sections: Frozen::freeze(serialized.sections),
+            meta_root: Id::root(),
+        })
+    }
+}
+
 /// An iterator over an item's neighbors.
 #[derive(Debug)]
 pub struct Neighbors<'a> {
@@ -404,7 +663,18 @@ impl<'a> Iterator for Iter<'a> {
 
 /// An item's unique identifier.
 /// (section index, item within that section index)
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize
+)]
 pub struct Id(u32, u32);
 
 impl Id {
@@ -434,7 +704,7 @@ impl Id {
 }
 
 /// An item in the binary.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Item {
     id: Id,
     name: String,
@@ -480,6 +750,18 @@ impl Item {
         }
     }
 
+    /// Prefix this item's name, and its demangled name if it has one.
+    fn qualify(&mut self, prefix: &str) {
+        self.name.insert_str(0, prefix);
+        if let ItemKind::Code(Code {
+            demangled: Some(ref mut demangled),
+            ..
+        }) = self.kind
+        {
+            demangled.insert_str(0, prefix);
+        }
+    }
+
     /// Get this item's kind.
     #[inline]
     pub fn kind(&self) -> &ItemKind {
@@ -511,7 +793,7 @@ impl Ord for Item {
 }
 
 /// The kind of item in the binary.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ItemKind {
     /// Executable code. Function bodies.
     Code(Code),
@@ -562,7 +844,7 @@ impl From<Misc> for ItemKind {
 }
 
 /// Executable code. Function bodies.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Code {
     demangled: Option<String>,
     monomorphization_of: Option<String>,
@@ -677,7 +959,7 @@ impl Code {
 
 /// Data inside the binary that may or may not end up loaded into memory
 /// with the executable code.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Data {
     ty: Option<String>,
 }
@@ -690,7 +972,7 @@ impl Data {
 }
 
 /// Debugging symbols and information, such as DWARF sections.
-#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct DebugInfo;
 
 impl DebugInfo {
@@ -701,7 +983,7 @@ impl DebugInfo {
 }
 
 /// Miscellaneous item. Perhaps metadata. Perhaps something else.
-#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct Misc;
 
 impl Misc {
@@ -37,10 +37,39 @@ pub enum Options {
     #[structopt(name = "diff")]
     Diff(Diff),
 
+    /// Track the size of each item across a series of builds of a binary.
+    #[structopt(name = "history")]
+    History(History),
+
     /// Find and display code and data that is not transitively referenced by
     /// any exports or public functions.
     #[structopt(name = "garbage")]
     Garbage(Garbage),
+
+    /// List the crates, directories, or source files whose code takes up the
+    /// most space in a binary.
+    #[structopt(name = "sources")]
+    Sources(Sources),
+
+    /// List the crates whose functions take up the most space in a binary,
+    /// based on the functions' names.
+    #[structopt(name = "crates")]
+    Crates(Crates),
+
+    /// Group items by rules that match their names, and list the groups that
+    /// take up the most space in a binary.
+    #[structopt(name = "group")]
+    Group(Group),
+
+    /// Save the items parsed from a binary to a snapshot file, which any other
+    /// sub-command can read in place of the binary.
+    #[structopt(name = "snapshot")]
+    Snapshot(Snapshot),
+
+    /// Check a binary against size budgets, and fail if any of them are
+    /// exceeded.
+    #[structopt(name = "check")]
+    Check(Check),
 }
 
 /// List the top code size offenders in a binary.
@@ -58,6 +87,12 @@ pub struct Top {
     #[structopt(long = "mode", default_value = "auto")]
     parse_mode: traits::ParseMode,
 
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
     /// The destination to write the output to. Defaults to `stdout`.
     #[cfg(feature = "cli")]
     #[structopt(short = "o", default_value = "-")]
@@ -89,6 +124,8 @@ impl Default for Top {
             #[cfg(feature = "cli")]
             parse_mode: Default::default(),
             #[cfg(feature = "cli")]
+            debug_info: false,
+            #[cfg(feature = "cli")]
             output_destination: Default::default(),
             #[cfg(feature = "cli")]
             output_format: Default::default(),
@@ -153,6 +190,12 @@ pub struct Dominators {
     #[structopt(long = "mode", default_value = "auto")]
     parse_mode: traits::ParseMode,
 
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
     /// The destination to write the output to. Defaults to `stdout`.
     #[cfg(feature = "cli")]
     #[structopt(short = "o", default_value = "-")]
@@ -243,6 +286,12 @@ pub struct Paths {
     #[structopt(long = "mode", default_value = "auto")]
     parse_mode: traits::ParseMode,
 
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
     /// The destination to write the output to. Defaults to `stdout`.
     #[cfg(feature = "cli")]
     #[structopt(short = "o", default_value = "-")]
@@ -281,6 +330,8 @@ impl Default for Paths {
             #[cfg(feature = "cli")]
             parse_mode: Default::default(),
             #[cfg(feature = "cli")]
+            debug_info: false,
+            #[cfg(feature = "cli")]
             output_destination: Default::default(),
             #[cfg(feature = "cli")]
             output_format: Default::default(),
@@ -377,6 +428,12 @@ pub struct Monos {
     #[structopt(short = "d", long = "mode", default_value = "auto")]
     parse_mode: traits::ParseMode,
 
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
     /// The destination to write the output to. Defaults to `stdout`.
     #[cfg(feature = "cli")]
     #[structopt(short = "o", default_value = "-")]
@@ -428,6 +485,8 @@ impl Default for Monos {
             #[cfg(feature = "cli")]
             parse_mode: Default::default(),
             #[cfg(feature = "cli")]
+            debug_info: false,
+            #[cfg(feature = "cli")]
             output_destination: Default::default(),
             #[cfg(feature = "cli")]
             output_format: Default::default(),
@@ -536,6 +595,12 @@ pub struct Diff {
     #[structopt(long = "mode", default_value = "auto")]
     parse_mode: traits::ParseMode,
 
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
     /// The path to the new version of the input binary.
     #[cfg(feature = "cli")]
     #[structopt(parse(from_os_str))]
@@ -565,6 +630,11 @@ pub struct Diff {
     /// Displays all items. Overrides -n <max_items>
     #[structopt(short = "a", long = "all")]
     all_items: bool,
+
+    /// Diff retained sizes, rather than shallow sizes, and show the items
+    /// whose immediate dominator changed.
+    #[structopt(long = "retained")]
+    retained: bool,
 }
 
 impl Default for Diff {
@@ -575,6 +645,8 @@ impl Default for Diff {
             #[cfg(feature = "cli")]
             parse_mode: Default::default(),
             #[cfg(feature = "cli")]
+            debug_info: false,
+            #[cfg(feature = "cli")]
             new_input: Default::default(),
             #[cfg(feature = "cli")]
             output_destination: Default::default(),
@@ -585,6 +657,7 @@ impl Default for Diff {
             max_items: 20,
             using_regexps: false,
             all_items: false,
+            retained: false,
         }
     }
 }
@@ -615,6 +688,11 @@ impl Diff {
         self.using_regexps
     }
 
+    /// Whether to diff retained sizes, rather than shallow sizes.
+    pub fn retained(&self) -> bool {
+        self.retained
+    }
+
     /// Set the maximum number of items to display.
     pub fn set_max_items(&mut self, n: u32) {
         self.max_items = n;
@@ -625,6 +703,111 @@ impl Diff {
     pub fn set_using_regexps(&mut self, using_regexps: bool) {
         self.using_regexps = using_regexps;
     }
+
+    /// Set whether to diff retained sizes, rather than shallow sizes.
+    pub fn set_retained(&mut self, do_it: bool) {
+        self.retained = do_it;
+    }
+}
+
+/// Track the size of each item across a series of builds of a binary.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+pub struct History {
+    /// The paths to the builds of the input binary, or snapshots of them, from
+    /// oldest to newest.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str), required = true, min_values = 2)]
+    inputs: Vec<path::PathBuf>,
+
+    /// The parse mode for the input binary data.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "mode", default_value = "auto")]
+    parse_mode: traits::ParseMode,
+
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// The maximum number of items to display.
+    #[structopt(short = "n", default_value = "20")]
+    max_items: u32,
+
+    /// Displays all items. Overrides -n <max_items>
+    #[structopt(short = "a", long = "all")]
+    all_items: bool,
+
+    /// Only display the items whose size grew from one build to the next,
+    /// without ever shrinking.
+    #[structopt(long = "growing")]
+    growing: bool,
+}
+
+impl Default for History {
+    fn default() -> History {
+        History {
+            #[cfg(feature = "cli")]
+            inputs: Default::default(),
+            #[cfg(feature = "cli")]
+            parse_mode: Default::default(),
+            #[cfg(feature = "cli")]
+            debug_info: false,
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+
+            max_items: 20,
+            all_items: false,
+            growing: false,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl History {
+    /// Construct a new, default `History`.
+    pub fn new() -> History {
+        History::default()
+    }
+
+    /// The maximum number of items to display.
+    pub fn max_items(&self) -> u32 {
+        if self.all_items {
+            u32::MAX
+        } else {
+            self.max_items
+        }
+    }
+
+    /// Whether to only display the items whose size grew monotonically.
+    pub fn growing(&self) -> bool {
+        self.growing
+    }
+
+    /// Set the maximum number of items to display.
+    pub fn set_max_items(&mut self, n: u32) {
+        self.max_items = n;
+        self.all_items = false;
+    }
+
+    /// Set whether to only display the items whose size grew monotonically.
+    pub fn set_growing(&mut self, growing: bool) {
+        self.growing = growing;
+    }
 }
 
 /// Find and display code and data that is not transitively referenced by any
@@ -643,6 +826,12 @@ pub struct Garbage {
     #[structopt(long = "mode", default_value = "auto")]
     parse_mode: traits::ParseMode,
 
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
     /// The destination to write the output to. Defaults to `stdout`.
     #[cfg(feature = "cli")]
     #[structopt(short = "o", default_value = "-")]
@@ -674,6 +863,8 @@ impl Default for Garbage {
             #[cfg(feature = "cli")]
             parse_mode: Default::default(),
             #[cfg(feature = "cli")]
+            debug_info: false,
+            #[cfg(feature = "cli")]
             output_destination: Default::default(),
             #[cfg(feature = "cli")]
             output_format: Default::default(),
@@ -712,3 +903,391 @@ impl Garbage {
         self.show_data_segments
     }
 }
+
+/// List the crates, directories, or source files whose code takes up the most
+/// space in a binary.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+pub struct Sources {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The parse mode for the input binary data.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "mode", default_value = "auto")]
+    parse_mode: traits::ParseMode,
+
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// What to aggregate code size by: `crate`, `directory`, or `file`.
+    #[structopt(long = "by", default_value = "crate")]
+    group_by: SourceGrouping,
+
+    /// The maximum number of rows to display.
+    #[structopt(short = "n", default_value = "20")]
+    max_items: u32,
+
+    /// Display all rows. Overrides -n <max_items>
+    #[structopt(short = "a", long = "all")]
+    all_items: bool,
+}
+
+impl Default for Sources {
+    fn default() -> Sources {
+        Sources {
+            #[cfg(feature = "cli")]
+            input: Default::default(),
+            #[cfg(feature = "cli")]
+            parse_mode: Default::default(),
+            #[cfg(feature = "cli")]
+            debug_info: false,
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+
+            group_by: SourceGrouping::Crate,
+            max_items: 20,
+            all_items: false,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Sources {
+    /// Construct a new, default `Sources`.
+    pub fn new() -> Sources {
+        Sources::default()
+    }
+
+    /// What to aggregate code size by.
+    pub fn group_by(&self) -> SourceGrouping {
+        self.group_by
+    }
+
+    /// The maximum number of rows to display.
+    pub fn max_items(&self) -> u32 {
+        if self.all_items {
+            u32::MAX
+        } else {
+            self.max_items
+        }
+    }
+
+    /// Set what to aggregate code size by.
+    pub fn set_group_by(&mut self, group_by: SourceGrouping) {
+        self.group_by = group_by;
+    }
+
+    /// Set the maximum number of rows to display.
+    pub fn set_max_items(&mut self, max: u32) {
+        self.max_items = max;
+        self.all_items = false;
+    }
+}
+
+/// What the `sources` analysis aggregates code size by.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SourceGrouping {
+    /// The Rust crate that each source file belongs to.
+    Crate,
+    /// The directory that each source file is in.
+    Directory,
+    /// Each source file.
+    File,
+}
+
+/// List the crates whose functions take up the most space in a binary, based
+/// on the functions' names.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+pub struct Crates {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The parse mode for the input binary data.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "mode", default_value = "auto")]
+    parse_mode: traits::ParseMode,
+
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// The maximum number of crates to display.
+    #[structopt(short = "n", default_value = "20")]
+    max_items: u32,
+
+    /// Display all crates. Overrides -n <max_items>
+    #[structopt(short = "a", long = "all")]
+    all_items: bool,
+
+    /// Sort list by retained size, rather than shallow size.
+    #[structopt(long = "retained")]
+    retained: bool,
+}
+
+impl Default for Crates {
+    fn default() -> Crates {
+        Crates {
+            #[cfg(feature = "cli")]
+            input: Default::default(),
+            #[cfg(feature = "cli")]
+            parse_mode: Default::default(),
+            #[cfg(feature = "cli")]
+            debug_info: false,
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+
+            max_items: 20,
+            all_items: false,
+            retained: false,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Crates {
+    /// Construct a new, default `Crates`.
+    pub fn new() -> Crates {
+        Crates::default()
+    }
+
+    /// The maximum number of crates to display.
+    pub fn max_items(&self) -> u32 {
+        if self.all_items {
+            u32::MAX
+        } else {
+            self.max_items
+        }
+    }
+
+    /// Sort list by retained size, rather than shallow size.
+    pub fn retained(&self) -> bool {
+        self.retained
+    }
+
+    /// Set the maximum number of crates to display.
+    pub fn set_max_items(&mut self, max: u32) {
+        self.max_items = max;
+        self.all_items = false;
+    }
+
+    /// Set whether to sort list by retained size, rather than shallow size.
+    pub fn set_retained(&mut self, do_it: bool) {
+        self.retained = do_it;
+    }
+}
+
+/// Group items by rules that match their names, and list the groups that take
+/// up the most space in a binary.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+#[derive(StructOpt)]
+pub struct Group {
+    /// The path to the input binary to size profile.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The parse mode for the input binary data.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "mode", default_value = "auto")]
+    parse_mode: traits::ParseMode,
+
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// The path to the config file with the rules to group items by.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "c", long = "config", parse(from_os_str))]
+    config: path::PathBuf,
+
+    /// The maximum number of groups to display.
+    #[structopt(short = "n", default_value = "20")]
+    max_items: u32,
+
+    /// Display all groups. Overrides -n <max_items>
+    #[structopt(short = "a", long = "all")]
+    all_items: bool,
+}
+
+impl Default for Group {
+    fn default() -> Group {
+        Group {
+            #[cfg(feature = "cli")]
+            input: Default::default(),
+            #[cfg(feature = "cli")]
+            parse_mode: Default::default(),
+            #[cfg(feature = "cli")]
+            debug_info: false,
+            #[cfg(feature = "cli")]
+            output_destination: Default::default(),
+            #[cfg(feature = "cli")]
+            output_format: Default::default(),
+            #[cfg(feature = "cli")]
+            config: Default::default(),
+
+            max_items: 20,
+            all_items: false,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Group {
+    /// Construct a new, default `Group`.
+    pub fn new() -> Group {
+        Group::default()
+    }
+
+    /// The maximum number of groups to display.
+    pub fn max_items(&self) -> u32 {
+        if self.all_items {
+            u32::MAX
+        } else {
+            self.max_items
+        }
+    }
+
+    /// Set the maximum number of groups to display.
+    pub fn set_max_items(&mut self, max: u32) {
+        self.max_items = max;
+        self.all_items = false;
+    }
+}
+
+/// Save the items parsed from a binary to a snapshot file, which any other
+/// sub-command can read in place of the binary.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+#[derive(StructOpt)]
+pub struct Snapshot {
+    /// The path to the input binary to take a snapshot of.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The parse mode for the input binary data.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "mode", default_value = "auto")]
+    parse_mode: traits::ParseMode,
+
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
+    /// The destination to write the snapshot to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+}
+
+#[wasm_bindgen]
+impl Snapshot {
+    /// Construct a new, default `Snapshot`.
+    pub fn new() -> Snapshot {
+        Snapshot::default()
+    }
+}
+
+/// Check a binary against size budgets, and fail if any of them are exceeded.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+#[derive(StructOpt)]
+pub struct Check {
+    /// The path to the input binary to check.
+    #[cfg(feature = "cli")]
+    #[structopt(parse(from_os_str))]
+    input: path::PathBuf,
+
+    /// The parse mode for the input binary data.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "mode", default_value = "auto")]
+    parse_mode: traits::ParseMode,
+
+    /// Attribute the bytes of DWARF debug info to the compilation units and
+    /// functions that they describe, rather than to whole debug sections.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "debug-info")]
+    debug_info: bool,
+
+    /// The destination to write the output to. Defaults to `stdout`.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "o", default_value = "-")]
+    output_destination: OutputDestination,
+
+    /// The format the output should be written in.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "f", long = "format", default_value = "text")]
+    output_format: traits::OutputFormat,
+
+    /// The path to the budget file with the limits to check.
+    #[cfg(feature = "cli")]
+    #[structopt(short = "c", long = "config", parse(from_os_str))]
+    budget: path::PathBuf,
+
+    /// The path to a baseline version of the binary, or a snapshot of it, to
+    /// check growth against.
+    #[cfg(feature = "cli")]
+    #[structopt(long = "baseline", parse(from_os_str))]
+    baseline: Option<path::PathBuf>,
+}
+
+#[wasm_bindgen]
+impl Check {
+    /// Construct a new, default `Check`.
+    pub fn new() -> Check {
+        Check::default()
+    }
+}
@@ -33,6 +33,10 @@ cfg_if! {
             /// Get the input data parse mode.
             fn parse_mode(&self) -> traits::ParseMode;
 
+            /// Should DWARF debug info be attributed to the compilation units
+            /// and functions that it describes?
+            fn debug_info(&self) -> bool;
+
             /// Get the output destination.
             fn output_destination(&self) -> &OutputDestination;
 
@@ -48,7 +52,13 @@ cfg_if! {
                     Options::Paths(ref paths) => paths.input(),
                     Options::Monos(ref monos) => monos.input(),
                     Options::Diff(ref diff) => diff.input(),
+                    Options::History(ref history) => history.input(),
                     Options::Garbage(ref garbo) => garbo.input(),
+                    Options::Sources(ref sources) => sources.input(),
+                    Options::Crates(ref crates) => crates.input(),
+                    Options::Group(ref group) => group.input(),
+                    Options::Snapshot(ref snapshot) => snapshot.input(),
+                    Options::Check(ref check) => check.input(),
                 }
             }
 
@@ -59,7 +69,30 @@ cfg_if! {
                     Options::Paths(ref paths) => paths.parse_mode(),
                     Options::Monos(ref monos) => monos.parse_mode(),
                     Options::Diff(ref diff) => diff.parse_mode(),
+                    Options::History(ref history) => history.parse_mode(),
                     Options::Garbage(ref garbo) => garbo.parse_mode(),
+                    Options::Sources(ref sources) => sources.parse_mode(),
+                    Options::Crates(ref crates) => crates.parse_mode(),
+                    Options::Group(ref group) => group.parse_mode(),
+                    Options::Snapshot(ref snapshot) => snapshot.parse_mode(),
+                    Options::Check(ref check) => check.parse_mode(),
+                }
+            }
+
+            fn debug_info(&self) -> bool {
+                match *self {
+                    Options::Top(ref top) => top.debug_info(),
+                    Options::Dominators(ref doms) => doms.debug_info(),
+                    Options::Paths(ref paths) => paths.debug_info(),
+                    Options::Monos(ref monos) => monos.debug_info(),
+                    Options::Diff(ref diff) => diff.debug_info(),
+                    Options::History(ref history) => history.debug_info(),
+                    Options::Garbage(ref garbo) => garbo.debug_info(),
+                    Options::Sources(ref sources) => sources.debug_info(),
+                    Options::Crates(ref crates) => crates.debug_info(),
+                    Options::Group(ref group) => group.debug_info(),
+                    Options::Snapshot(ref snapshot) => snapshot.debug_info(),
+                    Options::Check(ref check) => check.debug_info(),
                 }
             }
 
@@ -70,7 +103,13 @@ cfg_if! {
                     Options::Paths(ref paths) => paths.output_destination(),
                     Options::Monos(ref monos) => monos.output_destination(),
                     Options::Diff(ref diff) => diff.output_destination(),
+                    Options::History(ref history) => history.output_destination(),
                     Options::Garbage(ref garbo) => garbo.output_destination(),
+                    Options::Sources(ref sources) => sources.output_destination(),
+                    Options::Crates(ref crates) => crates.output_destination(),
+                    Options::Group(ref group) => group.output_destination(),
+                    Options::Snapshot(ref snapshot) => snapshot.output_destination(),
+                    Options::Check(ref check) => check.output_destination(),
                 }
             }
 
@@ -81,7 +120,13 @@ cfg_if! {
                     Options::Paths(ref paths) => paths.output_format(),
                     Options::Monos(ref monos) => monos.output_format(),
                     Options::Diff(ref diff) => diff.output_format(),
+                    Options::History(ref history) => history.output_format(),
                     Options::Garbage(ref garbo) => garbo.output_format(),
+                    Options::Sources(ref sources) => sources.output_format(),
+                    Options::Crates(ref crates) => crates.output_format(),
+                    Options::Group(ref group) => group.output_format(),
+                    Options::Snapshot(ref snapshot) => snapshot.output_format(),
+                    Options::Check(ref check) => check.output_format(),
                 }
             }
         }
@@ -95,6 +140,10 @@ cfg_if! {
                 self.parse_mode
             }
 
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -113,6 +162,10 @@ cfg_if! {
                 self.parse_mode
             }
 
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -131,6 +184,10 @@ cfg_if! {
                 self.parse_mode
             }
 
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -149,6 +206,10 @@ cfg_if! {
                 self.parse_mode
             }
 
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -167,6 +228,10 @@ cfg_if! {
                 self.parse_mode
             }
 
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -183,6 +248,38 @@ cfg_if! {
             }
         }
 
+        impl CommonCliOptions for History {
+            // The first build is the input that every sub-command parses, and
+            // the rest are parsed by the `history` sub-command itself.
+            fn input(&self) -> &path::Path {
+                &self.inputs[0]
+            }
+
+            fn parse_mode(&self) -> traits::ParseMode {
+                self.parse_mode
+            }
+
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+        }
+
+        impl History {
+            /// The paths to the builds of the input binary, from oldest to
+            /// newest.
+            pub fn inputs(&self) -> &[path::PathBuf] {
+                &self.inputs
+            }
+        }
+
         impl CommonCliOptions for Garbage {
             fn input(&self) -> &path::Path {
                 &self.input
@@ -192,6 +289,32 @@ cfg_if! {
                 self.parse_mode
             }
 
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+        }
+
+        impl CommonCliOptions for Sources {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn parse_mode(&self) -> traits::ParseMode {
+                self.parse_mode
+            }
+
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
             fn output_destination(&self) -> &OutputDestination {
                 &self.output_destination
             }
@@ -201,6 +324,130 @@ cfg_if! {
             }
         }
 
+        impl CommonCliOptions for Crates {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn parse_mode(&self) -> traits::ParseMode {
+                self.parse_mode
+            }
+
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+        }
+
+        impl CommonCliOptions for Group {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn parse_mode(&self) -> traits::ParseMode {
+                self.parse_mode
+            }
+
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+        }
+
+        impl CommonCliOptions for Snapshot {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn parse_mode(&self) -> traits::ParseMode {
+                self.parse_mode
+            }
+
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            // Snapshots are always written in their own format.
+            fn output_format(&self) -> traits::OutputFormat {
+                traits::OutputFormat::default()
+            }
+        }
+
+        impl CommonCliOptions for Check {
+            fn input(&self) -> &path::Path {
+                &self.input
+            }
+
+            fn parse_mode(&self) -> traits::ParseMode {
+                self.parse_mode
+            }
+
+            fn debug_info(&self) -> bool {
+                self.debug_info
+            }
+
+            fn output_destination(&self) -> &OutputDestination {
+                &self.output_destination
+            }
+
+            fn output_format(&self) -> traits::OutputFormat {
+                self.output_format
+            }
+        }
+
+        impl Group {
+            /// The path to the config file with the rules to group items by.
+            pub fn config(&self) -> &path::Path {
+                &self.config
+            }
+        }
+
+        impl Check {
+            /// The path to the budget file with the limits to check.
+            pub fn budget(&self) -> &path::Path {
+                &self.budget
+            }
+
+            /// The path to the baseline version of the binary, if any.
+            pub fn baseline(&self) -> Option<&path::Path> {
+                self.baseline.as_deref()
+            }
+        }
+
+        impl FromStr for SourceGrouping {
+            type Err = traits::Error;
+
+            fn from_str(s: &str) -> Result<Self, traits::Error> {
+                match s {
+                    "crate" => Ok(SourceGrouping::Crate),
+                    "directory" => Ok(SourceGrouping::Directory),
+                    "file" => Ok(SourceGrouping::File),
+                    _ => Err(traits::Error::with_msg(format!(
+                        "Unknown source grouping: {}",
+                        s
+                    ))),
+                }
+            }
+        }
+
         /// Where to output results.
         #[derive(Clone, Debug)]
         pub enum OutputDestination {
@@ -0,0 +1,55 @@
+//! Find the functions that AArch64 machine code refers to.
+//!
+//! Every instruction is four bytes long and aligned, so we can decode them
+//! one after another. Instructions are always little endian, even on big
+//! endian targets.
+
+/// Return the addresses that the machine code at the given address might
+/// refer to, either by calling or branching to it, or by taking its address.
+pub(super) fn references(code: &[u8], address: u64) -> Vec<u64> {
+    let mut targets = vec![];
+
+    let insns: Vec<u32> = code
+        .chunks_exact(4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+
+    for (i, &insn) in insns.iter().enumerate() {
+        let pc = address.wrapping_add(i as u64 * 4);
+
+        // `b imm26` and `bl imm26`: a branch, or branch with link, to a
+        // word offset from the current instruction.
+        if insn & 0x7C00_0000 == 0x1400_0000 {
+            let offset = sign_extend(insn & 0x03FF_FFFF, 26) << 2;
+            targets.push(pc.wrapping_add(offset as u64));
+            continue;
+        }
+
+        // `adrp xd, page` followed by `add xd, xd, #imm12`, which is how
+        // functions' addresses are taken.
+        if insn & 0x9F00_0000 == 0x9000_0000 {
+            let rd = insn & 0x1F;
+            let immlo = (insn >> 29) & 0x3;
+            let immhi = (insn >> 5) & 0x7_FFFF;
+            let page = sign_extend((immhi << 2) | immlo, 21) << 12;
+            let page = (pc & !0xFFF).wrapping_add(page as u64);
+
+            if let Some(&next) = insns.get(i + 1) {
+                // `add xd, xn, #imm12` with no shift, where `xn` is `xd`.
+                let is_add = next & 0xFFC0_0000 == 0x9100_0000;
+                if is_add && (next >> 5) & 0x1F == rd {
+                    let imm12 = (next >> 10) & 0xFFF;
+                    targets.push(page.wrapping_add(u64::from(imm12)));
+                }
+            }
+        }
+    }
+
+    targets
+}
+
+/// Sign extend the low `bits` bits of the given value.
+fn sign_extend(value: u32, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    (i64::from(value) << shift) >> shift
+}
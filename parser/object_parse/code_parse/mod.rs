@@ -0,0 +1,175 @@
+use object::target_lexicon::Architecture;
+use object::{self, Object, ObjectSection, SectionKind};
+use twiggy_ir as ir;
+use twiggy_traits as traits;
+
+use super::functions::Functions;
+
+mod aarch64;
+mod x86;
+
+/// Add edges for the calls between functions, and the places functions'
+/// addresses are taken, that are found in the machine code and relocations.
+pub(super) fn parse_edges(
+    items: &mut ir::ItemsBuilder,
+    file: &object::File,
+    functions: &Functions,
+) -> Result<(), traits::Error> {
+    parse_instruction_edges(items, file, functions);
+    parse_relocation_edges(items, file, functions);
+    parse_static_data_roots(items, file, functions);
+    Ok(())
+}
+
+/// Decode the machine code of each function, looking for direct calls and
+/// references to other functions and static data.
+fn parse_instruction_edges(
+    items: &mut ir::ItemsBuilder,
+    file: &object::File,
+    functions: &Functions,
+) {
+    let architecture = file.architecture();
+    let sections: Vec<_> = file
+        .sections()
+        .filter(|section| section.kind() == SectionKind::Text)
+        .collect();
+
+    for (range, id) in functions.ranges() {
+        let code = sections
+            .iter()
+            .filter_map(|section| section.data_range(range.begin, range.end - range.begin))
+            .next();
+        let code = match code {
+            Some(code) => code,
+            None => continue,
+        };
+
+        let targets = match architecture {
+            Architecture::X86_64 => x86::references(code, range.begin, true),
+            Architecture::I386 | Architecture::I586 | Architecture::I686 => {
+                x86::references(code, range.begin, false)
+            }
+            Architecture::Aarch64(_) => aarch64::references(code, range.begin),
+            // We don't know how to decode this architecture's machine code,
+            // so we will have to make do with the call sites described by the
+            // debugging information, and relocations.
+            _ => return,
+        };
+
+        for target in targets {
+            if let Some(callee) = functions.referenced_by(target) {
+                if callee != id {
+                    items.add_edge(id, callee);
+                }
+            }
+        }
+    }
+}
+
+/// Add edges for relocations that refer to functions or static data. If the
+/// relocation is applied inside of a function's machine code or a static data
+/// object, that item refers to the relocation's target. Otherwise, the
+/// target's address is stored somewhere we don't know about, such as an
+/// anonymous vtable, and we must assume that it is used.
+fn parse_relocation_edges(
+    items: &mut ir::ItemsBuilder,
+    file: &object::File,
+    functions: &Functions,
+) {
+    for section in file.sections() {
+        for (offset, relocation) in section.relocations() {
+            // Dynamic relocations, such as `R_X86_64_RELATIVE`, use the null
+            // symbol, whose address is zero, and put the whole target
+            // address in the addend.
+            let (base, symbol) = match relocation.target() {
+                object::RelocationTarget::Symbol(index) => match file.symbol_by_index(index) {
+                    Some(ref symbol) if index.0 == 0 => (symbol.address(), None),
+                    Some(ref symbol) if !symbol.is_undefined() => {
+                        (symbol.address(), Some(symbol.address()))
+                    }
+                    _ => continue,
+                },
+                object::RelocationTarget::Section(index) => match file.section_by_index(index) {
+                    Some(section) => (section.address(), None),
+                    None => continue,
+                },
+            };
+
+            // Relocations relative to the place they are applied have an
+            // addend that accounts for the size of the instruction, so the
+            // target might be either the symbol itself or the symbol plus
+            // the addend.
+            let address = base.wrapping_add(relocation.addend() as u64);
+            let target = functions
+                .starting_at(address)
+                .or_else(|| symbol.and_then(|symbol| functions.referenced_by(symbol)))
+                .or_else(|| functions.data_containing(address));
+            let target = match target {
+                Some(target) => target,
+                None => continue,
+            };
+
+            let place = section.address().wrapping_add(offset);
+            let source = functions
+                .containing(place)
+                .or_else(|| functions.data_containing(place));
+            match source {
+                Some(id) if id != target => items.add_edge(id, target),
+                Some(_) => {}
+                None => items.mark_root(target),
+            }
+        }
+    }
+}
+
+/// Look for functions' addresses in static data. When the address is stored
+/// in a static data object, that object refers to the function, otherwise the
+/// function is added as a root. This finds the entries of vtables and other
+/// tables of function pointers in binaries that don't have relocations for
+/// them, since their addresses were resolved at link time.
+fn parse_static_data_roots(
+    items: &mut ir::ItemsBuilder,
+    file: &object::File,
+    functions: &Functions,
+) {
+    let pointer_size = if file.is_64() { 8 } else { 4 };
+    let little_endian = file.is_little_endian();
+
+    for section in file.sections() {
+        match section.kind() {
+            SectionKind::Text | SectionKind::UninitializedData | SectionKind::UninitializedTls => {
+                continue
+            }
+            _ if section.address() == 0 => continue,
+            _ => {}
+        }
+
+        let data = section.data();
+        for (i, word) in data.chunks_exact(pointer_size).enumerate() {
+            let mut bytes = [0; 8];
+            let value = if little_endian {
+                bytes[..pointer_size].copy_from_slice(word);
+                u64::from_le_bytes(bytes)
+            } else {
+                bytes[8 - pointer_size..].copy_from_slice(word);
+                u64::from_be_bytes(bytes)
+            };
+
+            let place = section.address() + (i * pointer_size) as u64;
+            match functions.data_containing(place) {
+                Some(source) => {
+                    if let Some(target) = functions.referenced_by(value) {
+                        if source != target {
+                            items.add_edge(source, target);
+                        }
+                    }
+                }
+                None => {
+                    if let Some(id) = functions.starting_at(value) {
+                        items.mark_root(id);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Find the functions that x86 and x86-64 machine code refers to.
+//!
+//! Instructions are variable length, and we don't want to pull in a whole
+//! disassembler, so rather than decoding instructions one after another, we
+//! look for the encodings of the instructions we care about at every offset.
+//! This finds bogus references inside of other instructions' operands too,
+//! but those are filtered out by only accepting references to addresses
+//! that a function starts at.
+
+/// The opcode of `call rel32`.
+const CALL_REL32: u8 = 0xE8;
+
+/// The opcode of `jmp rel32`, which is used for tail calls.
+const JMP_REL32: u8 = 0xE9;
+
+/// The opcode of `lea`.
+const LEA: u8 = 0x8D;
+
+/// Return the addresses that the machine code at the given address might
+/// refer to, either by calling or jumping to it, or by taking its address.
+pub(super) fn references(code: &[u8], address: u64, is_64: bool) -> Vec<u64> {
+    let mut targets = vec![];
+
+    // Compute the address that a 32-bit displacement at the given offset
+    // refers to, relative to the end of the instruction.
+    let relative = |disp_offset: usize, insn_end: usize| -> Option<u64> {
+        let disp = code.get(disp_offset..disp_offset + 4)?;
+        let disp = i32::from_le_bytes([disp[0], disp[1], disp[2], disp[3]]);
+        let next = address.wrapping_add(insn_end as u64);
+        Some(next.wrapping_add(disp as i64 as u64))
+    };
+
+    for (i, &byte) in code.iter().enumerate() {
+        match byte {
+            CALL_REL32 | JMP_REL32 => targets.extend(relative(i + 1, i + 5)),
+
+            // `lea reg, [rip + disp32]`, which is how position independent
+            // code takes the address of a function. This needs a REX.W
+            // prefix, and a ModRM byte with `mod = 00` and `r/m = 101`.
+            0x48..=0x4F if is_64 && code.get(i + 1) == Some(&LEA) => {
+                if let Some(modrm) = code.get(i + 2) {
+                    if modrm & 0xC7 == 0x05 {
+                        targets.extend(relative(i + 3, i + 7));
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    targets
+}
@@ -1,14 +1,18 @@
 use gimli;
+use gimli::ReaderOffset;
 use twiggy_ir as ir;
 use twiggy_traits as traits;
 
+use super::debug_info::DebugInfo;
 use super::die_parse;
+use super::functions::Functions;
 
 pub(super) fn parse_items<R: gimli::Reader>(
     items: &mut ir::ItemsBuilder,
     dwarf: &gimli::Dwarf<R>,
     unit: &gimli::Unit<R>,
     unit_id: usize,
+    functions: &mut Functions,
 ) -> Result<(), traits::Error> {
     // Initialize an entry ID counter.
     let mut entry_id = 0;
@@ -33,7 +37,7 @@ pub(super) fn parse_items<R: gimli::Reader>(
             break;
         }
 
-        die_parse::parse_items(items, dwarf, unit, unit_id, entry, entry_id)?;
+        die_parse::parse_items(items, dwarf, unit, unit_id, entry, entry_id, functions)?;
         entry_id += 1;
     }
 
@@ -43,10 +47,10 @@ pub(super) fn parse_items<R: gimli::Reader>(
 pub(super) fn parse_edges<R: gimli::Reader>(
     items: &mut ir::ItemsBuilder,
     unit: &gimli::Unit<R>,
-    unit_id: usize,
+    functions: &Functions,
 ) -> Result<(), traits::Error> {
-    // Initialize an entry ID counter.
-    let mut entry_id = 0;
+    // The functions enclosing the current entry, along with their depths.
+    let mut callers: Vec<(isize, ir::Id)> = vec![];
 
     // Create an entries cursor, and move it to the root.
     let mut die_cursor = unit.entries();
@@ -68,10 +72,146 @@ pub(super) fn parse_edges<R: gimli::Reader>(
             break;
         }
 
-        let _ir_id = ir::Id::entry(unit_id, entry_id);
-        die_parse::parse_edges(items, entry)?;
+        while let Some(&(d, _)) = callers.last() {
+            if d < depth {
+                break;
+            }
+            callers.pop();
+        }
+        let caller = callers.last().map(|&(_, id)| id);
+        die_parse::parse_edges(items, unit, entry, caller, functions)?;
+
+        if entry.tag() == gimli::DW_TAG_subprogram {
+            if let Some(id) = functions.by_entry(entry.offset().to_unit_section_offset(unit)) {
+                callers.push((depth, id));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub(super) fn parse_function_entries<R: gimli::Reader>(
+    dwarf: &gimli::Dwarf<R>,
+    unit: &gimli::Unit<R>,
+    functions: &mut Functions,
+) -> Result<(), traits::Error> {
+    // Create an entries cursor, and move it to the root.
+    let mut die_cursor = unit.entries();
+
+    if die_cursor.next_dfs()?.is_none() {
+        let e = traits::Error::with_msg(
+            "Unexpected error while traversing debugging information entries.",
+        );
+        return Err(e);
+    }
+
+    // Parse the contained debugging information entries in depth-first order.
+    let mut depth = 0;
+    while let Some((delta, entry)) = die_cursor.next_dfs()? {
+        // Update depth value, and break out of the loop when we
+        // return to the original starting position.
+        depth += delta;
+        if depth <= 0 {
+            break;
+        }
+
+        die_parse::parse_function_entry(dwarf, unit, entry, functions)?;
+    }
+
+    Ok(())
+}
+
+pub(super) fn parse_debug_info<R: gimli::Reader>(
+    dwarf: &gimli::Dwarf<R>,
+    unit: &gimli::Unit<R>,
+    unit_id: usize,
+    functions: &Functions,
+    debug_info: &mut DebugInfo,
+) -> Result<(), traits::Error> {
+    // Everything that isn't inside of a function belongs to the compilation
+    // unit itself, including its header and line number program.
+    let name = match unit.name {
+        Some(ref name) => name.to_string_lossy()?.into_owned(),
+        None => format!("{}", unit_id),
+    };
+    let unit_owner =
+        debug_info.add_owner(format!("debug info for compilation unit '{}'", name), None);
+    debug_info.add_bytes(
+        unit_owner,
+        ".debug_info",
+        unit.header.header_size().into_u64(),
+    );
+    if let Some(ref program) = unit.line_program {
+        let header = program.header();
+        let size =
+            header.unit_length().into_u64() + u64::from(header.format().initial_length_size());
+        debug_info.add_bytes(unit_owner, ".debug_line", size);
+    }
+
+    // The owners of the current entry and the entries enclosing it, along with
+    // their depths.
+    let mut owners: Vec<(isize, usize)> = vec![(0, unit_owner)];
+
+    // Each entry's bytes extend up to the start of the next entry, so we need
+    // to remember where the last entry started, and who owns it.
+    let mut last: (u64, usize);
+
+    // Create an entries cursor, and move it to the root.
+    let mut die_cursor = unit.entries();
+
+    match die_cursor.next_dfs()? {
+        Some((_, root)) => {
+            die_parse::parse_debug_info(dwarf, unit, root, unit_owner, debug_info)?;
+            last = (root.offset().0.into_u64(), unit_owner);
+        }
+        None => {
+            let e = traits::Error::with_msg(
+                "Unexpected error while traversing debugging information entries.",
+            );
+            return Err(e);
+        }
+    }
+
+    // Parse the contained debugging information entries in depth-first order.
+    let mut entry_id = 0;
+    let mut depth = 0;
+    while let Some((delta, entry)) = die_cursor.next_dfs()? {
+        // Update depth value, and break out of the loop when we
+        // return to the original starting position.
+        depth += delta;
+        if depth <= 0 {
+            break;
+        }
+
+        while let Some(&(d, _)) = owners.last() {
+            if d < depth {
+                break;
+            }
+            owners.pop();
+        }
+        let mut owner = owners.last().map_or(unit_owner, |&(_, owner)| owner);
+
+        if entry.tag() == gimli::DW_TAG_subprogram {
+            let offset = entry.offset().to_unit_section_offset(unit);
+            let is_definition = entry.attr(gimli::DW_AT_low_pc)?.is_some()
+                || entry.attr(gimli::DW_AT_ranges)?.is_some();
+            if let (Some(code), true) = (functions.by_entry(offset), is_definition) {
+                let name = die_parse::function_name(dwarf, unit, unit_id, entry, entry_id)?;
+                owner = debug_info.add_owner(format!("debug info for {}", name), Some(code));
+                owners.push((depth, owner));
+            }
+        }
+
+        let offset = entry.offset().0.into_u64();
+        debug_info.add_bytes(last.1, ".debug_info", offset - last.0);
+        die_parse::parse_debug_info(dwarf, unit, entry, owner, debug_info)?;
+        last = (offset, owner);
         entry_id += 1;
     }
 
+    let end = unit.header.length_including_self().into_u64();
+    debug_info.add_bytes(last.1, ".debug_info", end.saturating_sub(last.0));
+
     Ok(())
 }
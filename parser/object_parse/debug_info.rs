@@ -0,0 +1,130 @@
+use std::collections::{BTreeMap, HashMap};
+
+use twiggy_ir as ir;
+
+/// The bytes of the DWARF sections that are attributed to each compilation
+/// unit and function. Each of these owners gets an `ir::DebugInfo` item, and
+/// the attributed bytes are carved out of the sections' items.
+#[derive(Debug)]
+pub(super) struct DebugInfo {
+    // The section of the IR that the debug info items are given entries in.
+    section: usize,
+
+    owners: Vec<Owner>,
+
+    // The `.debug_str` strings that are already attributed to an owner, as a
+    // map from the offset each string ends at to the offset it starts at.
+    // Strings are shared, so only their first user pays for them. Linkers
+    // also merge strings that are suffixes of other strings, so a later user
+    // may only need to pay for a longer prefix.
+    strings: HashMap<u64, u64>,
+
+    // The owners of the range lists, keyed by their section and offset. Range
+    // lists don't record their own lengths, so we take each one to extend up
+    // to the next one, or the end of the section.
+    range_lists: BTreeMap<(&'static str, u64), usize>,
+}
+
+#[derive(Debug)]
+struct Owner {
+    id: ir::Id,
+    name: String,
+    code: Option<ir::Id>,
+    sizes: HashMap<&'static str, u64>,
+}
+
+impl DebugInfo {
+    /// Create a new, empty set of owners, whose items will be given entries in
+    /// the given section of the IR.
+    pub fn new(section: usize) -> DebugInfo {
+        DebugInfo {
+            section,
+            owners: vec![],
+            strings: HashMap::new(),
+            range_lists: BTreeMap::new(),
+        }
+    }
+
+    /// Add a new owner of debugging information. If the owner is a function,
+    /// `code` is its item, which is given an edge to the new debug info item.
+    /// Returns the owner's index, for use with the `add_*` methods.
+    pub fn add_owner(&mut self, name: String, code: Option<ir::Id>) -> usize {
+        self.owners.push(Owner {
+            id: ir::Id::entry(self.section, self.owners.len()),
+            name,
+            code,
+            sizes: HashMap::new(),
+        });
+        self.owners.len() - 1
+    }
+
+    /// Attribute some bytes of the given section to an owner.
+    pub fn add_bytes(&mut self, owner: usize, section: &'static str, size: u64) {
+        *self.owners[owner].sizes.entry(section).or_insert(0) += size;
+    }
+
+    /// Attribute the string at the given `.debug_str` offset to an owner, if
+    /// it isn't already attributed to someone else.
+    pub fn add_string(&mut self, owner: usize, offset: u64, size: u64) {
+        let start = self.strings.entry(offset + size).or_insert(offset + size);
+        if offset < *start {
+            let size = *start - offset;
+            *start = offset;
+            self.add_bytes(owner, ".debug_str", size);
+        }
+    }
+
+    /// Attribute the range list at the given offset of `.debug_ranges` or
+    /// `.debug_rnglists` to an owner, if it isn't already attributed to
+    /// someone else.
+    pub fn add_range_list(&mut self, owner: usize, section: &'static str, offset: u64) {
+        self.range_lists.entry((section, offset)).or_insert(owner);
+    }
+
+    /// Add the debug info items, and return the number of bytes that were
+    /// attributed to them, keyed by the name of the section they came from.
+    /// `binary_section` gets the name and size of the binary's section with
+    /// the given DWARF name, since Mach-O names them differently.
+    pub fn finish<F>(
+        mut self,
+        items: &mut ir::ItemsBuilder,
+        binary_section: F,
+    ) -> HashMap<&'static str, u64>
+    where
+        F: Fn(&str) -> (String, u64),
+    {
+        let range_lists: Vec<_> = self.range_lists.iter().map(|(&k, &v)| (k, v)).collect();
+        for (i, &((section, offset), owner)) in range_lists.iter().enumerate() {
+            let end = match range_lists.get(i + 1) {
+                Some(&((next_section, next_offset), _)) if next_section == section => next_offset,
+                _ => binary_section(section).1,
+            };
+            self.add_bytes(owner, section, end.saturating_sub(offset));
+        }
+
+        let mut attributed = HashMap::new();
+        for owner in self.owners {
+            let size: u64 = owner.sizes.values().sum();
+            if size == 0 {
+                continue;
+            }
+
+            let item = ir::Item::new(owner.id, owner.name, size as u32, ir::DebugInfo::new());
+            match owner.code {
+                Some(code) => {
+                    items.add_item(item);
+                    items.add_edge(code, owner.id);
+                }
+                None => {
+                    items.add_root(item);
+                }
+            }
+
+            for (section, size) in owner.sizes {
+                *attributed.entry(section).or_insert(0) += size;
+                items.add_to_section(owner.id, &binary_section(section).0, size as u32);
+            }
+        }
+        attributed
+    }
+}
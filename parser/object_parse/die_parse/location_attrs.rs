@@ -48,6 +48,29 @@ impl<R: gimli::Reader> DieLocationAttributes<R> {
         }
     }
 
+    /// Compute the ranges of machine code addresses occupied by the entity
+    /// this DIE describes. This is empty if the DIE does not describe an
+    /// entity with a location in the binary.
+    pub fn address_ranges(
+        &self,
+        dwarf: &gimli::Dwarf<R>,
+        unit: &gimli::Unit<R>,
+    ) -> Result<Vec<gimli::Range>, traits::Error> {
+        if let Some(size) = self.contiguous_entity_size()? {
+            // `contiguous_entity_size` only returns a size if the DIE has a
+            // DW_AT_low_pc attribute, so this is always `Some`.
+            let begin = self.dw_at_low_pc()?.unwrap_or(0);
+            Ok(vec![gimli::Range {
+                begin,
+                end: begin + size,
+            }])
+        } else if let Some(offset) = self.dw_at_ranges()? {
+            Ok(dwarf.ranges(unit, offset)?.collect()?)
+        } else {
+            Ok(vec![])
+        }
+    }
+
     /// Compute the size of an entity occupying a contiguous range of machine
     /// code addresses in the binary.
     fn contiguous_entity_size(&self) -> FallilbleOption<u64> {
@@ -1,12 +1,15 @@
 use gimli;
+use gimli::ReaderOffset;
 use twiggy_ir as ir;
 use twiggy_traits as traits;
 
 mod item_name;
 mod location_attrs;
 
-use self::item_name::item_name;
+pub(super) use self::item_name::item_name;
 use self::location_attrs::DieLocationAttributes;
+use super::debug_info::DebugInfo;
+use super::functions::Functions;
 
 /// This type alias is used to represent an option return value for
 /// a procedure that could return an Error.
@@ -19,14 +22,31 @@ pub(super) fn parse_items<R: gimli::Reader>(
     unit_id: usize,
     entry: &gimli::DebuggingInformationEntry<R>,
     entry_id: usize,
+    functions: &mut Functions,
 ) -> Result<(), traits::Error> {
     let item: ir::Item = match entry.tag() {
         gimli::DW_TAG_subprogram => {
-            if let Some(size) = DieLocationAttributes::try_from(entry)?.entity_size(dwarf, unit)? {
+            let location = DieLocationAttributes::try_from(entry)?;
+            if let Some(size) = location.entity_size(dwarf, unit)? {
+                // Skip functions that the linker discarded.
+                let ranges = location.address_ranges(dwarf, unit)?;
+                if !ranges.iter().any(|&range| functions.is_code(range)) {
+                    return Ok(());
+                }
+
                 let id = ir::Id::entry(unit_id, entry_id);
                 let name = item_name(entry, dwarf, unit)?
-                    .unwrap_or_else(|| format!("Subroutine[{}][{}]", unit_id, entry_id));
+                    .unwrap_or_else(|| subroutine_name(unit_id, entry_id));
                 let kind: ir::ItemKind = ir::Code::new(&name).into();
+
+                // Remember where this function's machine code lives, and
+                // which DIEs describe it, so that we can find it again when
+                // parsing edges.
+                for range in ranges {
+                    functions.add_range(range, id);
+                }
+                add_entries(unit, entry, id, functions)?;
+
                 ir::Item::new(id, name, size as u32, kind)
             } else {
                 return Ok(());
@@ -39,10 +59,150 @@ pub(super) fn parse_items<R: gimli::Reader>(
     Ok(())
 }
 
+/// If the given entry describes a function whose machine code is already
+/// known, such as a wasm function whose item was parsed from the code section,
+/// remember that the entry describes it.
+pub(super) fn parse_function_entry<R: gimli::Reader>(
+    dwarf: &gimli::Dwarf<R>,
+    unit: &gimli::Unit<R>,
+    entry: &gimli::DebuggingInformationEntry<R>,
+    functions: &mut Functions,
+) -> Result<(), traits::Error> {
+    if entry.tag() != gimli::DW_TAG_subprogram {
+        return Ok(());
+    }
+
+    let location = DieLocationAttributes::try_from(entry)?;
+    let id = location
+        .address_ranges(dwarf, unit)?
+        .iter()
+        .filter_map(|range| functions.containing(range.begin))
+        .next();
+    if let Some(id) = id {
+        add_entries(unit, entry, id, functions)?;
+    }
+    Ok(())
+}
+
+/// Remember that the given entry, and the entries it completes, describe the
+/// function with the given item.
+fn add_entries<R: gimli::Reader>(
+    unit: &gimli::Unit<R>,
+    entry: &gimli::DebuggingInformationEntry<R>,
+    id: ir::Id,
+    functions: &mut Functions,
+) -> Result<(), traits::Error> {
+    functions.add_entry(entry.offset().to_unit_section_offset(unit), id);
+    for attr in &[gimli::DW_AT_specification, gimli::DW_AT_abstract_origin] {
+        if let Some(offset) = entry_reference(unit, entry.attr_value(*attr)?) {
+            functions.add_entry(offset, id);
+        }
+    }
+    Ok(())
+}
+
 pub(super) fn parse_edges<R: gimli::Reader>(
-    _items: &mut ir::ItemsBuilder,
-    _entry: &gimli::DebuggingInformationEntry<R>,
+    items: &mut ir::ItemsBuilder,
+    unit: &gimli::Unit<R>,
+    entry: &gimli::DebuggingInformationEntry<R>,
+    caller: Option<ir::Id>,
+    functions: &Functions,
 ) -> Result<(), traits::Error> {
-    // TODO: Add edges representing the call graph.
+    // Call sites are only described when optimizations are enabled, and only
+    // for direct calls, but when they are present they tell us exactly which
+    // function is being called.
+    match entry.tag() {
+        gimli::DW_TAG_call_site | gimli::DW_TAG_GNU_call_site => {
+            let origin = match entry.attr_value(gimli::DW_AT_call_origin)? {
+                Some(origin) => Some(origin),
+                None => entry.attr_value(gimli::DW_AT_abstract_origin)?,
+            };
+            let callee =
+                entry_reference(unit, origin).and_then(|offset| functions.by_entry(offset));
+            if let (Some(caller), Some(callee)) = (caller, callee) {
+                items.add_edge(caller, callee);
+            }
+        }
+        _ => {}
+    }
+
     Ok(())
 }
+
+/// Attribute the strings and range lists that the given entry refers to to
+/// the given owner.
+pub(super) fn parse_debug_info<R: gimli::Reader>(
+    dwarf: &gimli::Dwarf<R>,
+    unit: &gimli::Unit<R>,
+    entry: &gimli::DebuggingInformationEntry<R>,
+    owner: usize,
+    debug_info: &mut DebugInfo,
+) -> Result<(), traits::Error> {
+    let mut attrs = entry.attrs();
+    while let Some(attr) = attrs.next()? {
+        let string = match attr.value() {
+            gimli::AttributeValue::DebugStrRef(offset) => Some(offset),
+            gimli::AttributeValue::DebugStrOffsetsIndex(index) => {
+                Some(dwarf.debug_str_offsets.get_str_offset(
+                    unit.encoding().format,
+                    unit.str_offsets_base,
+                    index,
+                )?)
+            }
+            gimli::AttributeValue::RangeListsRef(offset) => {
+                let section = if unit.header.version() >= 5 {
+                    ".debug_rnglists"
+                } else {
+                    ".debug_ranges"
+                };
+                debug_info.add_range_list(owner, section, offset.0.into_u64());
+                None
+            }
+            _ => None,
+        };
+
+        if let Some(offset) = string {
+            // Strings are terminated by a null byte.
+            let size = dwarf.debug_str.get_str(offset)?.len().into_u64() + 1;
+            debug_info.add_string(owner, offset.0.into_u64(), size);
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the demangled name of the function described by the given entry, as
+/// its item is named.
+pub(super) fn function_name<R: gimli::Reader>(
+    dwarf: &gimli::Dwarf<R>,
+    unit: &gimli::Unit<R>,
+    unit_id: usize,
+    entry: &gimli::DebuggingInformationEntry<R>,
+    entry_id: usize,
+) -> Result<String, traits::Error> {
+    let name = item_name(entry, dwarf, unit)?.unwrap_or_else(|| subroutine_name(unit_id, entry_id));
+    Ok(match ir::Code::new(&name).demangled() {
+        Some(demangled) => demangled.to_string(),
+        None => name,
+    })
+}
+
+/// Get the name of a function that wasn't given a name by its entry.
+fn subroutine_name(unit_id: usize, entry_id: usize) -> String {
+    format!("Subroutine[{}][{}]", unit_id, entry_id)
+}
+
+/// Get the offset of the DIE that the given attribute value refers to, if it
+/// is a reference to a DIE.
+fn entry_reference<R: gimli::Reader>(
+    unit: &gimli::Unit<R>,
+    value: Option<gimli::AttributeValue<R>>,
+) -> Option<gimli::UnitSectionOffset<R::Offset>> {
+    match value? {
+        gimli::AttributeValue::UnitRef(offset) => Some(offset.to_unit_section_offset(unit)),
+        gimli::AttributeValue::DebugInfoRef(offset) => {
+            Some(gimli::UnitSectionOffset::DebugInfoOffset(offset))
+        }
+        _ => None,
+    }
+}
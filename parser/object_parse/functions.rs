@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, HashMap};
+
+use gimli;
+use gimli::ReaderOffset;
+use object::{self, Object, ObjectSection, SectionKind};
+use twiggy_ir as ir;
+
+/// The machine code address ranges and debugging information entries (DIEs)
+/// of the functions that were parsed into IR items, along with the address
+/// ranges of static data objects. This is used to map the addresses and DIE
+/// references that we find while parsing edges back to the items they refer
+/// to.
+#[derive(Debug, Default)]
+pub(super) struct Functions {
+    // The address ranges of the sections containing machine code.
+    code: Vec<gimli::Range>,
+
+    // Maps the address each range of machine code begins at to the address it
+    // ends at, and the identifier of the function it belongs to.
+    ranges: BTreeMap<u64, (u64, ir::Id)>,
+
+    // Maps the `.debug_info` offsets of DIEs describing a function to its
+    // IR item's identifier.
+    entries: HashMap<u64, ir::Id>,
+
+    // Maps the address each static data object begins at to the address it
+    // ends at, and the identifier of its IR item.
+    data: BTreeMap<u64, (u64, ir::Id)>,
+}
+
+impl Functions {
+    /// Create an empty set of functions for the given file, remembering where
+    /// its machine code is.
+    pub fn new(file: &object::File) -> Functions {
+        let code = file
+            .sections()
+            .filter(|section| section.kind() == SectionKind::Text)
+            .map(|section| gimli::Range {
+                begin: section.address(),
+                end: section.address() + section.size(),
+            })
+            .collect();
+        Functions {
+            code,
+            ..Default::default()
+        }
+    }
+
+    /// Is the given range of addresses inside of a section containing machine
+    /// code? Linkers leave the debugging information for functions that they
+    /// discarded in place, with their addresses set to zero, so this is used to
+    /// tell which functions really exist in the binary.
+    pub fn is_code(&self, range: gimli::Range) -> bool {
+        self.code
+            .iter()
+            .any(|code| code.begin <= range.begin && range.end <= code.end)
+    }
+
+    /// Record that the given range of machine code belongs to a function.
+    pub fn add_range(&mut self, range: gimli::Range, id: ir::Id) {
+        if range.begin < range.end {
+            self.ranges.insert(range.begin, (range.end, id));
+        }
+    }
+
+    /// Record that the DIE at the given offset describes a function.
+    pub fn add_entry<T: ReaderOffset>(&mut self, offset: gimli::UnitSectionOffset<T>, id: ir::Id) {
+        if let gimli::UnitSectionOffset::DebugInfoOffset(offset) = offset {
+            self.entries.insert(offset.0.into_u64(), id);
+        }
+    }
+
+    /// Record that the given range of memory holds a static data object.
+    pub fn add_data(&mut self, range: gimli::Range, id: ir::Id) {
+        if range.begin < range.end {
+            self.data.insert(range.begin, (range.end, id));
+        }
+    }
+
+    /// Find the function whose machine code contains the given address.
+    pub fn containing(&self, address: u64) -> Option<ir::Id> {
+        containing(&self.ranges, address)
+    }
+
+    /// Find the static data object that contains the given address.
+    pub fn data_containing(&self, address: u64) -> Option<ir::Id> {
+        containing(&self.data, address)
+    }
+
+    /// Find the function that begins at, or the static data object that
+    /// contains, the given address. These are the things that an address
+    /// found in machine code or static data can sensibly refer to.
+    pub fn referenced_by(&self, address: u64) -> Option<ir::Id> {
+        self.starting_at(address)
+            .or_else(|| self.data_containing(address))
+    }
+
+    /// Iterate over the functions and static data objects that begin within
+    /// the given range of memory, and the number of their bytes that are
+    /// inside of it.
+    pub fn within<'a>(&'a self, range: gimli::Range) -> impl Iterator<Item = (ir::Id, u64)> + 'a {
+        self.ranges
+            .range(range.begin..range.end)
+            .chain(self.data.range(range.begin..range.end))
+            .map(move |(&begin, &(end, id))| (id, end.min(range.end) - begin))
+    }
+
+    /// Find the function whose machine code begins at the given address.
+    /// Unlike `containing`, this will not match an address in the middle of
+    /// a function, so it is suitable for checking whether some value that
+    /// merely looks like an address really is a function's address.
+    pub fn starting_at(&self, address: u64) -> Option<ir::Id> {
+        self.ranges.get(&address).map(|&(_, id)| id)
+    }
+
+    /// Find the function described by the DIE at the given offset.
+    pub fn by_entry<T: ReaderOffset>(&self, offset: gimli::UnitSectionOffset<T>) -> Option<ir::Id> {
+        match offset {
+            gimli::UnitSectionOffset::DebugInfoOffset(offset) => {
+                self.entries.get(&offset.0.into_u64()).cloned()
+            }
+            gimli::UnitSectionOffset::DebugTypesOffset(_) => None,
+        }
+    }
+
+    /// Iterate over each range of machine code that overlaps the given range,
+    /// and the function it belongs to, in order of address.
+    pub fn overlapping<'a>(
+        &'a self,
+        range: gimli::Range,
+    ) -> impl Iterator<Item = (gimli::Range, ir::Id)> + 'a {
+        let first = self
+            .ranges
+            .range(..=range.begin)
+            .next_back()
+            .filter(|&(_, &(end, _))| end > range.begin)
+            .map_or(range.begin, |(&begin, _)| begin);
+        self.ranges
+            .range(first..range.end.max(first))
+            .map(|(&begin, &(end, id))| (gimli::Range { begin, end }, id))
+    }
+
+    /// Iterate over each range of machine code, and the function it belongs
+    /// to, in order of address.
+    pub fn ranges<'a>(&'a self) -> impl Iterator<Item = (gimli::Range, ir::Id)> + 'a {
+        self.ranges
+            .iter()
+            .map(|(&begin, &(end, id))| (gimli::Range { begin, end }, id))
+    }
+}
+
+fn containing(ranges: &BTreeMap<u64, (u64, ir::Id)>, address: u64) -> Option<ir::Id> {
+    ranges
+        .range(..=address)
+        .next_back()
+        .and_then(|(_, &(end, id))| if address < end { Some(id) } else { None })
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use twiggy_ir as ir;
+use twiggy_traits as traits;
+
+use super::functions::Functions;
+
+/// The ranges of machine code that each source file was compiled into, as
+/// described by the compilation units' line number programs.
+#[derive(Debug, Default)]
+pub(super) struct Lines {
+    // Each range of machine code, and the index of its file in `files`.
+    ranges: Vec<(gimli::Range, usize)>,
+    files: Vec<String>,
+    file_indices: HashMap<String, usize>,
+    // The directory of each Rust crate's root source file, and the crate's
+    // name.
+    crates: Vec<(PathBuf, String)>,
+}
+
+impl Lines {
+    /// Add the ranges of machine code described by a compilation unit's line
+    /// number program. Each row of the program covers the code from its own
+    /// address up to the next row's address.
+    pub fn parse_unit<R: gimli::Reader>(
+        &mut self,
+        dwarf: &gimli::Dwarf<R>,
+        unit: &gimli::Unit<R>,
+    ) -> Result<(), traits::Error> {
+        if let Some(krate) = unit_crate(unit)? {
+            self.crates.push(krate);
+        }
+
+        let program = match unit.line_program {
+            Some(ref program) => program.clone(),
+            None => return Ok(()),
+        };
+
+        // Map the program's file indices to our own, so that each path is
+        // only built once.
+        let mut files: HashMap<u64, Option<usize>> = HashMap::new();
+
+        let mut rows = program.rows();
+        let mut previous: Option<(u64, Option<usize>)> = None;
+        while let Some((header, row)) = rows.next_row()? {
+            if let Some((begin, Some(file))) = previous.take() {
+                if begin < row.address() {
+                    let range = gimli::Range {
+                        begin,
+                        end: row.address(),
+                    };
+                    self.ranges.push((range, file));
+                }
+            }
+            if row.end_sequence() {
+                continue;
+            }
+
+            let file = match files.get(&row.file_index()) {
+                Some(&file) => file,
+                None => {
+                    let file = file_path(dwarf, unit, header, row.file_index())?
+                        .map(|path| self.intern(path));
+                    files.insert(row.file_index(), file);
+                    file
+                }
+            };
+            previous = Some((row.address(), file));
+        }
+
+        Ok(())
+    }
+
+    fn intern(&mut self, path: String) -> usize {
+        if let Some(&index) = self.file_indices.get(&path) {
+            return index;
+        }
+        self.files.push(path.clone());
+        self.file_indices.insert(path, self.files.len() - 1);
+        self.files.len() - 1
+    }
+
+    /// Attribute the bytes of each function to the source files that they
+    /// were compiled from. Sequences of rows sometimes overlap, such as when
+    /// the linker discards a function but not its line number program, so
+    /// each byte is only attributed to the first file that covers it. Each
+    /// file in the directory of a crate's root source file, or below it,
+    /// belongs to that crate.
+    pub fn finish(mut self, items: &mut ir::ItemsBuilder, functions: &Functions) {
+        self.ranges
+            .sort_by_key(|&(range, _)| (range.begin, range.end));
+
+        let mut sizes: HashMap<(ir::Id, usize), u64> = HashMap::new();
+        let mut covered = 0;
+        for (range, file) in self.ranges {
+            let range = gimli::Range {
+                begin: range.begin.max(covered),
+                end: range.end,
+            };
+            if range.begin >= range.end {
+                continue;
+            }
+            covered = range.end;
+
+            for (function, id) in functions.overlapping(range) {
+                let size = range.end.min(function.end) - range.begin.max(function.begin);
+                *sizes.entry((id, file)).or_insert(0) += size;
+            }
+        }
+
+        let (files, roots) = (&self.files, &self.crates);
+        let mut crates: HashMap<usize, Option<&str>> = HashMap::new();
+        for ((id, file), size) in sizes {
+            items.add_source(id, &files[file], size as u32);
+            crates.entry(file).or_insert_with(|| {
+                let path = Path::new(&files[file]);
+                roots
+                    .iter()
+                    .filter(|(root, _)| path.starts_with(root))
+                    .max_by_key(|(root, _)| root.components().count())
+                    .map(|(_, krate)| &krate[..])
+            });
+        }
+        for (file, krate) in crates {
+            if let Some(krate) = krate {
+                items.set_source_crate(&files[file], krate);
+            }
+        }
+    }
+}
+
+/// Get the path of the file with the given index in a line number program's
+/// header. Relative paths are relative to the file's include directory, and
+/// that is relative to the compilation unit's directory. Any `..` components
+/// are resolved, so that each file only has one path.
+fn file_path<R: gimli::Reader>(
+    dwarf: &gimli::Dwarf<R>,
+    unit: &gimli::Unit<R>,
+    header: &gimli::LineProgramHeader<R>,
+    index: u64,
+) -> Result<Option<String>, traits::Error> {
+    let file = match header.file(index) {
+        Some(file) => file,
+        None => return Ok(None),
+    };
+
+    let mut path = PathBuf::new();
+    if let Some(ref comp_dir) = unit.comp_dir {
+        path.push(&*comp_dir.to_string_lossy()?);
+    }
+    if let Some(directory) = file.directory(header) {
+        path.push(&*dwarf.attr_string(unit, directory)?.to_string_lossy()?);
+    }
+    path.push(
+        &*dwarf
+            .attr_string(unit, file.path_name())?
+            .to_string_lossy()?,
+    );
+    Ok(Some(normalize(&path).to_string_lossy().into_owned()))
+}
+
+/// Resolve the `.` and `..` components of a path.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Get the directory of the root source file of the Rust crate that a
+/// compilation unit was compiled from, and the crate's name. Newer versions of
+/// rustc name each unit after the crate's root source file and its codegen
+/// unit, as in `src/lib.rs/@/foo.1a2b3c4d-cgu.0`, which starts with the crate's
+/// name. Older versions only use the root source file, so the name is that of
+/// the file, as in `foo.rs`, or of the `lib<name>` directory or registry
+/// package that a `lib.rs` or `main.rs` is in.
+fn unit_crate<R: gimli::Reader>(
+    unit: &gimli::Unit<R>,
+) -> Result<Option<(PathBuf, String)>, traits::Error> {
+    let mut entries = unit.entries();
+    let language = match entries.next_dfs()? {
+        Some((_, entry)) => entry.attr_value(gimli::DW_AT_language)?,
+        None => None,
+    };
+    match language {
+        Some(gimli::AttributeValue::Language(gimli::DW_LANG_Rust)) => {}
+        _ => return Ok(None),
+    }
+
+    let name = match unit.name {
+        Some(ref name) => name.to_string_lossy()?.into_owned(),
+        None => return Ok(None),
+    };
+    let (root_file, codegen_unit) = match name.find("/@/") {
+        Some(i) => (&name[..i], Some(&name[i + 3..])),
+        None => (&name[..], None),
+    };
+
+    let mut path = PathBuf::new();
+    if let Some(ref comp_dir) = unit.comp_dir {
+        path.push(&*comp_dir.to_string_lossy()?);
+    }
+    path.push(root_file);
+    let path = normalize(&path);
+
+    let krate = match codegen_unit {
+        Some(codegen_unit) => codegen_unit.split('.').next().map(str::to_string),
+        None => root_file_crate(&path),
+    };
+    Ok(krate
+        .filter(|krate| !krate.is_empty())
+        .and_then(|krate| Some((path.parent()?.to_path_buf(), krate))))
+}
+
+/// Get the name of the crate whose root source file is at the given path.
+fn root_file_crate(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    if stem != "lib" && stem != "main" {
+        return Some(stem.replace('-', "_"));
+    }
+
+    let dir = path.parent()?;
+    let dir_name = dir.file_name()?.to_str()?;
+    if let Some(name) = dir_name.strip_prefix("lib") {
+        return Some(name.to_string());
+    }
+
+    // Cargo keeps the packages it downloads in `<name>-<version>` directories.
+    if dir_name != "src" {
+        return None;
+    }
+    let package = dir.parent()?.file_name()?.to_str()?;
+    let version = package
+        .char_indices()
+        .find(|&(i, c)| c == '-' && package[i + 1..].starts_with(|c: char| c.is_ascii_digit()))?
+        .0;
+    Some(package[..version].replace('-', "_"))
+}
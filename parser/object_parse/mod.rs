@@ -1,14 +1,27 @@
 use std::borrow::{Borrow, Cow};
+use std::collections::HashMap;
+use std::ops::Range;
 
-use fallible_iterator::FallibleIterator;
 use gimli;
-use object::{self, Object};
+use object::{self, Object, ObjectSection};
 use twiggy_ir as ir;
 use twiggy_traits as traits;
 use typed_arena::Arena;
 
+mod code_parse;
 mod compilation_unit_parse;
+mod debug_info;
 mod die_parse;
+mod functions;
+mod line_parse;
+mod section_parse;
+mod symbol_parse;
+mod variable_parse;
+
+use self::debug_info::DebugInfo;
+use self::functions::Functions;
+use self::line_parse::Lines;
+pub(crate) use self::variable_parse::Variable;
 
 // Helper function used to load a given section of the file.
 fn load_section<'a, 'file, 'input, Sect, Endian>(
@@ -29,7 +42,11 @@ where
     Sect::from(gimli::EndianSlice::new(data_ref, endian))
 }
 
-pub fn parse(items: &mut ir::ItemsBuilder, data: &[u8]) -> Result<(), traits::Error> {
+pub fn parse(
+    items: &mut ir::ItemsBuilder,
+    data: &[u8],
+    attribute_debug_info: bool,
+) -> Result<(), traits::Error> {
     let file: object::File = object::File::parse(data)?;
 
     // Identify the file's endianty and create a typed arena to load sections.
@@ -63,35 +80,200 @@ pub fn parse(items: &mut ir::ItemsBuilder, data: &[u8]) -> Result<(), traits::Er
         ..Default::default()
     };
 
-    parse_items(items, &dwarf)?;
-    parse_edges(items, &dwarf)?;
+    let mut functions = Functions::new(&file);
+    let units = parse_items(items, &dwarf, &mut functions)?;
+    symbol_parse::parse_items(items, &file, &mut functions, units)?;
+    parse_sources(items, &dwarf, &functions)?;
+    // The debug info is only broken down when we were asked to, since it
+    // would otherwise dominate the items of most native binaries.
+    let debug_info = if attribute_debug_info {
+        parse_debug_info(items, &dwarf, &functions, units + 1, |name| {
+            file.section_by_name(name)
+                .map_or((name.to_string(), 0), |section| {
+                    let binary_name = section.name().unwrap_or(name);
+                    (binary_name.to_string(), section.size())
+                })
+        })?
+    } else {
+        HashMap::new()
+    };
+    let debug_info = debug_info
+        .into_iter()
+        .filter_map(|(name, size)| {
+            file.section_by_name(name)
+                .map(|section| (section.index().0, size))
+        })
+        .collect();
+    section_parse::parse_items(items, &file, data, &functions, &debug_info, units + 2)?;
+    parse_edges(items, &dwarf, &functions)?;
+    code_parse::parse_edges(items, &file, &functions)?;
+    symbol_parse::parse_roots(items, &file, &functions)?;
     Ok(())
 }
 
 fn parse_items<R: gimli::Reader>(
     items: &mut ir::ItemsBuilder,
     dwarf: &gimli::Dwarf<R>,
-) -> Result<(), traits::Error> {
-    // Parse the items in each compilation unit.
-    let mut headers = dwarf.units().enumerate();
-    while let Some((unit_id, header)) = headers.next()? {
+    functions: &mut Functions,
+) -> Result<usize, traits::Error> {
+    // Parse the items in each compilation unit, and return how many units
+    // there were.
+    let mut units = 0;
+    let mut headers = dwarf.units();
+    while let Some(header) = headers.next()? {
         let unit = dwarf.unit(header)?;
-        compilation_unit_parse::parse_items(items, dwarf, &unit, unit_id)?
+        compilation_unit_parse::parse_items(items, dwarf, &unit, units, functions)?;
+        units += 1;
     }
 
-    Ok(())
+    Ok(units)
 }
 
 fn parse_edges<R: gimli::Reader>(
     items: &mut ir::ItemsBuilder,
     dwarf: &gimli::Dwarf<R>,
+    functions: &Functions,
 ) -> Result<(), traits::Error> {
     // Parse the edges in each compilation unit.
-    let mut headers = dwarf.units().enumerate();
-    while let Some((unit_id, header)) = headers.next()? {
+    let mut headers = dwarf.units();
+    while let Some(header) = headers.next()? {
+        let unit = dwarf.unit(header)?;
+        compilation_unit_parse::parse_edges(items, &unit, functions)?
+    }
+
+    Ok(())
+}
+
+fn parse_sources<R: gimli::Reader>(
+    items: &mut ir::ItemsBuilder,
+    dwarf: &gimli::Dwarf<R>,
+    functions: &Functions,
+) -> Result<(), traits::Error> {
+    // Attribute the functions' bytes to the source files that they were
+    // compiled from, using each compilation unit's line number program.
+    let mut lines = Lines::default();
+    let mut headers = dwarf.units();
+    while let Some(header) = headers.next()? {
         let unit = dwarf.unit(header)?;
-        compilation_unit_parse::parse_edges(items, &unit, unit_id)?
+        lines.parse_unit(dwarf, &unit)?;
     }
+    lines.finish(items, functions);
 
     Ok(())
 }
+
+/// Parse the DWARF debugging information in a wasm module's `.debug_*` custom
+/// sections, given their contents by name, and the ranges of the code section
+/// that each function's body occupies. The functions' source locations are
+/// always recorded. If `attribute_debug_info` is set, the bytes of debugging
+/// information are also attributed to the compilation units and functions that
+/// they describe, and the number of bytes that were attributed is returned,
+/// keyed by the name of the section they came from.
+pub(crate) fn parse_wasm_debug_info(
+    items: &mut ir::ItemsBuilder,
+    sections: &HashMap<&str, &[u8]>,
+    bodies: &[(Range<u64>, ir::Id)],
+    debug_info_id: usize,
+    attribute_debug_info: bool,
+) -> Result<HashMap<&'static str, u64>, traits::Error> {
+    let dwarf = wasm_dwarf(sections);
+
+    // The functions already have items, parsed from the code section, so we
+    // only need to find the entries that describe them.
+    let mut functions = Functions::default();
+    for (body, id) in bodies {
+        let range = gimli::Range {
+            begin: body.start,
+            end: body.end,
+        };
+        functions.add_range(range, *id);
+    }
+    let mut headers = dwarf.units();
+    while let Some(header) = headers.next()? {
+        let unit = dwarf.unit(header)?;
+        compilation_unit_parse::parse_function_entries(&dwarf, &unit, &mut functions)?;
+    }
+    parse_sources(items, &dwarf, &functions)?;
+    if !attribute_debug_info {
+        return Ok(HashMap::new());
+    }
+
+    parse_debug_info(items, &dwarf, &functions, debug_info_id, |name| {
+        let size = sections.get(name).map_or(0, |data| data.len() as u64);
+        (name.to_string(), size)
+    })
+}
+
+/// Find the static variables described by the DWARF debugging information in
+/// a wasm module's `.debug_*` custom sections, given their contents by name.
+pub(crate) fn parse_wasm_variables(
+    sections: &HashMap<&str, &[u8]>,
+) -> Result<Vec<Variable>, traits::Error> {
+    let dwarf = wasm_dwarf(sections);
+    let mut variables = vec![];
+    let mut headers = dwarf.units();
+    while let Some(header) = headers.next()? {
+        let unit = dwarf.unit(header)?;
+        variable_parse::parse_variables(&dwarf, &unit, &mut variables)?;
+    }
+    Ok(variables)
+}
+
+/// Load the DWARF debugging information in a wasm module's `.debug_*` custom
+/// sections, given their contents by name.
+fn wasm_dwarf<'a>(
+    sections: &HashMap<&str, &'a [u8]>,
+) -> gimli::Dwarf<gimli::EndianSlice<'a, gimli::LittleEndian>> {
+    fn load_section<'a, Sect>(sections: &HashMap<&str, &'a [u8]>) -> Sect
+    where
+        Sect: gimli::Section<gimli::EndianSlice<'a, gimli::LittleEndian>>,
+    {
+        let data = sections.get(Sect::section_name()).cloned().unwrap_or(&[]);
+        Sect::from(gimli::EndianSlice::new(data, gimli::LittleEndian))
+    }
+
+    let debug_ranges: gimli::DebugRanges<_> = load_section(sections);
+    let debug_rnglists: gimli::DebugRngLists<_> = load_section(sections);
+    gimli::Dwarf {
+        debug_abbrev: load_section(sections),
+        debug_addr: load_section(sections),
+        debug_info: load_section(sections),
+        debug_line: load_section(sections),
+        debug_line_str: load_section(sections),
+        debug_str: load_section(sections),
+        debug_str_offsets: load_section(sections),
+        ranges: gimli::RangeLists::new(debug_ranges, debug_rnglists),
+        ..Default::default()
+    }
+}
+
+fn parse_debug_info<R, F>(
+    items: &mut ir::ItemsBuilder,
+    dwarf: &gimli::Dwarf<R>,
+    functions: &Functions,
+    debug_info_id: usize,
+    binary_section: F,
+) -> Result<HashMap<&'static str, u64>, traits::Error>
+where
+    R: gimli::Reader,
+    F: Fn(&str) -> (String, u64),
+{
+    // Attribute the debugging information in each compilation unit to the
+    // unit and the functions it describes.
+    let mut debug_info = DebugInfo::new(debug_info_id);
+    let mut headers = dwarf.units();
+    let mut unit_id = 0;
+    while let Some(header) = headers.next()? {
+        let unit = dwarf.unit(header)?;
+        compilation_unit_parse::parse_debug_info(
+            dwarf,
+            &unit,
+            unit_id,
+            functions,
+            &mut debug_info,
+        )?;
+        unit_id += 1;
+    }
+
+    Ok(debug_info.finish(items, binary_section))
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use object::{self, Object, ObjectSection, ObjectSegment, SectionKind};
+use twiggy_ir as ir;
+use twiggy_traits as traits;
+
+use super::functions::Functions;
+
+/// Get the offset and size of the given section's data in the file. Sections
+/// that don't take up any space in the file, such as `.bss`, don't have any.
+pub(super) fn file_range(section: &object::Section) -> Option<(u64, u64)> {
+    match section.kind() {
+        SectionKind::UninitializedData | SectionKind::UninitializedTls | SectionKind::Common => {
+            None
+        }
+        _ => section.file_range().filter(|&(_, size)| size > 0),
+    }
+}
+
+/// A section or header table in the file.
+struct Region {
+    offset: u64,
+    size: u64,
+    id: ir::Id,
+    name: String,
+    kind: ir::ItemKind,
+
+    // Whether this is one of the file's sections, rather than a header table.
+    section: bool,
+
+    // The number of bytes that belong to other items, such as the functions
+    // in a code section, and so are carved out of this one.
+    carved: u64,
+}
+
+/// Add an item for each section, with the functions, static data objects, and
+/// debugging information that have their own items carved out of it, and
+/// items for the file's headers and the padding between sections, so that the
+/// sizes of the items add up to the size of the file.
+///
+/// The sections' items are given entries in the `sections_id` section of the
+/// IR, and everything else is given entries in the section after that.
+pub(super) fn parse_items(
+    items: &mut ir::ItemsBuilder,
+    file: &object::File,
+    data: &[u8],
+    functions: &Functions,
+    debug_info: &HashMap<usize, u64>,
+    sections_id: usize,
+) -> Result<(), traits::Error> {
+    let mut regions = header_regions(data, sections_id + 1);
+    let mut gaps = regions.len();
+    for section in file.sections() {
+        let (offset, size) = match file_range(&section) {
+            Some(range) => range,
+            None => continue,
+        };
+        let name = section.name().unwrap_or("<unnamed section>");
+        let kind: ir::ItemKind = if is_debug_section(name) {
+            ir::DebugInfo::new().into()
+        } else {
+            ir::Misc::new().into()
+        };
+        let mut carved = debug_info.get(&section.index().0).cloned().unwrap_or(0);
+        if section.address() != 0 {
+            let range = gimli::Range {
+                begin: section.address(),
+                end: section.address() + size,
+            };
+            for (id, size) in functions.within(range) {
+                items.add_to_section(id, name, size as u32);
+                carved += size;
+            }
+        }
+        regions.push(Region {
+            offset,
+            size,
+            id: ir::Id::entry(sections_id, section.index().0),
+            name: name.to_string(),
+            kind,
+            section: true,
+            carved,
+        });
+    }
+    regions.sort_by_key(|region| region.offset);
+
+    // Walk the file from start to end, adding an item for each region, and
+    // for each gap between them. Regions shouldn't overlap, but if they do,
+    // we only count the overlapping bytes once.
+    let file_size = data.len() as u64;
+    let mut cursor = 0;
+    for region in regions {
+        if region.offset > cursor {
+            add_gap(
+                items,
+                file,
+                sections_id + 1,
+                &mut gaps,
+                cursor,
+                region.offset,
+            );
+        }
+
+        let begin = region.offset.max(cursor).min(file_size);
+        let end = (region.offset + region.size).min(file_size).max(begin);
+        let size = (end - begin).saturating_sub(region.carved);
+        items.add_root(ir::Item::new(
+            region.id,
+            region.name.clone(),
+            size as u32,
+            region.kind,
+        ));
+        if region.section {
+            items.add_to_section(region.id, &region.name, size as u32);
+        }
+        cursor = end;
+    }
+    if file_size > cursor {
+        add_gap(items, file, sections_id + 1, &mut gaps, cursor, file_size);
+    }
+
+    Ok(())
+}
+
+/// Add an item for the bytes between `begin` and `end` that are not part of
+/// any section or known header. At the start of the file, these are the
+/// headers. Elsewhere, they may be part of a segment that holds data that
+/// isn't described by any section, such as Mach-O's `__LINKEDIT` segment, or
+/// otherwise they are padding for alignment.
+fn add_gap(
+    items: &mut ir::ItemsBuilder,
+    file: &object::File,
+    id: usize,
+    gaps: &mut usize,
+    begin: u64,
+    end: u64,
+) {
+    let name = if begin == 0 {
+        "headers".to_string()
+    } else {
+        file.segments()
+            .find(|segment| {
+                let (offset, size) = segment.file_range();
+                offset <= begin && end <= offset + size
+            })
+            .and_then(|segment| segment.name().map(|name| format!("segment '{}'", name)))
+            .unwrap_or_else(|| "padding".to_string())
+    };
+    let id = ir::Id::entry(id, *gaps);
+    *gaps += 1;
+    items.add_root(ir::Item::new(
+        id,
+        name,
+        (end - begin) as u32,
+        ir::Misc::new(),
+    ));
+}
+
+/// Get the regions of an ELF file's headers: the ELF header itself, the
+/// program header table, and the section header table. Other formats' headers
+/// are all at the start of the file, and are found as a gap before the first
+/// section instead.
+fn header_regions(data: &[u8], id: usize) -> Vec<Region> {
+    let elf = match object::ElfFile::parse(data) {
+        Ok(elf) => elf,
+        Err(_) => return vec![],
+    };
+    let header = &elf.elf().header;
+
+    let tables = [
+        ("ELF header", 0, u64::from(header.e_ehsize)),
+        (
+            "program headers",
+            header.e_phoff,
+            u64::from(header.e_phentsize) * u64::from(header.e_phnum),
+        ),
+        (
+            "section headers",
+            header.e_shoff,
+            u64::from(header.e_shentsize) * u64::from(header.e_shnum),
+        ),
+    ];
+    tables
+        .iter()
+        .filter(|&&(_, _, size)| size > 0)
+        .enumerate()
+        .map(|(i, &(name, offset, size))| Region {
+            offset,
+            size,
+            id: ir::Id::entry(id, i),
+            name: name.to_string(),
+            kind: ir::Misc::new().into(),
+            section: false,
+            carved: 0,
+        })
+        .collect()
+}
+
+/// Does the section with the given name contain debugging information?
+fn is_debug_section(name: &str) -> bool {
+    name.starts_with(".debug_") || name.starts_with(".zdebug_") || name.starts_with("__debug_")
+}
@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, HashMap};
+
+use object::{self, Object, ObjectSection, SectionIndex, SymbolKind};
+use twiggy_ir as ir;
+use twiggy_traits as traits;
+
+use super::functions::Functions;
+use super::section_parse;
+
+/// Add items for the functions and static data objects in the symbol table
+/// that we did not find any debugging information for. If the binary was
+/// stripped of its debugging information entirely, then this is all we have
+/// to go on.
+///
+/// The symbols' items are given entries in the `symbols_id` section of the IR.
+pub(super) fn parse_items(
+    items: &mut ir::ItemsBuilder,
+    file: &object::File,
+    functions: &mut Functions,
+    symbols_id: usize,
+) -> Result<(), traits::Error> {
+    // Find where each section's data is. Sections that don't take up any
+    // space in the file can't contain any items.
+    let mut sections = HashMap::new();
+    for section in file.sections() {
+        if let Some((_, size)) = section_parse::file_range(&section) {
+            sections.insert(section.index().0, (section.address(), size));
+        }
+    }
+
+    // Prefer the full symbol table, and fall back to the dynamic symbol table
+    // when the binary was stripped of it. Symbols are ordered by address, so
+    // that we can skip aliases and symbols that overlap one another.
+    let mut symbols = BTreeMap::new();
+    let mut symbol_table: Vec<_> = file.symbols().map(|(_, symbol)| symbol).collect();
+    if symbol_table.is_empty() {
+        symbol_table = file.dynamic_symbols().map(|(_, symbol)| symbol).collect();
+    }
+    for symbol in symbol_table {
+        let is_item = (symbol.kind() == SymbolKind::Text || symbol.kind() == SymbolKind::Data)
+            && !symbol.is_undefined()
+            && symbol.size() > 0
+            && !symbol.name().unwrap_or_default().is_empty();
+        if is_item {
+            symbols.entry(symbol.address()).or_insert(symbol);
+        }
+    }
+
+    let mut end = 0;
+    for (index, (&address, symbol)) in symbols.iter().enumerate() {
+        if address < end {
+            continue;
+        }
+
+        let section = symbol
+            .section_index()
+            .and_then(|SectionIndex(index)| sections.get(&index));
+        let (section_address, section_size) = match section {
+            Some(&section) => section,
+            None => continue,
+        };
+        let size = symbol.size();
+        if address < section_address || address + size > section_address + section_size {
+            continue;
+        }
+
+        let range = gimli::Range {
+            begin: address,
+            end: address + size,
+        };
+        let id = ir::Id::entry(symbols_id, index);
+        let name = symbol.name().unwrap_or_default();
+        let item = if symbol.kind() == SymbolKind::Text {
+            // Functions that are described by debugging information already
+            // have an item.
+            if functions.containing(address).is_some() {
+                continue;
+            }
+            functions.add_range(range, id);
+            ir::Item::new(id, name, size as u32, ir::Code::new(name))
+        } else {
+            functions.add_data(range, id);
+            ir::Item::new(id, name, size as u32, ir::Data::new(None))
+        };
+
+        items.add_item(item);
+        end = range.end;
+    }
+
+    Ok(())
+}
+
+/// Add the functions and static data that can be used from outside of the
+/// binary as roots: the entry point, and the functions and data objects that
+/// are exported by the symbol table.
+pub(super) fn parse_roots(
+    items: &mut ir::ItemsBuilder,
+    file: &object::File,
+    functions: &Functions,
+) -> Result<(), traits::Error> {
+    if let Some(id) = functions.starting_at(file.entry()) {
+        items.mark_root(id);
+    }
+
+    let symbols = file.symbols().chain(file.dynamic_symbols());
+    for (_, symbol) in symbols {
+        if !symbol.is_global() || symbol.is_undefined() {
+            continue;
+        }
+
+        let id = match symbol.kind() {
+            SymbolKind::Text => functions.starting_at(symbol.address()),
+            SymbolKind::Data => functions.data_containing(symbol.address()),
+            _ => None,
+        };
+        if let Some(id) = id {
+            items.mark_root(id);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use twiggy_traits as traits;
+
+use super::die_parse::item_name;
+
+/// The longest chain of typedefs, qualifiers, and array element types that is
+/// followed to find the size and name of a variable's type.
+const MAX_TYPE_DEPTH: usize = 16;
+
+type FallibleOption<T> = Result<Option<T>, traits::Error>;
+
+/// A static variable that occupies a constant range of memory, found in the
+/// debugging information.
+#[derive(Debug)]
+pub(crate) struct Variable {
+    pub address: u64,
+    pub size: u64,
+    pub name: String,
+    pub ty: Option<String>,
+}
+
+/// Find every variable in the unit whose location is a constant address, and
+/// whose type has a known size.
+pub(super) fn parse_variables<R: gimli::Reader>(
+    dwarf: &gimli::Dwarf<R>,
+    unit: &gimli::Unit<R>,
+    variables: &mut Vec<Variable>,
+) -> Result<(), traits::Error> {
+    let mut die_cursor = unit.entries();
+    while let Some((_, entry)) = die_cursor.next_dfs()? {
+        if entry.tag() != gimli::DW_TAG_variable {
+            continue;
+        }
+
+        let address = match entry.attr_value(gimli::DW_AT_location)? {
+            Some(gimli::AttributeValue::Exprloc(expr)) => constant_address(expr, unit.encoding())?,
+            _ => None,
+        };
+        let ty = match entry.attr_value(gimli::DW_AT_type)? {
+            Some(gimli::AttributeValue::UnitRef(offset)) => Some(offset),
+            _ => None,
+        };
+        let (address, ty) = match (address, ty) {
+            (Some(address), Some(ty)) => (address, ty),
+            _ => continue,
+        };
+        let size = match type_size(unit, ty, 0)? {
+            Some(size) if size > 0 => size,
+            _ => continue,
+        };
+        let name = match item_name(entry, dwarf, unit)? {
+            Some(name) => name,
+            None => continue,
+        };
+        variables.push(Variable {
+            address,
+            size,
+            name,
+            ty: type_name(dwarf, unit, ty, 0)?,
+        });
+    }
+    Ok(())
+}
+
+/// Get the address of a location expression that is nothing but a constant
+/// address, as static variables' locations are.
+fn constant_address<R: gimli::Reader>(
+    expr: gimli::Expression<R>,
+    encoding: gimli::Encoding,
+) -> FallibleOption<u64> {
+    let mut operations = expr.operations(encoding);
+    match (operations.next()?, operations.next()?) {
+        (Some(gimli::Operation::Address { address }), None) => Ok(Some(address)),
+        _ => Ok(None),
+    }
+}
+
+/// Get the type that a type DIE refers to, such as the type that a typedef
+/// names, or the element type of an array.
+fn referenced_type<R: gimli::Reader>(
+    entry: &gimli::DebuggingInformationEntry<R>,
+) -> FallibleOption<gimli::UnitOffset<R::Offset>> {
+    match entry.attr_value(gimli::DW_AT_type)? {
+        Some(gimli::AttributeValue::UnitRef(offset)) => Ok(Some(offset)),
+        _ => Ok(None),
+    }
+}
+
+fn is_alias(tag: gimli::DwTag) -> bool {
+    matches!(
+        tag,
+        gimli::DW_TAG_typedef
+            | gimli::DW_TAG_const_type
+            | gimli::DW_TAG_volatile_type
+            | gimli::DW_TAG_restrict_type
+            | gimli::DW_TAG_atomic_type
+    )
+}
+
+/// Get the size of the type at the given offset in bytes.
+fn type_size<R: gimli::Reader>(
+    unit: &gimli::Unit<R>,
+    offset: gimli::UnitOffset<R::Offset>,
+    depth: usize,
+) -> FallibleOption<u64> {
+    if depth > MAX_TYPE_DEPTH {
+        return Ok(None);
+    }
+    let entry = unit.entry(offset)?;
+    if let Some(size) = entry.attr_value(gimli::DW_AT_byte_size)? {
+        return Ok(size.udata_value());
+    }
+
+    let referenced = match referenced_type(&entry)? {
+        Some(referenced) => referenced,
+        None => return Ok(None),
+    };
+    if is_alias(entry.tag()) {
+        type_size(unit, referenced, depth + 1)
+    } else if entry.tag() == gimli::DW_TAG_array_type {
+        let element_size = type_size(unit, referenced, depth + 1)?;
+        let count = array_count(unit, offset)?;
+        Ok(element_size.and_then(|size| count.and_then(|count| size.checked_mul(count))))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Get the name of the type at the given offset, if it has one.
+fn type_name<R: gimli::Reader>(
+    dwarf: &gimli::Dwarf<R>,
+    unit: &gimli::Unit<R>,
+    offset: gimli::UnitOffset<R::Offset>,
+    depth: usize,
+) -> FallibleOption<String> {
+    if depth > MAX_TYPE_DEPTH {
+        return Ok(None);
+    }
+    let entry = unit.entry(offset)?;
+    if let Some(name) = entry.attr_value(gimli::DW_AT_name)? {
+        return Ok(Some(
+            dwarf.attr_string(unit, name)?.to_string()?.to_string(),
+        ));
+    }
+
+    let referenced = match referenced_type(&entry)? {
+        Some(referenced) => referenced,
+        None => return Ok(None),
+    };
+    if is_alias(entry.tag()) {
+        type_name(dwarf, unit, referenced, depth + 1)
+    } else if entry.tag() == gimli::DW_TAG_array_type {
+        let element = type_name(dwarf, unit, referenced, depth + 1)?;
+        let count = array_count(unit, offset)?;
+        Ok(element.and_then(|element| count.map(|count| format!("[{}; {}]", element, count))))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Get the number of elements in the array type at the given offset, from the
+/// subranges of each of its dimensions.
+fn array_count<R: gimli::Reader>(
+    unit: &gimli::Unit<R>,
+    offset: gimli::UnitOffset<R::Offset>,
+) -> FallibleOption<u64> {
+    let mut tree = unit.entries_tree(Some(offset))?;
+    let root = tree.root()?;
+    let mut children = root.children();
+    let mut count: u64 = 1;
+    while let Some(child) = children.next()? {
+        let child = child.entry();
+        if child.tag() != gimli::DW_TAG_subrange_type {
+            continue;
+        }
+        let length = match child.attr_value(gimli::DW_AT_count)? {
+            Some(length) => length.udata_value(),
+            None => child
+                .attr_value(gimli::DW_AT_upper_bound)?
+                .and_then(|upper| upper.udata_value())
+                .and_then(|upper| upper.checked_add(1)),
+        };
+        count = match length.and_then(|length| count.checked_mul(length)) {
+            Some(count) => count,
+            None => return Ok(None),
+        };
+    }
+    Ok(Some(count))
+}
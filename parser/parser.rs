@@ -5,7 +5,7 @@
 
 use std::ffi::OsStr;
 use std::fs;
-use std::io::Read;
+use std::io::{self, Read};
 use std::path;
 
 use twiggy_ir as ir;
@@ -13,31 +13,267 @@ use twiggy_traits as traits;
 
 #[cfg(feature = "dwarf")]
 mod object_parse;
+mod snapshot;
 mod wasm_parse;
 
 const WASM_MAGIC_NUMBER: [u8; 4] = [0x00, 0x61, 0x73, 0x6D];
 
-/// Parse the file at the given path into IR items.
+const ELF_MAGIC_NUMBER: [u8; 4] = [0x7F, 0x45, 0x4C, 0x46];
+
+/// The magic numbers of 32- and 64-bit Mach-O binaries, in either byte order.
+const MACH_O_MAGIC_NUMBERS: [[u8; 4]; 4] = [
+    [0xFE, 0xED, 0xFA, 0xCE],
+    [0xFE, 0xED, 0xFA, 0xCF],
+    [0xCE, 0xFA, 0xED, 0xFE],
+    [0xCF, 0xFA, 0xED, 0xFE],
+];
+
+/// The magic numbers of fat (universal) Mach-O binaries, with 32- and 64-bit
+/// offsets. Java class files share the first of these.
+const FAT_MACH_O_MAGIC_NUMBERS: [[u8; 4]; 2] = [[0xCA, 0xFE, 0xBA, 0xBE], [0xCA, 0xFE, 0xBA, 0xBF]];
+
+/// The magic number of the MS-DOS stub that PE binaries start with.
+const PE_MAGIC_NUMBER: [u8; 2] = [0x4D, 0x5A];
+
+/// The signature that `e_lfanew` points to in PE binaries.
+const PE_SIGNATURE: [u8; 4] = [0x50, 0x45, 0x00, 0x00];
+
+/// The machine types that start COFF object files: x86 and x86-64.
+const COFF_MACHINES: [[u8; 2]; 2] = [[0x4C, 0x01], [0x64, 0x86]];
+
+/// The magic number of static archives.
+const ARCHIVE_MAGIC_NUMBER: &[u8] = b"!<arch>\n";
+
+/// Options for parsing a binary into IR items, beyond its parse mode.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ParseOptions {
+    debug_info: bool,
+}
+
+impl ParseOptions {
+    /// Whether to attribute the bytes of the DWARF debug info to the
+    /// compilation units and functions that they describe. Otherwise, each
+    /// debug section is a single item, like any other section. Defaults to
+    /// `false`.
+    pub fn debug_info(mut self, debug_info: bool) -> ParseOptions {
+        self.debug_info = debug_info;
+        self
+    }
+}
+
+/// Parse the file at the given path into IR items. In the automatic parse
+/// mode, the file may also be a snapshot written by `write_snapshot`.
 pub fn read_and_parse<P: AsRef<path::Path>>(
     path: P,
     mode: traits::ParseMode,
+) -> Result<ir::Items, traits::Error> {
+    read_and_parse_with_options(path, mode, ParseOptions::default())
+}
+
+/// Like `read_and_parse`, but with the given parse options.
+pub fn read_and_parse_with_options<P: AsRef<path::Path>>(
+    path: P,
+    mode: traits::ParseMode,
+    options: ParseOptions,
 ) -> Result<ir::Items, traits::Error> {
     let path = path.as_ref();
     let mut file = fs::File::open(path)?;
     let mut data = vec![];
     file.read_to_end(&mut data)?;
 
+    parse_with_options(&data, mode, path.to_str(), options)
+}
+
+/// Parse the given data into IR items, detecting its format automatically.
+pub fn parse(data: &[u8]) -> Result<ir::Items, traits::Error> {
+    parse_with_mode(data, traits::ParseMode::Auto, None)
+}
+
+/// Parse the given data into IR items with the given parse mode. In the
+/// automatic parse mode, the format is detected with `detect_mode`, and the
+/// error explains which format was detected if the data can't be parsed as
+/// that format. The name hint, such as the path that the data was read from,
+/// is only used to detect wasm binaries by their `.wasm` extension when their
+/// magic bytes are not recognized.
+pub fn parse_with_mode(
+    data: &[u8],
+    mode: traits::ParseMode,
+    name_hint: Option<&str>,
+) -> Result<ir::Items, traits::Error> {
+    parse_with_options(data, mode, name_hint, ParseOptions::default())
+}
+
+/// Like `parse_with_mode`, but with the given parse options.
+pub fn parse_with_options(
+    data: &[u8],
+    mode: traits::ParseMode,
+    name_hint: Option<&str>,
+    options: ParseOptions,
+) -> Result<ir::Items, traits::Error> {
+    let debug_info = options.debug_info;
+    match mode {
+        traits::ParseMode::Auto => {
+            let detected = detect_mode(data, name_hint).ok_or_else(|| {
+                traits::Error::with_msg(format!(
+                    "could not detect the format of {}, expected a wasm, ELF, Mach-O, or PE \
+                     binary, a static archive, or a twiggy snapshot",
+                    name_hint.map_or("the input".to_string(), |name| format!("`{}`", name))
+                ))
+            })?;
+            parse_detected(data, detected, debug_info).map_err(|e| {
+                e.context(format!(
+                    "could not parse the input as `{}`, which was detected from its contents; \
+                     pass `--mode` to choose another parse mode",
+                    detected
+                ))
+            })
+        }
+        traits::ParseMode::Wasm | traits::ParseMode::Snapshot => {
+            parse_detected(data, mode, debug_info)
+        }
+        #[cfg(feature = "dwarf")]
+        traits::ParseMode::Dwarf => parse_other(data, debug_info),
+        _ => {
+            let detected = detect_mode(data, None);
+            if detected != Some(mode) {
+                return Err(traits::Error::with_msg(format!(
+                    "the input is not in the `{}` format{}",
+                    mode,
+                    detected.map_or(String::new(), |detected| format!(
+                        ", its contents look like `{}`",
+                        detected
+                    ))
+                )));
+            }
+            parse_detected(data, mode, debug_info)
+        }
+    }
+}
+
+/// Detect the parse mode for the given data from its magic bytes, or failing
+/// that, from the extension of its name hint. Returns `None` if the format is
+/// not recognized.
+pub fn detect_mode(data: &[u8], name_hint: Option<&str>) -> Option<traits::ParseMode> {
+    let starts_with_any = |magics: &[&[u8]]| magics.iter().any(|magic| data.starts_with(magic));
+
+    if snapshot::sniff(data) {
+        Some(traits::ParseMode::Snapshot)
+    } else if data.starts_with(&WASM_MAGIC_NUMBER) {
+        Some(traits::ParseMode::Wasm)
+    } else if data.starts_with(&ELF_MAGIC_NUMBER) {
+        Some(traits::ParseMode::Elf)
+    } else if starts_with_any(&[
+        &MACH_O_MAGIC_NUMBERS[0],
+        &MACH_O_MAGIC_NUMBERS[1],
+        &MACH_O_MAGIC_NUMBERS[2],
+        &MACH_O_MAGIC_NUMBERS[3],
+    ]) || is_fat_mach_o(data)
+    {
+        Some(traits::ParseMode::MachO)
+    } else if is_pe(data) || is_coff(data) {
+        Some(traits::ParseMode::Pe)
+    } else if data.starts_with(ARCHIVE_MAGIC_NUMBER) {
+        Some(traits::ParseMode::Archive)
+    } else {
+        let extension = name_hint.and_then(|name| path::Path::new(name).extension());
+        match extension.and_then(OsStr::to_str) {
+            Some("wasm") => Some(traits::ParseMode::Wasm),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the data starts with a fat Mach-O header. Java class files start
+/// with the same magic number, followed by their version, which is always far
+/// larger than any plausible number of architectures in a fat binary.
+fn is_fat_mach_o(data: &[u8]) -> bool {
+    if data.len() < 8
+        || !FAT_MACH_O_MAGIC_NUMBERS
+            .iter()
+            .any(|magic| data.starts_with(magic))
+    {
+        return false;
+    }
+    let arches = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+    arches > 0 && arches < 45
+}
+
+/// Read the little-endian integer of `size` bytes at the given offset, if the
+/// data is long enough.
+fn read_le(data: &[u8], offset: usize, size: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset + size)?;
+    Some(bytes.iter().rev().fold(0, |n, &b| n << 8 | u64::from(b)))
+}
+
+/// Whether the data starts with an MS-DOS stub whose `e_lfanew` field points
+/// at a PE signature.
+fn is_pe(data: &[u8]) -> bool {
+    if !data.starts_with(&PE_MAGIC_NUMBER) {
+        return false;
+    }
+    let signature = read_le(data, 0x3C, 4)
+        .and_then(|e_lfanew| data.get(e_lfanew as usize..e_lfanew as usize + 4));
+    signature == Some(&PE_SIGNATURE[..])
+}
+
+/// Whether the data starts with the header of a COFF object file. Its
+/// machine type is only two bytes, so the rest of the header must also be
+/// plausible: it has some sections, and its section headers and symbol table
+/// fit inside the data.
+fn is_coff(data: &[u8]) -> bool {
+    const HEADER_SIZE: u64 = 20;
+    const SECTION_HEADER_SIZE: u64 = 40;
+    const SYMBOL_SIZE: u64 = 18;
+
+    if !COFF_MACHINES
+        .iter()
+        .any(|machine| data.starts_with(machine))
+    {
+        return false;
+    }
+    let header = (
+        read_le(data, 2, 2),
+        read_le(data, 8, 4),
+        read_le(data, 12, 4),
+        read_le(data, 16, 2),
+    );
+    let (sections, symbols_offset, symbols, optional_header_size) = match header {
+        (Some(a), Some(b), Some(c), Some(d)) => (a, b, c, d),
+        _ => return false,
+    };
+    let len = data.len() as u64;
+    let headers_end = HEADER_SIZE + optional_header_size + sections * SECTION_HEADER_SIZE;
+    let symbols_fit = symbols_offset == 0
+        || (symbols_offset >= headers_end && symbols_offset + symbols * SYMBOL_SIZE <= len);
+    sections > 0 && headers_end <= len && symbols_fit
+}
+
+fn parse_detected(
+    data: &[u8],
+    mode: traits::ParseMode,
+    debug_info: bool,
+) -> Result<ir::Items, traits::Error> {
     match mode {
-        traits::ParseMode::Wasm => parse_wasm(&data),
+        traits::ParseMode::Snapshot => snapshot::parse(data),
+        traits::ParseMode::Wasm => parse_wasm(data, debug_info),
+        #[cfg(feature = "dwarf")]
+        traits::ParseMode::MachO if is_fat_mach_o(data) => parse_fat_mach_o(data, debug_info),
         #[cfg(feature = "dwarf")]
-        traits::ParseMode::Dwarf => parse_other(&data),
-        traits::ParseMode::Auto => parse_auto(path.extension(), &data),
+        traits::ParseMode::Archive => parse_archive(data, debug_info),
+        #[cfg(feature = "dwarf")]
+        _ => parse_other(data, debug_info),
+        #[cfg(not(feature = "dwarf"))]
+        _ => Err(traits::Error::with_msg(format!(
+            "parsing `{}` binaries requires twiggy's `dwarf` feature",
+            mode
+        ))),
     }
 }
 
-/// Parse the given data into IR items.
-pub fn parse(data: &[u8]) -> Result<ir::Items, traits::Error> {
-    parse_fallback(data)
+/// Write a snapshot of the given IR items, which `read_and_parse` can read
+/// back in place of the binary that they were parsed from.
+pub fn write_snapshot(items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+    snapshot::write(items, dest)
 }
 
 /// A trait for parsing things into `ir::Item`s.
@@ -64,45 +300,322 @@ pub(crate) trait Parse<'a> {
     ) -> Result<(), traits::Error>;
 }
 
-fn parse_auto(extension: Option<&OsStr>, data: &[u8]) -> Result<ir::Items, traits::Error> {
-    if sniff_wasm(extension, &data) {
-        parse_wasm(&data)
+fn parse_wasm(data: &[u8], debug_info: bool) -> Result<ir::Items, traits::Error> {
+    let mut items = ir::ItemsBuilder::new(data.len() as u32);
+
+    if wasmparser::Parser::is_component(data) {
+        let mut component = wasm_parse::ComponentReader::new(data)?;
+        component.parse_items(&mut items, debug_info)?;
+        component.parse_edges(&mut items, ())?;
     } else {
-        #[cfg(feature = "dwarf")]
-        let res = parse_other(&data);
-        #[cfg(not(feature = "dwarf"))]
-        let res = parse_fallback(&data);
-        res
+        let mut module = wasm_parse::ModuleReader::new(data)?;
+        module.parse_items(&mut items, debug_info)?;
+        module.parse_edges(&mut items, ())?;
     }
+
+    Ok(items.finish())
 }
 
-fn sniff_wasm(extension: Option<&OsStr>, data: &[u8]) -> bool {
-    match extension.and_then(|s| s.to_str()) {
-        Some("wasm") => true,
-        _ => data.get(0..4) == Some(&WASM_MAGIC_NUMBER),
-    }
+#[cfg(feature = "dwarf")]
+fn parse_other(data: &[u8], debug_info: bool) -> Result<ir::Items, traits::Error> {
+    let mut items = ir::ItemsBuilder::new(data.len() as u32);
+
+    object_parse::parse(&mut items, data, debug_info)?;
+
+    Ok(items.finish())
 }
 
-fn parse_wasm(data: &[u8]) -> Result<ir::Items, traits::Error> {
+/// Parse each architecture's slice of a fat Mach-O binary on its own, and
+/// gather their items under an item for each slice.
+#[cfg(feature = "dwarf")]
+fn parse_fat_mach_o(data: &[u8], debug_info: bool) -> Result<ir::Items, traits::Error> {
+    use goblin::mach::{constants::cputype, MultiArch};
+
     let mut items = ir::ItemsBuilder::new(data.len() as u32);
+    let arches = MultiArch::new(data)
+        .and_then(|multi| multi.arches())
+        .map_err(|e| traits::Error::with_msg(format!("invalid fat Mach-O header: {}", e)))?;
 
-    let mut module1 = wasmparser::ModuleReader::new(data)?;
-    module1.parse_items(&mut items, ())?;
-    let mut module2 = wasmparser::ModuleReader::new(data)?;
-    module2.parse_edges(&mut items, ())?;
+    // The fat header's item is added first, so that the slices' items are
+    // given `Id`s after it.
+    let slices_size: usize = arches.iter().map(|arch| arch.size as usize).sum();
+    items.add_root(ir::Item::new(
+        ir::Id::entry(0, 0),
+        "fat header",
+        data.len().saturating_sub(slices_size) as u32,
+        ir::Misc::new(),
+    ));
+
+    for arch in &arches {
+        let name = cputype::get_arch_name_from_types(arch.cputype(), arch.cpusubtype())
+            .map_or_else(
+                || format!("cputype {}", arch.cputype()),
+                ToString::to_string,
+            );
+        let slice = data
+            .get(arch.offset as usize..(arch.offset as usize).saturating_add(arch.size as usize))
+            .ok_or_else(|| {
+                traits::Error::with_msg(format!("the `{}` slice is out of bounds", name))
+            })?;
+        let part = parse_other(slice, debug_info)
+            .map_err(|e| e.context(format!("could not parse the `{}` slice", name)))?;
+        let id = items.add_part(
+            format!("Mach-O slice \"{}\"", name),
+            uncovered(slice.len(), &part),
+            &part,
+        );
+        items.mark_root(id);
+    }
 
     Ok(items.finish())
 }
 
+/// Parse each object file in a static archive on its own, and gather their
+/// items under an item for each member. Members that aren't object files, such
+/// as symbol tables and `.rlib` metadata, are represented by a single item.
 #[cfg(feature = "dwarf")]
-fn parse_other(data: &[u8]) -> Result<ir::Items, traits::Error> {
+fn parse_archive(data: &[u8], debug_info: bool) -> Result<ir::Items, traits::Error> {
+    use goblin::archive::Archive;
+
     let mut items = ir::ItemsBuilder::new(data.len() as u32);
+    let archive = Archive::parse(data)
+        .map_err(|e| traits::Error::with_msg(format!("invalid static archive: {}", e)))?;
 
-    object_parse::parse(&mut items, data)?;
+    let mut objects = vec![];
+    let mut members_size = 0;
+    for (index, name) in archive.members().into_iter().enumerate() {
+        let member = archive.extract(name, data).map_err(|e| {
+            traits::Error::with_msg(format!("invalid archive member `{}`: {}", name, e))
+        })?;
+        members_size += member.len();
+        if object::File::parse(member).is_ok() {
+            objects.push((name, member));
+        } else {
+            // These items are added before the objects' parts, so that the
+            // parts are given `Id`s after them.
+            items.add_root(ir::Item::new(
+                ir::Id::entry(0, index + 1),
+                format!("archive member \"{}\"", name),
+                member.len() as u32,
+                ir::Misc::new(),
+            ));
+        }
+    }
+    items.add_root(ir::Item::new(
+        ir::Id::entry(0, 0),
+        "archive headers",
+        data.len().saturating_sub(members_size) as u32,
+        ir::Misc::new(),
+    ));
+
+    for (name, member) in objects {
+        let part = parse_other(member, debug_info)
+            .map_err(|e| e.context(format!("could not parse the archive member `{}`", name)))?;
+        let id = items.add_part(
+            format!("archive member \"{}\"", name),
+            uncovered(member.len(), &part),
+            &part,
+        );
+        items.mark_root(id);
+    }
 
     Ok(items.finish())
 }
 
-fn parse_fallback(data: &[u8]) -> Result<ir::Items, traits::Error> {
-    parse_wasm(data)
+/// The number of bytes of a part of the binary that its items don't cover.
+#[cfg(feature = "dwarf")]
+fn uncovered(size: usize, part: &ir::Items) -> u32 {
+    let covered: u32 = part
+        .iter()
+        .filter(|item| item.id() != part.meta_root())
+        .map(|item| item.size())
+        .sum();
+    (size as u32).saturating_sub(covered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use traits::ParseMode;
+
+    const WASM: &[u8] = include_bytes!("../twiggy/tests/all/fixtures/wee_alloc.wasm");
+    const ELF: &[u8] = include_bytes!("../twiggy/tests/all/fixtures/hello_elf");
+    const ARCHIVE: &[u8] = include_bytes!("../twiggy/tests/all/fixtures/libhello.a");
+    const UNKNOWN: &[u8] = include_bytes!("../twiggy/tests/all/fixtures/groups.txt");
+
+    /// The header of a thin, 64-bit, little-endian Mach-O binary, without any
+    /// load commands.
+    fn mach_o() -> Vec<u8> {
+        let mut data = vec![0xCF, 0xFA, 0xED, 0xFE];
+        data.resize(32, 0);
+        data
+    }
+
+    /// The header of a fat Mach-O binary with two slices.
+    fn fat_mach_o() -> Vec<u8> {
+        let mut data = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 2];
+        data.resize(48, 0);
+        data
+    }
+
+    /// An MS-DOS stub whose `e_lfanew` points at a PE signature.
+    fn pe() -> Vec<u8> {
+        let mut data = b"MZ".to_vec();
+        data.resize(0x3C, 0);
+        data.extend_from_slice(&0x40u32.to_le_bytes());
+        data.extend_from_slice(b"PE\0\0");
+        data.resize(0x80, 0);
+        data
+    }
+
+    /// The header of an x86-64 COFF object file with a single, empty section,
+    /// and a symbol table with a single symbol after it.
+    fn coff() -> Vec<u8> {
+        let mut data = vec![0x64, 0x86, 1, 0];
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&60u32.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&[0; 4]);
+        data.resize(60 + 18, 0);
+        data
+    }
+
+    fn error(result: Result<ir::Items, traits::Error>) -> String {
+        match result {
+            Ok(_) => panic!("expected an error"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn detect_mode_by_magic_bytes() {
+        assert_eq!(detect_mode(WASM, None), Some(ParseMode::Wasm));
+        assert_eq!(detect_mode(ELF, None), Some(ParseMode::Elf));
+        assert_eq!(detect_mode(&mach_o(), None), Some(ParseMode::MachO));
+        assert_eq!(detect_mode(&fat_mach_o(), None), Some(ParseMode::MachO));
+        assert_eq!(detect_mode(&pe(), None), Some(ParseMode::Pe));
+        assert_eq!(detect_mode(&coff(), None), Some(ParseMode::Pe));
+        assert_eq!(detect_mode(ARCHIVE, None), Some(ParseMode::Archive));
+        assert_eq!(detect_mode(UNKNOWN, None), None);
+    }
+
+    #[test]
+    fn detect_mode_by_name_hint() {
+        assert_eq!(
+            detect_mode(UNKNOWN, Some("groups.wasm")),
+            Some(ParseMode::Wasm)
+        );
+        assert_eq!(detect_mode(UNKNOWN, Some("groups.txt")), None);
+        assert_eq!(detect_mode(ELF, Some("hello.wasm")), Some(ParseMode::Elf));
+    }
+
+    #[test]
+    fn detect_mode_ignores_java_class_files() {
+        let class = [0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x34];
+        assert_eq!(detect_mode(&class, None), None);
+    }
+
+    #[test]
+    fn detect_mode_ignores_implausible_pe_and_coff_headers() {
+        // Text that happens to start with a COFF machine type.
+        assert_eq!(detect_mode(b"d\x86 is not a COFF object", None), None);
+        assert_eq!(detect_mode(b"L\x01 is not one either", None), None);
+
+        // An MS-DOS stub without a PE signature.
+        let mut dos = pe();
+        dos[0x40] = b'X';
+        assert_eq!(detect_mode(&dos, None), None);
+        let mut dos = pe();
+        dos[0x3C] = 0xFF;
+        assert_eq!(detect_mode(&dos, None), None);
+        assert_eq!(detect_mode(b"MZ", None), None);
+
+        // COFF headers without sections, or whose section headers or symbol
+        // table don't fit in the data.
+        let mut no_sections = coff();
+        no_sections[2] = 0;
+        assert_eq!(detect_mode(&no_sections, None), None);
+        let mut too_many_sections = coff();
+        too_many_sections[3] = 1;
+        assert_eq!(detect_mode(&too_many_sections, None), None);
+        let mut optional_header = coff();
+        optional_header[16] = 0xFF;
+        assert_eq!(detect_mode(&optional_header, None), None);
+        let mut symbols = coff();
+        symbols[12] = 2;
+        assert_eq!(detect_mode(&symbols, None), None);
+    }
+
+    #[test]
+    fn parse_with_mode_wasm() {
+        let items = parse_with_mode(WASM, ParseMode::Wasm, None).unwrap();
+        assert!(items.iter().any(|item| item.name() == "hello"));
+        let detected = parse_with_mode(WASM, ParseMode::Auto, None).unwrap();
+        assert_eq!(items.size(), detected.size());
+    }
+
+    #[cfg(feature = "dwarf")]
+    #[test]
+    fn parse_with_mode_elf() {
+        let items = parse_with_mode(ELF, ParseMode::Elf, None).unwrap();
+        assert_eq!(items.size(), ELF.len() as u32);
+        parse_with_mode(ELF, ParseMode::Auto, None).unwrap();
+    }
+
+    #[cfg(feature = "dwarf")]
+    #[test]
+    fn parse_with_mode_archive() {
+        parse_with_mode(ARCHIVE, ParseMode::Archive, None).unwrap();
+        parse_with_mode(ARCHIVE, ParseMode::Auto, None).unwrap();
+    }
+
+    #[test]
+    fn parse_with_mode_unknown_format() {
+        assert_eq!(
+            error(parse_with_mode(
+                UNKNOWN,
+                ParseMode::Auto,
+                Some("groups.txt")
+            )),
+            "could not detect the format of `groups.txt`, expected a wasm, ELF, Mach-O, or PE \
+             binary, a static archive, or a twiggy snapshot"
+        );
+        assert_eq!(
+            error(parse_with_mode(UNKNOWN, ParseMode::Auto, None)),
+            "could not detect the format of the input, expected a wasm, ELF, Mach-O, or PE \
+             binary, a static archive, or a twiggy snapshot"
+        );
+        assert_eq!(
+            error(parse_with_mode(UNKNOWN, ParseMode::Pe, None)),
+            "the input is not in the `pe` format"
+        );
+        assert!(error(parse_with_mode(b"d\x86...", ParseMode::Auto, None))
+            .starts_with("could not detect the format of the input"));
+    }
+
+    #[test]
+    fn parse_with_mode_mismatch() {
+        assert_eq!(
+            error(parse_with_mode(ELF, ParseMode::Archive, None)),
+            "the input is not in the `archive` format, its contents look like `elf`"
+        );
+        assert_eq!(
+            error(parse_with_mode(&mach_o(), ParseMode::Elf, None)),
+            "the input is not in the `elf` format, its contents look like `macho`"
+        );
+        assert_eq!(
+            error(parse_with_mode(&fat_mach_o(), ParseMode::Pe, None)),
+            "the input is not in the `pe` format, its contents look like `macho`"
+        );
+        assert_eq!(
+            error(parse_with_mode(&pe(), ParseMode::MachO, None)),
+            "the input is not in the `macho` format, its contents look like `pe`"
+        );
+    }
+
+    #[test]
+    fn parse_with_mode_names_the_detected_format() {
+        let message = error(parse_with_mode(&fat_mach_o(), ParseMode::Auto, None));
+        assert!(message.starts_with("could not parse the input as `macho`"));
+    }
 }
@@ -0,0 +1,51 @@
+//! Snapshots of parsed IR items, which can be read back in place of the binary
+//! that they were parsed from.
+//!
+//! A snapshot starts with a magic number and the version of its format,
+//! followed by the items, serialized with `bincode`. The version must be
+//! bumped whenever the serialized representation of `ir::Items` changes.
+
+use std::io::{self, Write};
+
+use twiggy_ir as ir;
+use twiggy_traits as traits;
+
+const SNAPSHOT_MAGIC_NUMBER: [u8; 8] = *b"\0twiggy\0";
+
+const SNAPSHOT_VERSION: u32 = 3;
+
+/// Does the given data look like a snapshot?
+pub(crate) fn sniff(data: &[u8]) -> bool {
+    data.get(0..SNAPSHOT_MAGIC_NUMBER.len()) == Some(&SNAPSHOT_MAGIC_NUMBER)
+}
+
+/// Read the items back from a snapshot.
+pub(crate) fn parse(data: &[u8]) -> Result<ir::Items, traits::Error> {
+    if !sniff(data) {
+        return Err(traits::Error::with_msg("not a twiggy snapshot"));
+    }
+
+    let data = &data[SNAPSHOT_MAGIC_NUMBER.len()..];
+    let version = match data.get(0..4) {
+        Some(version) => u32::from_le_bytes([version[0], version[1], version[2], version[3]]),
+        None => return Err(traits::Error::with_msg("truncated twiggy snapshot")),
+    };
+    if version != SNAPSHOT_VERSION {
+        return Err(traits::Error::with_msg(format!(
+            "unsupported twiggy snapshot version {}, expected version {}",
+            version, SNAPSHOT_VERSION
+        )));
+    }
+
+    Ok(bincode::deserialize(&data[4..])?)
+}
+
+/// Write a snapshot of the given items.
+pub(crate) fn write(items: &ir::Items, dest: &mut dyn io::Write) -> Result<(), traits::Error> {
+    let mut dest = io::BufWriter::new(dest);
+    dest.write_all(&SNAPSHOT_MAGIC_NUMBER)?;
+    dest.write_all(&SNAPSHOT_VERSION.to_le_bytes())?;
+    bincode::serialize_into(&mut dest, items)?;
+    dest.flush()?;
+    Ok(())
+}
@@ -0,0 +1,747 @@
+use super::{iterate_with_size, Parse};
+use std::collections::HashMap;
+use std::ops::Range;
+use twiggy_ir::{self as ir, Id};
+use twiggy_traits as traits;
+use wasmparser::{self, ComponentExternalKind, ExternalKind};
+
+/// A section of a wasm component, along with whatever `wasmparser` read from
+/// its header.
+enum Section<'a> {
+    Custom(wasmparser::CustomSectionReader<'a>),
+    /// A nested core module or component, which is parsed on its own. This is
+    /// the range of its bytes, after the section's id and size.
+    Module(Range<usize>),
+    Component(Range<usize>),
+    CoreInstance(wasmparser::InstanceSectionReader<'a>),
+    CoreType(wasmparser::CoreTypeSectionReader<'a>),
+    Instance(wasmparser::ComponentInstanceSectionReader<'a>),
+    Alias(wasmparser::ComponentAliasSectionReader<'a>),
+    Type(wasmparser::ComponentTypeSectionReader<'a>),
+    Canonical(wasmparser::ComponentCanonicalSectionReader<'a>),
+    Start(wasmparser::ComponentStartFunction),
+    Import(wasmparser::ComponentImportSectionReader<'a>),
+    Export(wasmparser::ComponentExportSectionReader<'a>),
+}
+
+struct IndexedSection<'a>(usize, Section<'a>);
+
+/// Names of nested core modules or components, by index.
+type Names<'a> = HashMap<u32, &'a str>;
+
+/// A wasm component, split up into its sections. Nested core modules and
+/// components are parsed on their own, and their items are gathered under an
+/// item for each of them.
+pub struct ComponentReader<'a> {
+    data: &'a [u8],
+    /// The size of the magic number and version that the component starts
+    /// with.
+    header_size: u32,
+    sections: Vec<IndexedSection<'a>>,
+    /// The size of each section, including its id and size.
+    sizes: HashMap<usize, u32>,
+    /// The item of each nested core module and component, by section.
+    parts: HashMap<usize, Id>,
+}
+
+/// The items in each of a component's index spaces, which are built up as
+/// its sections are read in order.
+#[derive(Default)]
+struct IndexSpaces {
+    core_modules: Vec<Id>,
+    core_instances: Vec<Id>,
+    core_types: Vec<Id>,
+    core_funcs: Vec<Id>,
+    core_tables: Vec<Id>,
+    core_memories: Vec<Id>,
+    core_globals: Vec<Id>,
+    core_tags: Vec<Id>,
+    funcs: Vec<Id>,
+    values: Vec<Id>,
+    types: Vec<Id>,
+    instances: Vec<Id>,
+    components: Vec<Id>,
+}
+
+impl IndexSpaces {
+    fn core(&mut self, kind: ExternalKind) -> &mut Vec<Id> {
+        match kind {
+            ExternalKind::Func | ExternalKind::FuncExact => &mut self.core_funcs,
+            ExternalKind::Table => &mut self.core_tables,
+            ExternalKind::Memory => &mut self.core_memories,
+            ExternalKind::Global => &mut self.core_globals,
+            ExternalKind::Tag => &mut self.core_tags,
+        }
+    }
+
+    fn component(&mut self, kind: ComponentExternalKind) -> &mut Vec<Id> {
+        match kind {
+            ComponentExternalKind::Module => &mut self.core_modules,
+            ComponentExternalKind::Func => &mut self.funcs,
+            ComponentExternalKind::Value => &mut self.values,
+            ComponentExternalKind::Type => &mut self.types,
+            ComponentExternalKind::Instance => &mut self.instances,
+            ComponentExternalKind::Component => &mut self.components,
+        }
+    }
+
+    fn import(&mut self, ty: wasmparser::ComponentTypeRef) -> &mut Vec<Id> {
+        match ty {
+            wasmparser::ComponentTypeRef::Module(_) => &mut self.core_modules,
+            wasmparser::ComponentTypeRef::Func(_) => &mut self.funcs,
+            wasmparser::ComponentTypeRef::Value(_) => &mut self.values,
+            wasmparser::ComponentTypeRef::Type(_) => &mut self.types,
+            wasmparser::ComponentTypeRef::Instance(_) => &mut self.instances,
+            wasmparser::ComponentTypeRef::Component(_) => &mut self.components,
+        }
+    }
+}
+
+/// Add an edge to the item at the given index of an index space, if there is
+/// one. Outer aliases and invalid indices leave holes that aren't linked.
+fn link(items: &mut ir::ItemsBuilder, from: Id, space: &[Id], index: u32) {
+    if let Some(&to) = space.get(index as usize) {
+        items.add_edge(from, to);
+    }
+}
+
+/// Add an edge to the type that an import or export is described by, if it
+/// names one.
+fn link_type(
+    items: &mut ir::ItemsBuilder,
+    from: Id,
+    spaces: &IndexSpaces,
+    ty: wasmparser::ComponentTypeRef,
+) {
+    match ty {
+        wasmparser::ComponentTypeRef::Module(index) => link(items, from, &spaces.core_types, index),
+        wasmparser::ComponentTypeRef::Func(index)
+        | wasmparser::ComponentTypeRef::Instance(index)
+        | wasmparser::ComponentTypeRef::Component(index)
+        | wasmparser::ComponentTypeRef::Value(wasmparser::ComponentValType::Type(index))
+        | wasmparser::ComponentTypeRef::Type(wasmparser::TypeBounds::Eq(index)) => {
+            link(items, from, &spaces.types, index)
+        }
+        wasmparser::ComponentTypeRef::Value(wasmparser::ComponentValType::Primitive(_))
+        | wasmparser::ComponentTypeRef::Type(wasmparser::TypeBounds::SubResource) => {}
+    }
+}
+
+impl<'a> ComponentReader<'a> {
+    /// Read the sections of the wasm component in the given data.
+    pub fn new(data: &'a [u8]) -> Result<ComponentReader<'a>, traits::Error> {
+        let mut parser = wasmparser::Parser::new(0);
+        let mut offset = 0;
+        let mut header_size = 0;
+        let mut sections = vec![];
+        let mut sizes = HashMap::new();
+
+        loop {
+            let (payload, consumed) = match parser.parse(&data[offset..], true)? {
+                wasmparser::Chunk::Parsed { payload, consumed } => (payload, consumed),
+                wasmparser::Chunk::NeedMoreData(_) => {
+                    return Err(traits::Error::with_msg("unexpected end of wasm component"))
+                }
+            };
+            offset += consumed;
+
+            let mut size = consumed;
+            let section = match payload {
+                wasmparser::Payload::Version { .. } => {
+                    header_size = consumed as u32;
+                    continue;
+                }
+                wasmparser::Payload::End(_) => break,
+                wasmparser::Payload::ModuleSection {
+                    unchecked_range, ..
+                } => {
+                    // The nested module is skipped over here, and parsed on
+                    // its own later.
+                    let range = nested_range(data, unchecked_range)?;
+                    offset = range.end;
+                    size += range.len();
+                    Section::Module(range)
+                }
+                wasmparser::Payload::ComponentSection {
+                    unchecked_range, ..
+                } => {
+                    let range = nested_range(data, unchecked_range)?;
+                    offset = range.end;
+                    size += range.len();
+                    Section::Component(range)
+                }
+                wasmparser::Payload::CustomSection(reader) => Section::Custom(reader),
+                wasmparser::Payload::InstanceSection(reader) => Section::CoreInstance(reader),
+                wasmparser::Payload::CoreTypeSection(reader) => Section::CoreType(reader),
+                wasmparser::Payload::ComponentInstanceSection(reader) => Section::Instance(reader),
+                wasmparser::Payload::ComponentAliasSection(reader) => Section::Alias(reader),
+                wasmparser::Payload::ComponentTypeSection(reader) => Section::Type(reader),
+                wasmparser::Payload::ComponentCanonicalSection(reader) => {
+                    Section::Canonical(reader)
+                }
+                wasmparser::Payload::ComponentStartSection { start, .. } => Section::Start(start),
+                wasmparser::Payload::ComponentImportSection(reader) => Section::Import(reader),
+                wasmparser::Payload::ComponentExportSection(reader) => Section::Export(reader),
+                _ => {
+                    return Err(traits::Error::with_msg(
+                        "unsupported wasm section in a component",
+                    ))
+                }
+            };
+            sizes.insert(sections.len(), size as u32);
+            sections.push(IndexedSection(sections.len(), section));
+        }
+
+        Ok(ComponentReader {
+            data,
+            header_size,
+            sections,
+            sizes,
+            parts: HashMap::new(),
+        })
+    }
+
+    /// Get the names of the nested core modules and components from the
+    /// `component-name` custom section, keyed by their indices.
+    fn part_names(&self) -> Result<(Names<'a>, Names<'a>), traits::Error> {
+        let mut modules = HashMap::new();
+        let mut components = HashMap::new();
+        for IndexedSection(_, section) in &self.sections {
+            if let Section::Custom(reader) = section {
+                if let wasmparser::KnownCustom::ComponentName(reader) = reader.as_known() {
+                    for subsection in reader {
+                        let (names, map) = match subsection? {
+                            wasmparser::ComponentName::CoreModules(map) => (&mut modules, map),
+                            wasmparser::ComponentName::Components(map) => (&mut components, map),
+                            _ => continue,
+                        };
+                        for naming in map {
+                            let naming = naming?;
+                            names.insert(naming.index, naming.name);
+                        }
+                    }
+                }
+            }
+        }
+        Ok((modules, components))
+    }
+}
+
+impl<'a> Parse<'a> for ComponentReader<'a> {
+    /// Whether to attribute the nested modules' debug info to their functions.
+    type ItemsExtra = bool;
+
+    fn parse_items(
+        &mut self,
+        items: &mut ir::ItemsBuilder,
+        debug_info: bool,
+    ) -> Result<(), traits::Error> {
+        let id = Id::section(self.sections.len());
+        items.add_root(ir::Item::new(
+            id,
+            "wasm magic bytes",
+            self.header_size,
+            ir::Misc::new(),
+        ));
+
+        // A component may have any number of sections of each kind, so the
+        // headers of a kind that appears more than once are numbered.
+        let mut kinds = HashMap::new();
+        for IndexedSection(_, section) in &self.sections {
+            if let Some(kind) = section_kind(section) {
+                *kinds.entry(kind).or_insert(0) += 1;
+            }
+        }
+        let mut seen = HashMap::new();
+
+        // Entries are named after their indices in the component's index
+        // spaces, rather than in their sections.
+        let mut spaces = IndexSpaces::default();
+        for IndexedSection(idx, section) in &self.sections {
+            let idx = *idx;
+            let start = items.size_added();
+            match section {
+                Section::Custom(reader) => reader.clone().parse_items(items, (idx, 0))?,
+                // Nested modules and components are added last, below, because
+                // their items are given `Id`s after those of every other item.
+                Section::Module(_) | Section::Component(_) => continue,
+                Section::CoreInstance(reader) => {
+                    let base = spaces.core_instances.len();
+                    parse_entries(items, reader, idx, |i, _| {
+                        format!("core instance[{}]", base + i)
+                    })?;
+                }
+                Section::CoreType(reader) => {
+                    let mut next = spaces.core_types.len();
+                    parse_entries(items, reader, idx, |_, ty| {
+                        let name = format!("core type[{}]", next);
+                        next += core_type_count(ty);
+                        name
+                    })?;
+                }
+                Section::Instance(reader) => {
+                    let base = spaces.instances.len();
+                    parse_entries(items, reader, idx, |i, _| format!("instance[{}]", base + i))?;
+                }
+                Section::Alias(reader) => {
+                    parse_entries(items, reader, idx, |_, alias| alias_name(alias))?;
+                }
+                Section::Type(reader) => {
+                    let base = spaces.types.len();
+                    parse_entries(items, reader, idx, |i, _| format!("type[{}]", base + i))?;
+                }
+                Section::Canonical(reader) => {
+                    let (mut funcs, mut core_funcs) = (spaces.funcs.len(), spaces.core_funcs.len());
+                    parse_entries(items, reader, idx, |_, func| {
+                        if let wasmparser::CanonicalFunction::Lift { .. } = func {
+                            funcs += 1;
+                            format!("canonical function[{}]: lift", funcs - 1)
+                        } else {
+                            core_funcs += 1;
+                            format!(
+                                "canonical core function[{}]: {}",
+                                core_funcs - 1,
+                                canonical_name(func)
+                            )
+                        }
+                    })?;
+                }
+                Section::Start(_) => {
+                    let size = self.sizes[&idx];
+                    let id = Id::entry(idx, 0);
+                    items.add_root(ir::Item::new(
+                        id,
+                        "\"start\" section",
+                        size,
+                        ir::Misc::new(),
+                    ));
+                    count_entries(section, &mut spaces)?;
+                    continue;
+                }
+                Section::Import(reader) => {
+                    parse_entries(items, reader, idx, |_, import| {
+                        format!("import \"{}\"", import.name.name)
+                    })?;
+                }
+                Section::Export(reader) => {
+                    for (i, (export, size)) in iterate_with_size(reader)?.into_iter().enumerate() {
+                        let id = Id::entry(idx, i);
+                        let name = format!("export \"{}\"", export.name.name);
+                        items.add_root(ir::Item::new(id, name, size, ir::Misc::new()));
+                    }
+                }
+            }
+            count_entries(section, &mut spaces)?;
+
+            let kind = section_kind(section).expect("every other section has headers");
+            let name = if kinds[&kind] > 1 {
+                let n = seen.entry(kind.clone()).or_insert(0);
+                *n += 1;
+                format!("{}[{}] headers", kind, *n - 1)
+            } else {
+                format!("{} headers", kind)
+            };
+            let added = items.size_added() - start;
+            let size = self.sizes[&idx];
+            assert!(added <= size);
+            let id = Id::section(idx);
+            items.add_root(ir::Item::new(id, name, size - added, ir::Misc::new()));
+        }
+
+        let (module_names, component_names) = self.part_names()?;
+        let mut spaces = IndexSpaces::default();
+        for IndexedSection(idx, section) in &self.sections {
+            let part = match section {
+                Section::Module(range) => {
+                    let index = spaces.core_modules.len() as u32;
+                    spaces.core_modules.push(Id::entry(*idx, 0));
+                    let name = match module_names.get(&index) {
+                        Some(name) => format!("module \"{}\"", name),
+                        None => format!("module[{}]", index),
+                    };
+                    Some((name, range))
+                }
+                Section::Component(range) => {
+                    let index = spaces.components.len() as u32;
+                    spaces.components.push(Id::entry(*idx, 0));
+                    let name = match component_names.get(&index) {
+                        Some(name) => format!("component \"{}\"", name),
+                        None => format!("component[{}]", index),
+                    };
+                    Some((name, range))
+                }
+                _ => {
+                    count_entries(section, &mut spaces)?;
+                    None
+                }
+            };
+            if let Some((name, range)) = part {
+                let part = super::super::parse_wasm(&self.data[range.clone()], debug_info)
+                    .map_err(|e| e.context(format!("could not parse {}", name)))?;
+                let header = self.sizes[idx] - range.len() as u32;
+                let id = items.add_qualified_part(name, header, &part);
+                self.parts.insert(*idx, id);
+            }
+        }
+
+        Ok(())
+    }
+
+    type EdgesExtra = ();
+
+    fn parse_edges(
+        &mut self,
+        items: &mut ir::ItemsBuilder,
+        _extra: (),
+    ) -> Result<(), traits::Error> {
+        // Every reference in a component is to an item that was defined by an
+        // earlier section, so the index spaces are built up while the edges
+        // are drawn.
+        let mut spaces = IndexSpaces::default();
+        for IndexedSection(idx, section) in &self.sections {
+            let idx = *idx;
+            match section {
+                Section::Custom(_) => {}
+                Section::Module(_) => spaces.core_modules.push(self.parts[&idx]),
+                Section::Component(_) => spaces.components.push(self.parts[&idx]),
+                Section::CoreInstance(reader) => {
+                    for (i, instance) in reader.clone().into_iter().enumerate() {
+                        let id = Id::entry(idx, i);
+                        match instance? {
+                            wasmparser::Instance::Instantiate { module_index, args } => {
+                                link(items, id, &spaces.core_modules, module_index);
+                                for arg in args.iter() {
+                                    link(items, id, &spaces.core_instances, arg.index);
+                                }
+                            }
+                            wasmparser::Instance::FromExports(exports) => {
+                                for export in exports.iter() {
+                                    link(items, id, spaces.core(export.kind), export.index);
+                                }
+                            }
+                        }
+                        spaces.core_instances.push(id);
+                    }
+                }
+                Section::CoreType(reader) => {
+                    for (i, ty) in reader.clone().into_iter().enumerate() {
+                        for _ in 0..core_type_count(&ty?) {
+                            spaces.core_types.push(Id::entry(idx, i));
+                        }
+                    }
+                }
+                Section::Instance(reader) => {
+                    for (i, instance) in reader.clone().into_iter().enumerate() {
+                        let id = Id::entry(idx, i);
+                        match instance? {
+                            wasmparser::ComponentInstance::Instantiate {
+                                component_index,
+                                args,
+                            } => {
+                                link(items, id, &spaces.components, component_index);
+                                for arg in args.iter() {
+                                    link(items, id, spaces.component(arg.kind), arg.index);
+                                }
+                            }
+                            wasmparser::ComponentInstance::FromExports(exports) => {
+                                for export in exports.iter() {
+                                    link(items, id, spaces.component(export.kind), export.index);
+                                }
+                            }
+                        }
+                        spaces.instances.push(id);
+                    }
+                }
+                Section::Alias(reader) => {
+                    for (i, alias) in reader.clone().into_iter().enumerate() {
+                        let id = Id::entry(idx, i);
+                        match alias? {
+                            wasmparser::ComponentAlias::InstanceExport {
+                                kind,
+                                instance_index,
+                                ..
+                            } => {
+                                link(items, id, &spaces.instances, instance_index);
+                                spaces.component(kind).push(id);
+                            }
+                            wasmparser::ComponentAlias::CoreInstanceExport {
+                                kind,
+                                instance_index,
+                                ..
+                            } => {
+                                link(items, id, &spaces.core_instances, instance_index);
+                                spaces.core(kind).push(id);
+                            }
+                            wasmparser::ComponentAlias::Outer { kind, .. } => {
+                                outer_space(&mut spaces, kind).push(id);
+                            }
+                        }
+                    }
+                }
+                Section::Type(reader) => {
+                    for (i, ty) in reader.clone().into_iter().enumerate() {
+                        let id = Id::entry(idx, i);
+                        if let wasmparser::ComponentType::Resource {
+                            dtor: Some(dtor), ..
+                        } = ty?
+                        {
+                            link(items, id, &spaces.core_funcs, dtor);
+                        }
+                        spaces.types.push(id);
+                    }
+                }
+                Section::Canonical(reader) => {
+                    for (i, func) in reader.clone().into_iter().enumerate() {
+                        let id = Id::entry(idx, i);
+                        let func = func?;
+                        let options: &[wasmparser::CanonicalOption] = match func {
+                            wasmparser::CanonicalFunction::Lift {
+                                core_func_index,
+                                type_index,
+                                ref options,
+                            } => {
+                                link(items, id, &spaces.core_funcs, core_func_index);
+                                link(items, id, &spaces.types, type_index);
+                                options
+                            }
+                            wasmparser::CanonicalFunction::Lower {
+                                func_index,
+                                ref options,
+                            } => {
+                                link(items, id, &spaces.funcs, func_index);
+                                options
+                            }
+                            wasmparser::CanonicalFunction::ResourceNew { resource }
+                            | wasmparser::CanonicalFunction::ResourceDrop { resource }
+                            | wasmparser::CanonicalFunction::ResourceRep { resource } => {
+                                link(items, id, &spaces.types, resource);
+                                &[]
+                            }
+                            _ => &[],
+                        };
+                        for option in options {
+                            match *option {
+                                wasmparser::CanonicalOption::Memory(memory) => {
+                                    link(items, id, &spaces.core_memories, memory);
+                                }
+                                wasmparser::CanonicalOption::Realloc(func)
+                                | wasmparser::CanonicalOption::PostReturn(func)
+                                | wasmparser::CanonicalOption::Callback(func) => {
+                                    link(items, id, &spaces.core_funcs, func);
+                                }
+                                wasmparser::CanonicalOption::CoreType(ty) => {
+                                    link(items, id, &spaces.core_types, ty);
+                                }
+                                _ => {}
+                            }
+                        }
+                        if let wasmparser::CanonicalFunction::Lift { .. } = func {
+                            spaces.funcs.push(id);
+                        } else {
+                            spaces.core_funcs.push(id);
+                        }
+                    }
+                }
+                Section::Start(start) => {
+                    let id = Id::entry(idx, 0);
+                    link(items, id, &spaces.funcs, start.func_index);
+                    for &arg in start.arguments.iter() {
+                        link(items, id, &spaces.values, arg);
+                    }
+                    for _ in 0..start.results {
+                        spaces.values.push(id);
+                    }
+                }
+                Section::Import(reader) => {
+                    for (i, import) in reader.clone().into_iter().enumerate() {
+                        let import = import?;
+                        let id = Id::entry(idx, i);
+                        link_type(items, id, &spaces, import.ty);
+                        spaces.import(import.ty).push(id);
+                    }
+                }
+                Section::Export(reader) => {
+                    // Exports are the component's roots. Each export also adds
+                    // a new item to its kind's index space.
+                    for (i, export) in reader.clone().into_iter().enumerate() {
+                        let export = export?;
+                        let id = Id::entry(idx, i);
+                        link(items, id, spaces.component(export.kind), export.index);
+                        if let Some(ty) = export.ty {
+                            link_type(items, id, &spaces, ty);
+                        }
+                        spaces.component(export.kind).push(id);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that a nested module or component is within the outer component's
+/// data.
+fn nested_range(data: &[u8], range: Range<u64>) -> Result<Range<usize>, traits::Error> {
+    let range = range.start as usize..range.end as usize;
+    if range.end > data.len() {
+        return Err(traits::Error::with_msg(
+            "nested wasm module or component is out of bounds",
+        ));
+    }
+    Ok(range)
+}
+
+/// Add a placeholder for each item that a section adds to the component's index
+/// spaces, other than nested modules and components, so that items can be
+/// numbered the same way as the component's own references number them.
+fn count_entries(section: &Section<'_>, spaces: &mut IndexSpaces) -> Result<(), traits::Error> {
+    let placeholder = Id::root();
+    match section {
+        Section::CoreInstance(reader) => {
+            for _ in 0..reader.count() {
+                spaces.core_instances.push(placeholder);
+            }
+        }
+        Section::CoreType(reader) => {
+            for ty in reader.clone() {
+                for _ in 0..core_type_count(&ty?) {
+                    spaces.core_types.push(placeholder);
+                }
+            }
+        }
+        Section::Instance(reader) => {
+            for _ in 0..reader.count() {
+                spaces.instances.push(placeholder);
+            }
+        }
+        Section::Type(reader) => {
+            for _ in 0..reader.count() {
+                spaces.types.push(placeholder);
+            }
+        }
+        Section::Canonical(reader) => {
+            for func in reader.clone() {
+                if let wasmparser::CanonicalFunction::Lift { .. } = func? {
+                    spaces.funcs.push(placeholder);
+                } else {
+                    spaces.core_funcs.push(placeholder);
+                }
+            }
+        }
+        Section::Start(start) => {
+            for _ in 0..start.results {
+                spaces.values.push(placeholder);
+            }
+        }
+        Section::Import(reader) => {
+            for import in reader.clone() {
+                spaces.import(import?.ty).push(placeholder);
+            }
+        }
+        Section::Alias(reader) => {
+            for alias in reader.clone() {
+                match alias? {
+                    wasmparser::ComponentAlias::InstanceExport { kind, .. } => {
+                        spaces.component(kind).push(placeholder);
+                    }
+                    wasmparser::ComponentAlias::CoreInstanceExport { kind, .. } => {
+                        spaces.core(kind).push(placeholder);
+                    }
+                    wasmparser::ComponentAlias::Outer { kind, .. } => {
+                        outer_space(spaces, kind).push(placeholder);
+                    }
+                }
+            }
+        }
+        Section::Export(reader) => {
+            for export in reader.clone() {
+                spaces.component(export?.kind).push(placeholder);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn outer_space(
+    spaces: &mut IndexSpaces,
+    kind: wasmparser::ComponentOuterAliasKind,
+) -> &mut Vec<Id> {
+    match kind {
+        wasmparser::ComponentOuterAliasKind::CoreModule => &mut spaces.core_modules,
+        wasmparser::ComponentOuterAliasKind::CoreType => &mut spaces.core_types,
+        wasmparser::ComponentOuterAliasKind::Type => &mut spaces.types,
+        wasmparser::ComponentOuterAliasKind::Component => &mut spaces.components,
+    }
+}
+
+/// Add an item for each entry of a component section, named by the given
+/// function.
+fn parse_entries<'a, T, F>(
+    items: &mut ir::ItemsBuilder,
+    reader: &wasmparser::SectionLimited<'a, T>,
+    idx: usize,
+    mut name: F,
+) -> Result<(), traits::Error>
+where
+    T: wasmparser::FromReader<'a>,
+    F: FnMut(usize, &T) -> String,
+{
+    for (i, (entry, size)) in iterate_with_size(reader)?.into_iter().enumerate() {
+        let id = Id::entry(idx, i);
+        items.add_item(ir::Item::new(id, name(i, &entry), size, ir::Misc::new()));
+    }
+    Ok(())
+}
+
+/// The kind of a section, which its headers are named after, unless it is a
+/// nested module or component, or a start section, which have no headers.
+fn section_kind(section: &Section<'_>) -> Option<String> {
+    let kind = match section {
+        Section::Custom(reader) => return Some(format!("custom section '{}'", reader.name())),
+        Section::Module(_) | Section::Component(_) | Section::Start(_) => return None,
+        Section::CoreInstance(_) => "core instance section",
+        Section::CoreType(_) => "core type section",
+        Section::Instance(_) => "instance section",
+        Section::Alias(_) => "alias section",
+        Section::Type(_) => "type section",
+        Section::Canonical(_) => "canonical function section",
+        Section::Import(_) => "import section",
+        Section::Export(_) => "export section",
+    };
+    Some(kind.to_string())
+}
+
+/// The number of types that a core type section entry defines.
+fn core_type_count(ty: &wasmparser::CoreType<'_>) -> usize {
+    match ty {
+        wasmparser::CoreType::Rec(group) => group.types().len(),
+        wasmparser::CoreType::Module(_) => 1,
+    }
+}
+
+fn alias_name(alias: &wasmparser::ComponentAlias<'_>) -> String {
+    match alias {
+        wasmparser::ComponentAlias::InstanceExport { name, .. } => {
+            format!("alias of instance export \"{}\"", name)
+        }
+        wasmparser::ComponentAlias::CoreInstanceExport { name, .. } => {
+            format!("alias of core instance export \"{}\"", name)
+        }
+        wasmparser::ComponentAlias::Outer { index, .. } => {
+            format!("alias of outer item[{}]", index)
+        }
+    }
+}
+
+fn canonical_name(func: &wasmparser::CanonicalFunction) -> &'static str {
+    match func {
+        wasmparser::CanonicalFunction::Lift { .. } => "lift",
+        wasmparser::CanonicalFunction::Lower { .. } => "lower",
+        wasmparser::CanonicalFunction::ResourceNew { .. } => "resource.new",
+        wasmparser::CanonicalFunction::ResourceDrop { .. } => "resource.drop",
+        wasmparser::CanonicalFunction::ResourceRep { .. } => "resource.rep",
+        _ => "builtin",
+    }
+}
@@ -1,61 +1,245 @@
 use super::Parse;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ops::Range;
 use twiggy_ir::{self as ir, Id};
 use twiggy_traits as traits;
-use wasmparser::SectionWithLimitedItems;
-use wasmparser::{self, Operator, SectionReader, Type};
+use wasmparser::{self, Operator};
+
+mod component;
+mod pointers;
+
+pub use self::component::ComponentReader;
 
 #[derive(Default)]
 pub struct SectionIndices {
-    type_: Option<usize>,
     code: Option<usize>,
+    types: Vec<Id>,
     functions: Vec<Id>,
     tables: Vec<Id>,
     memories: Vec<Id>,
     globals: Vec<Id>,
+    tags: Vec<Id>,
+    elements: Vec<Id>,
+    data: Vec<Id>,
+
+    // Every type in the type index space, and the type of each tag.
+    sub_types: Vec<wasmparser::SubType>,
+    tag_types: Vec<u32>,
+
+    // The index of the first type with an identical signature, for each entry
+    // in the type index space. `call_indirect` checks types structurally, so
+    // two type indices with the same signature must be treated as equal.
+    canonical_types: Vec<u32>,
+    // The canonical type index of each function in the function index space.
+    function_types: Vec<u32>,
+    // The function indices placed into each table by active element segments,
+    // less those overwritten by later segments.
+    table_elements: HashMap<u32, BTreeSet<u32>>,
+    // Tables that are imported or exported, and can therefore be read or
+    // mutated by code outside of this module, or whose elements are accessed
+    // by instructions other than `call_indirect`, such as `table.get`.
+    exposed_tables: HashSet<u32>,
+    // The (table index, canonical type index) pairs used by `call_indirect`
+    // instructions anywhere in the code section.
+    indirect_calls: HashSet<(u32, u32)>,
+}
+
+impl SectionIndices {
+    /// Get the functions that a `call_indirect` with the given type and table
+    /// immediates may dispatch to.
+    fn indirect_call_targets<'a>(
+        &'a self,
+        type_index: u32,
+        table_index: u32,
+    ) -> impl Iterator<Item = Id> + 'a {
+        let ty = self.canonical_types.get(type_index as usize).cloned();
+        self.table_elements
+            .get(&table_index)
+            .into_iter()
+            .flat_map(|elems| elems.iter())
+            .filter(move |&&f| ty.is_some() && self.function_types.get(f as usize) == ty.as_ref())
+            .map(move |&f| self.functions[f as usize])
+    }
+
+    /// Is the given function, stored in the given table, kept alive by
+    /// `call_indirect` instructions rather than by the table itself?
+    ///
+    /// This is only the case for tables that are private to this module, and
+    /// whose elements are only used by `call_indirect`: if the table is
+    /// imported or exported, the host can call anything in it, and a function
+    /// read out of the table by `table.get` or `table.copy`, or replaced by
+    /// `table.set`, `table.grow`, `table.fill`, or `table.init`, can't be
+    /// tracked by its type.
+    fn is_retained_by_indirect_calls(&self, table_index: u32, func_index: u32) -> bool {
+        if self.exposed_tables.contains(&table_index) {
+            return false;
+        }
+        let in_table = self
+            .table_elements
+            .get(&table_index)
+            .is_some_and(|elems| elems.contains(&func_index));
+        if !in_table {
+            return false;
+        }
+        match self.function_types.get(func_index as usize) {
+            Some(&ty) => self.indirect_calls.contains(&(table_index, ty)),
+            None => false,
+        }
+    }
 }
 
-struct IndexedSection<'a>(usize, wasmparser::Section<'a>);
+/// A section of a wasm module, along with whatever `wasmparser` read from its
+/// header.
+enum Section<'a> {
+    Custom(wasmparser::CustomSectionReader<'a>),
+    Type(wasmparser::TypeSectionReader<'a>),
+    Import(wasmparser::ImportSectionReader<'a>),
+    Function(wasmparser::FunctionSectionReader<'a>),
+    Table(wasmparser::TableSectionReader<'a>),
+    Memory(wasmparser::MemorySectionReader<'a>),
+    Tag(wasmparser::TagSectionReader<'a>),
+    Global(wasmparser::GlobalSectionReader<'a>),
+    Export(wasmparser::ExportSectionReader<'a>),
+    Start {
+        func: u32,
+        range: Range<u64>,
+    },
+    Element(wasmparser::ElementSectionReader<'a>),
+    DataCount {
+        range: Range<u64>,
+    },
+    Data(wasmparser::DataSectionReader<'a>),
+    /// The code section's contents, and each function body along with its
+    /// size, including the size that is prefixed to it.
+    Code {
+        #[cfg_attr(not(feature = "dwarf"), allow(dead_code))]
+        range: Range<u64>,
+        bodies: Vec<(wasmparser::FunctionBody<'a>, u32)>,
+    },
+}
 
-impl<'a> Parse<'a> for wasmparser::ModuleReader<'a> {
-    type ItemsExtra = ();
+struct IndexedSection<'a>(usize, Section<'a>);
+
+/// A wasm module, split up into its sections.
+pub struct ModuleReader<'a> {
+    /// The size of the magic number and version that the module starts with.
+    header_size: u32,
+    sections: Vec<IndexedSection<'a>>,
+    /// The size of each section, including its id and size.
+    sizes: HashMap<usize, u32>,
+}
 
+impl<'a> ModuleReader<'a> {
+    /// Read the sections of the wasm module in the given data.
+    pub fn new(data: &'a [u8]) -> Result<ModuleReader<'a>, traits::Error> {
+        let mut parser = wasmparser::Parser::new(0);
+        let mut offset = 0;
+        let mut header_size = 0;
+        let mut sections = vec![];
+        let mut sizes = HashMap::new();
+
+        loop {
+            let (payload, consumed) = match parser.parse(&data[offset..], true)? {
+                wasmparser::Chunk::Parsed { payload, consumed } => (payload, consumed),
+                wasmparser::Chunk::NeedMoreData(_) => {
+                    return Err(traits::Error::with_msg("unexpected end of wasm module"))
+                }
+            };
+            offset += consumed;
+
+            let section = match payload {
+                wasmparser::Payload::Version { .. } => {
+                    header_size = consumed as u32;
+                    continue;
+                }
+                wasmparser::Payload::End(_) => break,
+                wasmparser::Payload::CodeSectionEntry(body) => {
+                    // The bodies are part of the code section that was
+                    // started by the last section.
+                    let last = sections.len() - 1;
+                    if let Some(IndexedSection(_, Section::Code { bodies, .. })) =
+                        sections.last_mut()
+                    {
+                        bodies.push((body, consumed as u32));
+                    }
+                    *sizes.entry(last).or_insert(0) += consumed as u32;
+                    continue;
+                }
+                wasmparser::Payload::CustomSection(reader) => Section::Custom(reader),
+                wasmparser::Payload::TypeSection(reader) => Section::Type(reader),
+                wasmparser::Payload::ImportSection(reader) => Section::Import(reader),
+                wasmparser::Payload::FunctionSection(reader) => Section::Function(reader),
+                wasmparser::Payload::TableSection(reader) => Section::Table(reader),
+                wasmparser::Payload::MemorySection(reader) => Section::Memory(reader),
+                wasmparser::Payload::TagSection(reader) => Section::Tag(reader),
+                wasmparser::Payload::GlobalSection(reader) => Section::Global(reader),
+                wasmparser::Payload::ExportSection(reader) => Section::Export(reader),
+                wasmparser::Payload::StartSection { func, range } => Section::Start { func, range },
+                wasmparser::Payload::ElementSection(reader) => Section::Element(reader),
+                wasmparser::Payload::DataCountSection { range, .. } => Section::DataCount { range },
+                wasmparser::Payload::DataSection(reader) => Section::Data(reader),
+                wasmparser::Payload::CodeSectionStart {
+                    unchecked_range, ..
+                } => Section::Code {
+                    range: unchecked_range,
+                    bodies: vec![],
+                },
+                wasmparser::Payload::UnknownSection { id, .. } => {
+                    return Err(traits::Error::with_msg(format!(
+                        "unknown wasm section with id {}",
+                        id
+                    )))
+                }
+                _ => {
+                    return Err(traits::Error::with_msg(
+                        "unsupported wasm section in a module",
+                    ))
+                }
+            };
+            sizes.insert(sections.len(), consumed as u32);
+            sections.push(IndexedSection(sections.len(), section));
+        }
+
+        Ok(ModuleReader {
+            header_size,
+            sections,
+            sizes,
+        })
+    }
+}
+
+impl<'a> Parse<'a> for ModuleReader<'a> {
+    /// Whether to attribute the DWARF debug info to the functions that it
+    /// describes.
+    type ItemsExtra = bool;
+
+    #[cfg_attr(not(feature = "dwarf"), allow(unused_variables))]
     fn parse_items(
         &mut self,
         items: &mut ir::ItemsBuilder,
-        _extra: (),
+        debug_info: bool,
     ) -> Result<(), traits::Error> {
-        let initial_offset = self.current_position();
-        let mut sections: Vec<IndexedSection<'_>> = Vec::new();
-        let mut code_section: Option<IndexedSection<'_>> = None;
-        let mut function_section: Option<IndexedSection<'_>> = None;
-        let mut sizes: HashMap<usize, u32> = HashMap::new();
+        let sizes = &self.sizes;
+        let mut sections: Vec<&IndexedSection<'_>> = Vec::new();
+        let mut code_section: Option<&IndexedSection<'_>> = None;
+        let mut function_section: Option<&IndexedSection<'_>> = None;
 
         // The function and code sections must be handled differently, so these
         // are not placed in the same `sections` array as the rest.
-        let mut idx = 0;
-        while !self.eof() {
-            let start = self.current_position();
-            let section = self.read()?;
-            let size = self.current_position() - start;
-            let indexed_section = IndexedSection(idx, section);
-            match indexed_section.1.code {
-                wasmparser::SectionCode::Code => code_section = Some(indexed_section),
-                wasmparser::SectionCode::Function => function_section = Some(indexed_section),
-                _ => sections.push(indexed_section),
-            };
-            sizes.insert(idx, size as u32);
-            idx += 1;
+        for section in self.sections.iter() {
+            match section.1 {
+                Section::Code { .. } => code_section = Some(section),
+                Section::Function(_) => function_section = Some(section),
+                _ => sections.push(section),
+            }
         }
 
-        let sections_cnt = sections.len()
-            + if code_section.is_some() { 1 } else { 0 }
-            + if function_section.is_some() { 1 } else { 0 };
+        let sections_cnt = self.sections.len();
         let id = Id::section(sections_cnt);
         items.add_root(ir::Item::new(
             id,
             "wasm magic bytes".to_string(),
-            initial_offset as u32,
+            self.header_size,
             ir::Misc::new(),
         ));
 
@@ -67,77 +251,100 @@ impl<'a> Parse<'a> for wasmparser::ModuleReader<'a> {
         let names = parse_names_section(&sections)?;
         let imported_functions = count_imported_functions(&sections)?;
 
+        // Data segments are split up into the symbols of the `linking` custom
+        // section, or failing that, the static variables described by DWARF.
+        #[cfg_attr(not(feature = "dwarf"), allow(unused_mut))]
+        let mut data_symbols = parse_data_symbols(&sections)?;
+        #[cfg(feature = "dwarf")]
+        for (segment, variables) in parse_data_variables(&sections)? {
+            data_symbols.entry(segment).or_insert(variables);
+        }
+
+        #[cfg(feature = "dwarf")]
+        let bodies = match code_section {
+            Some(code_section) => code_ranges(code_section),
+            None => vec![],
+        };
+
         // Next, we parse the function and code sections together, so that we
         // can collapse corresponding entries from the code and function
         // sections into a single representative IR item.
         match (function_section, code_section) {
             (Some(function_section), Some(code_section)) => (function_section, code_section)
-                .parse_items(items, (imported_functions, &names, &sizes))?,
+                .parse_items(items, (imported_functions, &names, sizes))?,
+            (None, None) => {}
             _ => Err(traits::Error::with_msg(
                 "function or code section is missing",
             ))?,
         };
 
-        for IndexedSection(idx, section) in sections.into_iter() {
+        // Break the DWARF debugging information in the `.debug_*` custom
+        // sections down by compilation unit and function, if there is any and
+        // we were asked to. The bytes that are attributed to them are carved
+        // out of the custom sections' items below.
+        #[cfg(feature = "dwarf")]
+        let debug_info = parse_debug_info(items, &sections, &bodies, sections_cnt + 1, debug_info)?;
+        #[cfg(not(feature = "dwarf"))]
+        let debug_info: HashMap<&str, u64> = HashMap::new();
+
+        for &IndexedSection(idx, ref section) in sections.into_iter() {
             let start = items.size_added();
-            let name = get_section_name(&section);
-            match section.code {
-                wasmparser::SectionCode::Custom { name, .. } => {
-                    CustomSectionReader(name, section).parse_items(items, idx)?;
-                }
-                wasmparser::SectionCode::Type => {
-                    section.get_type_section_reader()?.parse_items(items, idx)?;
-                }
-                wasmparser::SectionCode::Import => {
-                    section
-                        .get_import_section_reader()?
-                        .parse_items(items, idx)?;
-                }
-                wasmparser::SectionCode::Table => {
-                    section
-                        .get_table_section_reader()?
-                        .parse_items(items, idx)?;
-                }
-                wasmparser::SectionCode::Memory => {
-                    section
-                        .get_memory_section_reader()?
-                        .parse_items(items, idx)?;
-                }
-                wasmparser::SectionCode::Global => {
-                    section
-                        .get_global_section_reader()?
-                        .parse_items(items, idx)?;
-                }
-                wasmparser::SectionCode::Export => {
-                    section
-                        .get_export_section_reader()?
-                        .parse_items(items, idx)?;
-                }
-                wasmparser::SectionCode::Start => {
-                    StartSection(section).parse_items(items, idx)?;
-                }
-                wasmparser::SectionCode::Element => {
-                    section
-                        .get_element_section_reader()?
-                        .parse_items(items, idx)?;
-                }
-                wasmparser::SectionCode::Data => {
-                    section.get_data_section_reader()?.parse_items(items, idx)?;
-                }
-                wasmparser::SectionCode::DataCount => {
-                    DataCountSection(section).parse_items(items, idx)?;
-                }
-                wasmparser::SectionCode::Code | wasmparser::SectionCode::Function => {
+            let name = get_section_name(section);
+            let carved = match section {
+                Section::Custom(reader) => {
+                    debug_info.get(reader.name()).cloned().unwrap_or(0) as u32
+                }
+                _ => 0,
+            };
+            match section {
+                Section::Custom(reader) => {
+                    reader.clone().parse_items(items, (idx, carved))?;
+                }
+                Section::Type(reader) => {
+                    reader.clone().parse_items(items, idx)?;
+                }
+                Section::Import(reader) => {
+                    reader.clone().parse_items(items, idx)?;
+                }
+                Section::Table(reader) => {
+                    reader.clone().parse_items(items, idx)?;
+                }
+                Section::Memory(reader) => {
+                    reader.clone().parse_items(items, idx)?;
+                }
+                Section::Tag(reader) => {
+                    reader.clone().parse_items(items, idx)?;
+                }
+                Section::Global(reader) => {
+                    reader.clone().parse_items(items, idx)?;
+                }
+                Section::Export(reader) => {
+                    reader.clone().parse_items(items, idx)?;
+                }
+                Section::Start { func, range } => {
+                    StartSection(*func, range.clone()).parse_items(items, idx)?;
+                }
+                Section::Element(reader) => {
+                    reader.clone().parse_items(items, idx)?;
+                }
+                Section::Data(reader) => {
+                    reader.clone().parse_items(items, (idx, &data_symbols))?;
+                }
+                Section::DataCount { range } => {
+                    DataCountSection(range.clone()).parse_items(items, idx)?;
+                }
+                Section::Code { .. } | Section::Function(_) => {
                     unreachable!("unexpected code or function section found");
                 }
             };
             let id = Id::section(idx);
-            let added = items.size_added() - start;
+            let added = items.size_added() - start + carved;
             let size = sizes
                 .get(&idx)
                 .ok_or_else(|| traits::Error::with_msg("Could not find section size"))?;
             assert!(added <= *size);
             items.add_root(ir::Item::new(id, name, size - added, ir::Misc::new()));
+            items.add_entries_to_section(idx, get_binary_section_name(section));
         }
 
         Ok(())
@@ -150,21 +357,16 @@ impl<'a> Parse<'a> for wasmparser::ModuleReader<'a> {
         items: &mut ir::ItemsBuilder,
         _extra: (),
     ) -> Result<(), traits::Error> {
-        let mut sections: Vec<IndexedSection<'_>> = Vec::new();
-        let mut code_section: Option<IndexedSection<'a>> = None;
-        let mut function_section: Option<IndexedSection<'a>> = None;
-
-        let mut idx = 0;
-        while !self.eof() {
-            let section = self.read()?;
-            match section.code {
-                wasmparser::SectionCode::Code => code_section = Some(IndexedSection(idx, section)),
-                wasmparser::SectionCode::Function => {
-                    function_section = Some(IndexedSection(idx, section))
-                }
-                _ => sections.push(IndexedSection(idx, section)),
-            };
-            idx += 1;
+        let mut sections: Vec<&IndexedSection<'_>> = Vec::new();
+        let mut code_section: Option<&IndexedSection<'_>> = None;
+        let mut function_section: Option<&IndexedSection<'_>> = None;
+
+        for section in self.sections.iter() {
+            match section.1 {
+                Section::Code { .. } => code_section = Some(section),
+                Section::Function(_) => function_section = Some(section),
+                _ => sections.push(section),
+            }
         }
 
         // Like above we do some preprocessing here before actually drawing all
@@ -173,120 +375,231 @@ impl<'a> Parse<'a> for wasmparser::ModuleReader<'a> {
         // the wasm module. To handle that we build up all this data in
         // `SectionIndices` here as we parse all the various sections.
         let mut indices = SectionIndices::default();
-        for IndexedSection(idx, section) in sections.iter() {
-            match section.code {
-                wasmparser::SectionCode::Type => {
-                    indices.type_ = Some(*idx);
-                }
-                wasmparser::SectionCode::Import => {
-                    let reader = section.get_import_section_reader()?;
-                    for (i, import) in reader.into_iter().enumerate() {
-                        let id = Id::entry(*idx, i);
+        for &&IndexedSection(idx, ref section) in sections.iter() {
+            match section {
+                Section::Type(reader) => {
+                    let mut types: Vec<Option<wasmparser::FuncType>> = Vec::new();
+                    for (i, rec_group) in reader.clone().into_iter().enumerate() {
+                        for ty in rec_group?.into_types() {
+                            indices.sub_types.push(ty.clone());
+                            let ty = match ty.composite_type.inner {
+                                wasmparser::CompositeInnerType::Func(ty) => Some(ty),
+                                _ => None,
+                            };
+                            let canonical = types
+                                .iter()
+                                .position(|t| ty.is_some() && *t == ty)
+                                .unwrap_or(types.len());
+                            indices.canonical_types.push(canonical as u32);
+                            indices.types.push(Id::entry(idx, i));
+                            types.push(ty);
+                        }
+                    }
+                }
+                Section::Import(reader) => {
+                    for (i, import) in reader.clone().into_imports().enumerate() {
+                        let id = Id::entry(idx, i);
                         match import?.ty {
-                            wasmparser::ImportSectionEntryType::Function(_) => {
+                            wasmparser::TypeRef::Func(type_ref)
+                            | wasmparser::TypeRef::FuncExact(type_ref) => {
                                 indices.functions.push(id);
+                                indices.function_types.push(type_ref);
                             }
-                            wasmparser::ImportSectionEntryType::Table(_) => {
+                            wasmparser::TypeRef::Table(_) => {
+                                indices.exposed_tables.insert(indices.tables.len() as u32);
                                 indices.tables.push(id);
                             }
-                            wasmparser::ImportSectionEntryType::Memory(_) => {
+                            wasmparser::TypeRef::Memory(_) => {
                                 indices.memories.push(id);
                             }
-                            wasmparser::ImportSectionEntryType::Global(_) => {
+                            wasmparser::TypeRef::Global(_) => {
                                 indices.globals.push(id);
                             }
+                            wasmparser::TypeRef::Tag(tag) => {
+                                indices.tags.push(id);
+                                indices.tag_types.push(tag.func_type_idx);
+                            }
                         }
                     }
                 }
-                wasmparser::SectionCode::Global => {
-                    for i in 0..section.get_global_section_reader()?.get_count() {
-                        let id = Id::entry(*idx, i as usize);
-                        indices.globals.push(id);
+                Section::Global(reader) => {
+                    for i in 0..reader.count() {
+                        indices.globals.push(Id::entry(idx, i as usize));
+                    }
+                }
+                Section::Memory(reader) => {
+                    for i in 0..reader.count() {
+                        indices.memories.push(Id::entry(idx, i as usize));
                     }
                 }
-                wasmparser::SectionCode::Memory => {
-                    for i in 0..section.get_memory_section_reader()?.get_count() {
-                        let id = Id::entry(*idx, i as usize);
-                        indices.memories.push(id);
+                Section::Table(reader) => {
+                    for i in 0..reader.count() {
+                        indices.tables.push(Id::entry(idx, i as usize));
                     }
                 }
-                wasmparser::SectionCode::Table => {
-                    for i in 0..section.get_table_section_reader()?.get_count() {
-                        let id = Id::entry(*idx, i as usize);
-                        indices.tables.push(id);
+                Section::Tag(reader) => {
+                    for (i, tag) in reader.clone().into_iter().enumerate() {
+                        indices.tags.push(Id::entry(idx, i));
+                        indices.tag_types.push(tag?.func_type_idx);
                     }
                 }
-                wasmparser::SectionCode::Code => {
-                    Err(traits::Error::with_msg("unexpected code section"))?
+                Section::Data(reader) => {
+                    for i in 0..reader.count() {
+                        indices.data.push(Id::entry(idx, i as usize));
+                    }
+                }
+                Section::Export(reader) => {
+                    for exp in reader.clone() {
+                        let exp = exp?;
+                        if let wasmparser::ExternalKind::Table = exp.kind {
+                            indices.exposed_tables.insert(exp.index);
+                        }
+                    }
+                }
+                Section::Element(reader) => {
+                    // The slots of each table that are filled by segments at
+                    // constant offsets. A later segment may overwrite the
+                    // functions that an earlier one placed in the table, and
+                    // those can no longer be called through it.
+                    let mut slots: HashMap<u32, BTreeMap<u64, u32>> = HashMap::new();
+                    for (i, elem) in reader.clone().into_iter().enumerate() {
+                        let elem = elem?;
+                        indices.elements.push(Id::entry(idx, i));
+                        if let wasmparser::ElementKind::Active {
+                            table_index,
+                            ref offset_expr,
+                        } = elem.kind
+                        {
+                            let table_index = table_index.unwrap_or(0);
+                            let functions = element_functions(&elem.items)?;
+                            let offset = match const_offset(offset_expr)? {
+                                Some(offset) => offset,
+                                None => {
+                                    let table = indices.table_elements.entry(table_index);
+                                    table.or_default().extend(functions.into_iter().flatten());
+                                    continue;
+                                }
+                            };
+                            let table = slots.entry(table_index).or_default();
+                            for (slot, func) in (offset..).zip(functions) {
+                                match func {
+                                    Some(func) => table.insert(slot, func),
+                                    None => table.remove(&slot),
+                                };
+                            }
+                        }
+                    }
+                    for (table_index, table) in slots {
+                        let elems = indices.table_elements.entry(table_index).or_default();
+                        elems.extend(table.into_values());
+                    }
                 }
-                wasmparser::SectionCode::Function => {
+                Section::Code { .. } => Err(traits::Error::with_msg("unexpected code section"))?,
+                Section::Function(_) => {
                     Err(traits::Error::with_msg("unexpected function section"))?
                 }
                 _ => {}
             }
         }
-        if let (Some(IndexedSection(_, function_section)), Some(IndexedSection(code_idx, _))) =
-            (function_section.as_ref(), code_section.as_ref())
+        if let (
+            Some(IndexedSection(_, Section::Function(function_reader))),
+            Some(IndexedSection(code_idx, Section::Code { bodies, .. })),
+        ) = (function_section, code_section)
         {
             indices.code = Some(*code_idx);
-            for i in 0..function_section.get_function_section_reader()?.get_count() {
-                let id = Id::entry(*code_idx, i as usize);
+            for (i, type_ref) in function_reader.clone().into_iter().enumerate() {
+                let id = Id::entry(*code_idx, i);
                 indices.functions.push(id);
+                indices.function_types.push(type_ref?);
             }
-        }
 
-        match (function_section, code_section) {
-            (Some(function_section), Some(code_section)) => {
-                (function_section, code_section).parse_edges(items, &indices)?
+            // Canonicalize the functions' type indices now that we have seen
+            // both the imported and the defined functions.
+            for ty in indices.function_types.iter_mut() {
+                *ty = indices
+                    .canonical_types
+                    .get(*ty as usize)
+                    .cloned()
+                    .unwrap_or(*ty);
             }
-            _ => panic!("function or code section is missing"),
-        };
-        for IndexedSection(idx, section) in sections.into_iter() {
-            match section.code {
-                wasmparser::SectionCode::Custom { name, .. } => {
-                    CustomSectionReader(name, section).parse_edges(items, ())?;
+
+            for (body, _) in bodies {
+                for op in body.get_operators_reader()? {
+                    match op? {
+                        Operator::CallIndirect {
+                            type_index,
+                            table_index,
+                        }
+                        | Operator::ReturnCallIndirect {
+                            type_index,
+                            table_index,
+                        } => {
+                            if let Some(&ty) = indices.canonical_types.get(type_index as usize) {
+                                indices.indirect_calls.insert((table_index, ty));
+                            }
+                        }
+                        Operator::TableGet { table }
+                        | Operator::TableSet { table }
+                        | Operator::TableGrow { table }
+                        | Operator::TableFill { table }
+                        | Operator::TableInit { table, .. } => {
+                            indices.exposed_tables.insert(table);
+                        }
+                        Operator::TableCopy {
+                            dst_table,
+                            src_table,
+                        } => {
+                            indices.exposed_tables.insert(dst_table);
+                            indices.exposed_tables.insert(src_table);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if let (Some(function_section), Some(code_section)) = (function_section, code_section) {
+            (function_section, code_section).parse_edges(items, &indices)?;
+        }
+        for &IndexedSection(idx, ref section) in sections.into_iter() {
+            match section {
+                Section::Custom(reader) => {
+                    reader.clone().parse_edges(items, ())?;
                 }
-                wasmparser::SectionCode::Type => {
-                    section.get_type_section_reader()?.parse_edges(items, ())?;
+                Section::Type(reader) => {
+                    reader.clone().parse_edges(items, ())?;
                 }
-                wasmparser::SectionCode::Import => {
-                    section
-                        .get_import_section_reader()?
-                        .parse_edges(items, ())?;
+                Section::Import(reader) => {
+                    reader.clone().parse_edges(items, ())?;
                 }
-                wasmparser::SectionCode::Table => {
-                    section.get_table_section_reader()?.parse_edges(items, ())?;
+                Section::Table(reader) => {
+                    reader.clone().parse_edges(items, (&indices, idx))?;
                 }
-                wasmparser::SectionCode::Memory => {
-                    section
-                        .get_memory_section_reader()?
-                        .parse_edges(items, ())?;
+                Section::Memory(reader) => {
+                    reader.clone().parse_edges(items, ())?;
                 }
-                wasmparser::SectionCode::Global => {
-                    section
-                        .get_global_section_reader()?
-                        .parse_edges(items, ())?;
+                Section::Tag(reader) => {
+                    reader.clone().parse_edges(items, (&indices, idx))?;
                 }
-                wasmparser::SectionCode::Export => {
-                    section
-                        .get_export_section_reader()?
-                        .parse_edges(items, (&indices, idx))?;
+                Section::Global(reader) => {
+                    reader.clone().parse_edges(items, (&indices, idx))?;
                 }
-                wasmparser::SectionCode::Start => {
-                    StartSection(section).parse_edges(items, (&indices, idx))?;
+                Section::Export(reader) => {
+                    reader.clone().parse_edges(items, (&indices, idx))?;
                 }
-                wasmparser::SectionCode::Element => {
-                    section
-                        .get_element_section_reader()?
-                        .parse_edges(items, (&indices, idx))?;
+                Section::Start { func, range } => {
+                    StartSection(*func, range.clone()).parse_edges(items, (&indices, idx))?;
                 }
-                wasmparser::SectionCode::Data => {
-                    section.get_data_section_reader()?.parse_edges(items, ())?;
+                Section::Element(reader) => {
+                    reader.clone().parse_edges(items, (&indices, idx))?;
                 }
-                wasmparser::SectionCode::DataCount => {
-                    DataCountSection(section).parse_edges(items, ())?;
+                Section::Data(reader) => {
+                    reader.clone().parse_edges(items, (&indices, idx))?;
                 }
-                wasmparser::SectionCode::Code | wasmparser::SectionCode::Function => {
+                Section::DataCount { range } => {
+                    DataCountSection(range.clone()).parse_edges(items, ())?;
+                }
+                Section::Code { .. } | Section::Function(_) => {
                     unreachable!("unexpected code or function section found");
                 }
             }
@@ -296,41 +609,227 @@ impl<'a> Parse<'a> for wasmparser::ModuleReader<'a> {
     }
 }
 
-fn get_section_name(section: &wasmparser::Section<'_>) -> String {
-    match section.code {
-        wasmparser::SectionCode::Custom { name, .. } => {
-            format!("custom section '{}' headers", name)
+fn get_section_name(section: &Section<'_>) -> String {
+    match section {
+        Section::Custom(reader) => format!("custom section '{}' headers", reader.name()),
+        Section::Type(_) => "type section headers".to_string(),
+        Section::Import(_) => "import section headers".to_string(),
+        Section::Function(_) => "function section headers".to_string(),
+        Section::Table(_) => "table section headers".to_string(),
+        Section::Memory(_) => "memory section headers".to_string(),
+        Section::Tag(_) => "tag section headers".to_string(),
+        Section::Global(_) => "global section headers".to_string(),
+        Section::Export(_) => "export section headers".to_string(),
+        Section::Start { .. } => "start section headers".to_string(),
+        Section::Element(_) => "element section headers".to_string(),
+        Section::Code { .. } => "code section headers".to_string(),
+        Section::Data(_) => "data section headers".to_string(),
+        Section::DataCount { .. } => "data count section headers".to_string(),
+    }
+}
+
+/// Get the name of a section in the wasm spec, or the name of a custom section,
+/// which is what section budgets refer to it by.
+fn get_binary_section_name<'a>(section: &Section<'a>) -> &'a str {
+    match section {
+        Section::Custom(reader) => reader.name(),
+        Section::Type(_) => "type",
+        Section::Import(_) => "import",
+        Section::Function(_) => "function",
+        Section::Table(_) => "table",
+        Section::Memory(_) => "memory",
+        Section::Tag(_) => "tag",
+        Section::Global(_) => "global",
+        Section::Export(_) => "export",
+        Section::Start { .. } => "start",
+        Section::Element(_) => "element",
+        Section::Code { .. } => "code",
+        Section::Data(_) => "data",
+        Section::DataCount { .. } => "datacount",
+    }
+}
+
+/// Get the range of the code section that each function body occupies, and
+/// the id of the body's item. DWARF for wasm addresses code by its offset from
+/// the start of the code section's contents, and takes each function to begin
+/// after the size that is prefixed to its body.
+#[cfg(feature = "dwarf")]
+fn code_ranges(IndexedSection(idx, section): &IndexedSection) -> Vec<(Range<u64>, Id)> {
+    match section {
+        Section::Code { range, bodies } => bodies
+            .iter()
+            .enumerate()
+            .map(|(i, (body, _))| {
+                let body = body.range();
+                let body = body.start - range.start..body.end - range.start;
+                (body, Id::entry(*idx, i))
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+#[cfg(feature = "dwarf")]
+fn parse_debug_info<'a>(
+    items: &mut ir::ItemsBuilder,
+    indexed_sections: &[&IndexedSection<'a>],
+    bodies: &[(Range<u64>, Id)],
+    debug_info_idx: usize,
+    attribute_debug_info: bool,
+) -> Result<HashMap<&'static str, u64>, traits::Error> {
+    let mut sections = HashMap::new();
+    for IndexedSection(_, section) in indexed_sections.iter() {
+        if let Section::Custom(reader) = section {
+            if reader.name().starts_with(".debug_") {
+                sections.insert(reader.name(), reader.data());
+            }
+        }
+    }
+    if !sections.contains_key(".debug_info") {
+        return Ok(HashMap::new());
+    }
+
+    super::object_parse::parse_wasm_debug_info(
+        items,
+        &sections,
+        bodies,
+        debug_info_idx,
+        attribute_debug_info,
+    )
+}
+
+/// A named range of a data segment, such as a static variable, that is given
+/// an item of its own.
+pub struct DataSymbol {
+    name: String,
+    ty: Option<String>,
+    offset: u32,
+    size: u32,
+}
+
+/// Get the defined data symbols of the `linking` custom section, keyed by the
+/// index of the segment that each one is in.
+fn parse_data_symbols(
+    indexed_sections: &[&IndexedSection<'_>],
+) -> Result<HashMap<u32, Vec<DataSymbol>>, traits::Error> {
+    let mut symbols: HashMap<u32, Vec<DataSymbol>> = HashMap::new();
+    for IndexedSection(_, section) in indexed_sections.iter() {
+        if let Section::Custom(reader) = section {
+            if let wasmparser::KnownCustom::Linking(reader) = reader.as_known() {
+                for subsection in reader {
+                    let table = match subsection? {
+                        wasmparser::Linking::SymbolTable(table) => table,
+                        _ => continue,
+                    };
+                    for symbol in table {
+                        if let wasmparser::SymbolInfo::Data {
+                            name,
+                            symbol: Some(symbol),
+                            ..
+                        } = symbol?
+                        {
+                            symbols.entry(symbol.index).or_default().push(DataSymbol {
+                                name: name.to_string(),
+                                ty: None,
+                                offset: symbol.offset,
+                                size: symbol.size,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(symbols)
+}
+
+/// Get the static variables described by DWARF, keyed by the index of the
+/// active data segment that each one is in.
+#[cfg(feature = "dwarf")]
+fn parse_data_variables(
+    indexed_sections: &[&IndexedSection<'_>],
+) -> Result<HashMap<u32, Vec<DataSymbol>>, traits::Error> {
+    let mut sections = HashMap::new();
+    let mut segments = vec![];
+    for IndexedSection(_, section) in indexed_sections.iter() {
+        match section {
+            Section::Custom(reader) if reader.name().starts_with(".debug_") => {
+                sections.insert(reader.name(), reader.data());
+            }
+            Section::Data(reader) => {
+                for (i, d) in reader.clone().into_iter().enumerate() {
+                    let d = d?;
+                    if let Some(address) = data_address(&d)? {
+                        let end = address + d.data.len() as u64;
+                        segments.push((i as u32, address..end));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if !sections.contains_key(".debug_info") {
+        return Ok(HashMap::new());
+    }
+
+    let mut symbols: HashMap<u32, Vec<DataSymbol>> = HashMap::new();
+    for variable in super::object_parse::parse_wasm_variables(&sections)? {
+        let end = variable.address.saturating_add(variable.size);
+        let segment = segments
+            .iter()
+            .find(|(_, range)| range.start <= variable.address && end <= range.end);
+        if let Some((index, range)) = segment {
+            symbols.entry(*index).or_default().push(DataSymbol {
+                name: variable.name,
+                ty: variable.ty,
+                offset: (variable.address - range.start) as u32,
+                size: variable.size as u32,
+            });
         }
-        wasmparser::SectionCode::Type => "type section headers".to_string(),
-        wasmparser::SectionCode::Import => "import section headers".to_string(),
-        wasmparser::SectionCode::Function => "function section headers".to_string(),
-        wasmparser::SectionCode::Table => "table section headers".to_string(),
-        wasmparser::SectionCode::Memory => "memory section headers".to_string(),
-        wasmparser::SectionCode::Global => "global section headers".to_string(),
-        wasmparser::SectionCode::Export => "export section headers".to_string(),
-        wasmparser::SectionCode::Start => "start section headers".to_string(),
-        wasmparser::SectionCode::Element => "element section headers".to_string(),
-        wasmparser::SectionCode::Code => "code section headers".to_string(),
-        wasmparser::SectionCode::Data => "data section headers".to_string(),
-        wasmparser::SectionCode::DataCount => "data count section headers".to_string(),
+    }
+    Ok(symbols)
+}
+
+/// Get the constant address that an active data segment of the first memory
+/// is placed at. Only the first memory's segments are linked to their
+/// addresses, since loads from other memories aren't tracked.
+fn data_address(d: &wasmparser::Data<'_>) -> Result<Option<u64>, traits::Error> {
+    if let wasmparser::DataKind::Active {
+        memory_index: 0,
+        ref offset_expr,
+    } = d.kind
+    {
+        return const_offset(offset_expr);
+    }
+    Ok(None)
+}
+
+/// Get the value of a constant expression that is a single constant integer,
+/// such as the offset of most data and element segments.
+fn const_offset(expr: &wasmparser::ConstExpr<'_>) -> Result<Option<u64>, traits::Error> {
+    match expr.get_operators_reader().read()? {
+        Operator::I32Const { value } => Ok(Some(u64::from(value as u32))),
+        Operator::I64Const { value } => Ok(Some(value as u64)),
+        _ => Ok(None),
     }
 }
 
 fn parse_names_section<'a>(
-    indexed_sections: &[IndexedSection<'a>],
+    indexed_sections: &[&IndexedSection<'a>],
 ) -> Result<HashMap<usize, &'a str>, traits::Error> {
     let mut names = HashMap::new();
     for IndexedSection(_, section) in indexed_sections.iter() {
-        if let wasmparser::SectionCode::Custom { name: "name", .. } = section.code {
-            for subsection in section.get_name_section_reader()? {
-                let f = match subsection? {
-                    wasmparser::Name::Function(f) => f,
-                    _ => continue,
-                };
-                let mut map = f.get_map()?;
-                for _ in 0..map.get_count() {
-                    let naming = map.read()?;
-                    names.insert(naming.index as usize, naming.name);
+        if let Section::Custom(reader) = section {
+            if let wasmparser::KnownCustom::Name(reader) = reader.as_known() {
+                for subsection in reader {
+                    let map = match subsection? {
+                        wasmparser::Name::Function(map) => map,
+                        _ => continue,
+                    };
+                    for naming in map {
+                        let naming = naming?;
+                        names.insert(naming.index as usize, naming.name);
+                    }
                 }
             }
         }
@@ -339,14 +838,17 @@ fn parse_names_section<'a>(
 }
 
 fn count_imported_functions<'a>(
-    indexed_sections: &[IndexedSection<'a>],
+    indexed_sections: &[&IndexedSection<'a>],
 ) -> Result<usize, traits::Error> {
     let mut imported_functions = 0;
     for IndexedSection(_, section) in indexed_sections.iter() {
-        if let wasmparser::SectionCode::Import = section.code {
-            for import in section.get_import_section_reader()? {
-                if let wasmparser::ImportSectionEntryType::Function(_) = import?.ty {
-                    imported_functions += 1;
+        if let Section::Import(reader) = section {
+            for import in reader.clone().into_imports() {
+                match import?.ty {
+                    wasmparser::TypeRef::Func(_) | wasmparser::TypeRef::FuncExact(_) => {
+                        imported_functions += 1;
+                    }
+                    _ => {}
                 }
             }
         }
@@ -354,7 +856,52 @@ fn count_imported_functions<'a>(
     Ok(imported_functions)
 }
 
-impl<'a> Parse<'a> for (IndexedSection<'a>, IndexedSection<'a>) {
+/// Get the functions that an element segment's items refer to, or `None` for
+/// the items that are null references.
+fn element_functions(
+    elements: &wasmparser::ElementItems<'_>,
+) -> Result<Vec<Option<u32>>, traits::Error> {
+    let mut functions = vec![];
+    match elements {
+        wasmparser::ElementItems::Functions(reader) => {
+            for func_idx in reader.clone() {
+                functions.push(Some(func_idx?));
+            }
+        }
+        wasmparser::ElementItems::Expressions(_ty, reader) => {
+            for expr in reader.clone() {
+                let mut func = None;
+                for op in expr?.get_operators_reader() {
+                    if let Operator::RefFunc { function_index } = op? {
+                        func = Some(function_index);
+                    }
+                }
+                functions.push(func);
+            }
+        }
+    }
+    Ok(functions)
+}
+
+/// Get the items that a constant expression refers to: the functions of its
+/// `ref.func`s and the globals of its `global.get`s.
+fn const_expr_refs(
+    expr: &wasmparser::ConstExpr<'_>,
+    indices: &SectionIndices,
+) -> Result<Vec<Id>, traits::Error> {
+    let mut refs = vec![];
+    for op in expr.get_operators_reader() {
+        let to = match op? {
+            Operator::RefFunc { function_index } => indices.functions.get(function_index as usize),
+            Operator::GlobalGet { global_index } => indices.globals.get(global_index as usize),
+            _ => None,
+        };
+        refs.extend(to.cloned());
+    }
+    Ok(refs)
+}
+
+impl<'a> Parse<'a> for (&IndexedSection<'a>, &IndexedSection<'a>) {
     type ItemsExtra = (usize, &'a HashMap<usize, &'a str>, &'a HashMap<usize, u32>);
 
     fn parse_items(
@@ -363,54 +910,66 @@ impl<'a> Parse<'a> for (IndexedSection<'a>, IndexedSection<'a>) {
         (imported_functions, names, sizes): Self::ItemsExtra,
     ) -> Result<(), traits::Error> {
         let (
-            IndexedSection(func_section_idx, func_section),
-            IndexedSection(code_section_idx, code_section),
-        ) = self;
-
-        let mut func_reader = func_section.get_function_section_reader()?;
-        let mut code_reader = code_section.get_code_section_reader()?;
+            IndexedSection(func_section_idx, Section::Function(func_reader)),
+            IndexedSection(code_section_idx, code_section @ Section::Code { bodies, .. }),
+        ) = self
+        else {
+            unreachable!("expected a function and a code section");
+        };
 
-        let func_items: Vec<ir::Item> = iterate_with_size(&mut func_reader)
+        let func_items: Vec<ir::Item> = iterate_with_size(func_reader)?
+            .into_iter()
             .enumerate()
-            .map(|(i, func)| {
-                let (_func, size) = func?;
+            .map(|(i, (_func, size))| {
                 let id = Id::entry(*func_section_idx, i);
                 let name = format!("func[{}]", i);
-                let item = ir::Item::new(id, name, size, ir::Misc::new());
-                Ok(item)
+                ir::Item::new(id, name, size, ir::Misc::new())
             })
-            .collect::<Result<_, traits::Error>>()?;
+            .collect();
 
-        let code_items: Vec<ir::Item> = iterate_with_size(&mut code_reader)
-            .zip(func_items.into_iter())
+        let code_items: Vec<ir::Item> = bodies
+            .iter()
+            .zip(func_items.iter())
             .enumerate()
-            .map(|(i, (body, func))| {
-                let (_body, size) = body?;
+            .map(|(i, ((_body, size), func))| {
                 let id = Id::entry(*code_section_idx, i);
                 let name = names
                     .get(&(i + imported_functions))
                     .map_or_else(|| format!("code[{}]", i), |name| name.to_string());
                 let code = ir::Code::new(&name);
-                let item = ir::Item::new(id, name, size + func.size(), code);
-                Ok(item)
+                ir::Item::new(id, name, size + func.size(), code)
             })
-            .collect::<Result<_, traits::Error>>()?;
+            .collect();
 
         let start = items.size_added();
         let name = get_section_name(code_section);
-        for item in code_items.into_iter() {
-            items.add_item(item);
+        let (mut bodies_size, mut funcs_size) = (0, 0);
+        for ((item, (_body, body_size)), func) in
+            code_items.into_iter().zip(bodies).zip(&func_items)
+        {
+            let id = items.add_item(item);
+            items.add_to_section(id, "code", *body_size);
+            items.add_to_section(id, "function", func.size());
+            bodies_size += body_size;
+            funcs_size += func.size();
         }
         let id = Id::section(*code_section_idx);
         let added = items.size_added() - start;
-        let size = sizes
-            .get(&code_section_idx)
-            .ok_or_else(|| traits::Error::with_msg("Could not find section size"))?
-            + sizes
-                .get(&func_section_idx)
-                .ok_or_else(|| traits::Error::with_msg("Could not find section size"))?;
+        let section_size = |idx| {
+            sizes
+                .get(idx)
+                .cloned()
+                .ok_or_else(|| traits::Error::with_msg("Could not find section size"))
+        };
+        let (code_size, func_size) = (
+            section_size(code_section_idx)?,
+            section_size(func_section_idx)?,
+        );
+        let size = code_size + func_size;
         assert!(added <= size);
         items.add_root(ir::Item::new(id, name, size - added, ir::Misc::new()));
+        items.add_to_section(id, "code", code_size - bodies_size);
+        items.add_to_section(id, "function", func_size - funcs_size);
 
         Ok(())
     }
@@ -422,21 +981,22 @@ impl<'a> Parse<'a> for (IndexedSection<'a>, IndexedSection<'a>) {
         items: &mut ir::ItemsBuilder,
         indices: Self::EdgesExtra,
     ) -> Result<(), traits::Error> {
-        let (IndexedSection(_, function_section), IndexedSection(code_section_idx, code_section)) =
-            self;
-
-        let mut func_reader = function_section.get_function_section_reader()?;
-        let mut code_reader = code_section.get_code_section_reader()?;
+        let (
+            IndexedSection(_, Section::Function(func_reader)),
+            IndexedSection(code_section_idx, Section::Code { bodies, .. }),
+        ) = self
+        else {
+            unreachable!("expected a function and a code section");
+        };
 
         type Edge = (ir::Id, ir::Id);
 
         let mut edges: Vec<Edge> = Vec::new();
 
         // Function section reader parsing.
-        for (func_i, type_ref) in iterate_with_size(&mut func_reader).enumerate() {
-            let (type_ref, _) = type_ref?;
-            if let Some(type_idx) = indices.type_ {
-                let type_id = Id::entry(type_idx, type_ref as usize);
+        for (func_i, type_ref) in func_reader.clone().into_iter().enumerate() {
+            let type_ref = type_ref?;
+            if let Some(&type_id) = indices.types.get(type_ref as usize) {
                 if let Some(code_idx) = indices.code {
                     let body_id = Id::entry(code_idx, func_i);
                     edges.push((body_id, type_id));
@@ -445,50 +1005,87 @@ impl<'a> Parse<'a> for (IndexedSection<'a>, IndexedSection<'a>) {
         }
 
         // Code section reader parsing.
-        for (b_i, body) in iterate_with_size(&mut code_reader).enumerate() {
-            let (body, _size) = body?;
+        let imported_functions = indices.functions.len().saturating_sub(bodies.len());
+        for (b_i, (body, _size)) in bodies.iter().enumerate() {
             let body_id = Id::entry(*code_section_idx, b_i);
 
-            let mut cache = None;
+            // Static data is referenced by the constant addresses that the
+            // body computes.
+            if let Some(&ty) = indices.function_types.get(imported_functions + b_i) {
+                for data_id in pointers::data_references(items, indices, body, ty)? {
+                    edges.push((body_id, data_id));
+                }
+            }
+
             for op in body.get_operators_reader()? {
-                let prev = cache.take();
                 match op? {
-                    Operator::Call { function_index } => {
+                    Operator::Call { function_index }
+                    | Operator::ReturnCall { function_index }
+                    | Operator::RefFunc { function_index } => {
                         let f_id = indices.functions[function_index as usize];
                         edges.push((body_id, f_id));
                     }
 
-                    // TODO: Rather than looking at indirect calls, need to look
-                    // at where the vtables get initialized and/or vtable
-                    // indices get pushed onto the stack.
-                    Operator::CallIndirect { .. } => continue,
+                    // An indirect call may dispatch to any function in the
+                    // table whose signature matches the call's type. A
+                    // function dispatching to itself doesn't retain anything.
+                    Operator::CallIndirect {
+                        type_index,
+                        table_index,
+                    }
+                    | Operator::ReturnCallIndirect {
+                        type_index,
+                        table_index,
+                    } => {
+                        for f_id in indices.indirect_call_targets(type_index, table_index) {
+                            if f_id != body_id {
+                                edges.push((body_id, f_id));
+                            }
+                        }
+                    }
 
-                    Operator::GetGlobal { global_index } | Operator::SetGlobal { global_index } => {
+                    Operator::GlobalGet { global_index } | Operator::GlobalSet { global_index } => {
                         let g_id = indices.globals[global_index as usize];
                         edges.push((body_id, g_id));
                     }
 
-                    Operator::I32Load { memarg }
-                    | Operator::I32Load8S { memarg }
-                    | Operator::I32Load8U { memarg }
-                    | Operator::I32Load16S { memarg }
-                    | Operator::I32Load16U { memarg }
-                    | Operator::I64Load { memarg }
-                    | Operator::I64Load8S { memarg }
-                    | Operator::I64Load8U { memarg }
-                    | Operator::I64Load16S { memarg }
-                    | Operator::I64Load16U { memarg }
-                    | Operator::I64Load32S { memarg }
-                    | Operator::I64Load32U { memarg }
-                    | Operator::F32Load { memarg }
-                    | Operator::F64Load { memarg } => {
-                        if let Some(Operator::I32Const { value }) = prev {
-                            if let Some(data_id) = items.get_data(value as u32 + memarg.offset) {
-                                edges.push((body_id, data_id));
+                    // Passive data segments are only used by the code that
+                    // copies them into memory or drops them.
+                    Operator::MemoryInit { data_index, .. } | Operator::DataDrop { data_index } => {
+                        if let Some(&d_id) = indices.data.get(data_index as usize) {
+                            edges.push((body_id, d_id));
+                        }
+                    }
+
+                    // Likewise for passive element segments and tables.
+                    Operator::TableInit { elem_index, .. } | Operator::ElemDrop { elem_index } => {
+                        if let Some(&e_id) = indices.elements.get(elem_index as usize) {
+                            edges.push((body_id, e_id));
+                        }
+                    }
+
+                    // Tags are retained by the code that throws or catches them.
+                    Operator::Throw { tag_index } | Operator::Catch { tag_index } => {
+                        if let Some(&t_id) = indices.tags.get(tag_index as usize) {
+                            edges.push((body_id, t_id));
+                        }
+                    }
+                    Operator::TryTable { try_table } => {
+                        for catch in try_table.catches {
+                            match catch {
+                                wasmparser::Catch::One { tag, .. }
+                                | wasmparser::Catch::OneRef { tag, .. } => {
+                                    if let Some(&t_id) = indices.tags.get(tag as usize) {
+                                        edges.push((body_id, t_id));
+                                    }
+                                }
+                                wasmparser::Catch::All { .. }
+                                | wasmparser::Catch::AllRef { .. } => {}
                             }
                         }
                     }
-                    other => cache = Some(other),
+
+                    _ => {}
                 }
             }
         }
@@ -510,20 +1107,36 @@ impl<'a> Parse<'a> for wasmparser::NameSectionReader<'a> {
         idx: usize,
     ) -> Result<(), traits::Error> {
         let mut i = 0;
-        while !self.eof() {
-            let start = self.original_position();
-            let subsection = self.read()?;
-            let size = (self.original_position() - start) as u32;
+        loop {
+            let start = self.sections.original_position();
+            let subsection = match self.next() {
+                Some(subsection) => subsection?,
+                None => break,
+            };
+            let size = (self.sections.original_position() - start) as u32;
             let name = match subsection {
-                wasmparser::Name::Module(_) => "\"module name\" subsection",
-                wasmparser::Name::Function(_) => "\"function names\" subsection",
-                wasmparser::Name::Local(_) => "\"local names\" subsection",
+                wasmparser::Name::Module { .. } => "\"module name\" subsection".to_string(),
+                wasmparser::Name::Function(_) => "\"function names\" subsection".to_string(),
+                wasmparser::Name::Local(_) => "\"local names\" subsection".to_string(),
+                wasmparser::Name::Label(_) => "\"label names\" subsection".to_string(),
+                wasmparser::Name::Type(_) => "\"type names\" subsection".to_string(),
+                wasmparser::Name::Table(_) => "\"table names\" subsection".to_string(),
+                wasmparser::Name::Memory(_) => "\"memory names\" subsection".to_string(),
+                wasmparser::Name::Global(_) => "\"global names\" subsection".to_string(),
+                wasmparser::Name::Element(_) => "\"elem segment names\" subsection".to_string(),
+                wasmparser::Name::Data(_) => "\"data segment names\" subsection".to_string(),
+                wasmparser::Name::Field(_) => "\"field names\" subsection".to_string(),
+                wasmparser::Name::Tag(_) => "\"tag names\" subsection".to_string(),
+                wasmparser::Name::Parameter(_) => "\"parameter names\" subsection".to_string(),
+                wasmparser::Name::TagParameter(_) => {
+                    "\"tag parameter names\" subsection".to_string()
+                }
+                wasmparser::Name::Unknown { ty, .. } => format!("unknown subsection {}", ty),
             };
             let id = Id::entry(idx, i);
             items.add_root(ir::Item::new(id, name, size, ir::DebugInfo::new()));
             i += 1;
         }
-
         Ok(())
     }
 
@@ -534,24 +1147,23 @@ impl<'a> Parse<'a> for wasmparser::NameSectionReader<'a> {
     }
 }
 
-struct CustomSectionReader<'a>(&'a str, wasmparser::Section<'a>);
-
-impl<'a> Parse<'a> for CustomSectionReader<'a> {
-    type ItemsExtra = usize;
+impl<'a> Parse<'a> for wasmparser::CustomSectionReader<'a> {
+    /// The section's index, and the number of its bytes that belong to other
+    /// items, such as the debug info items for each function.
+    type ItemsExtra = (usize, u32);
 
     fn parse_items(
         &mut self,
         items: &mut ir::ItemsBuilder,
-        idx: usize,
+        (idx, carved): (usize, u32),
     ) -> Result<(), traits::Error> {
-        let name = self.0;
-        if name == "name" {
-            self.1.get_name_section_reader()?.parse_items(items, idx)?;
+        if let wasmparser::KnownCustom::Name(mut reader) = self.as_known() {
+            reader.parse_items(items, idx)?;
         } else {
-            let range = self.1.get_binary_reader().range();
-            let size = (range.end - range.start) as u32;
+            let range = self.data_range();
+            let size = (range.end - range.start) as u32 - carved;
             let id = Id::entry(idx, 0);
-            let name = format!("custom section '{}'", self.0);
+            let name = format!("custom section '{}'", self.name());
             items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
         }
         Ok(())
@@ -572,34 +1184,21 @@ impl<'a> Parse<'a> for wasmparser::TypeSectionReader<'a> {
         items: &mut ir::ItemsBuilder,
         idx: usize,
     ) -> Result<(), traits::Error> {
-        for (i, ty) in iterate_with_size(self).enumerate() {
-            let (ty, size) = ty?;
+        // Each entry in the type section is a recursion group of one or more
+        // types, and gets a single item.
+        let mut type_index = 0;
+        for (i, (rec_group, size)) in iterate_with_size(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
-
-            let mut name = format!("type[{}]: (", i);
-            for (i, param) in ty.params.iter().enumerate() {
-                if i != 0 {
-                    name.push_str(", ");
-                }
-                name.push_str(ty2str(*param));
-            }
-            name.push_str(") -> ");
-
-            match ty.returns.len() {
-                0 => name.push_str("nil"),
-                1 => name.push_str(ty2str(ty.returns[0])),
-                _ => {
-                    name.push_str("(");
-                    for (i, result) in ty.returns.iter().enumerate() {
-                        if i != 0 {
-                            name.push_str(", ");
-                        }
-                        name.push_str(ty2str(*result));
-                    }
-                    name.push_str(")");
-                }
-            }
-
+            let types: Vec<_> = rec_group.into_types().collect();
+            let name = match types.as_slice() {
+                [ty] => format!("type[{}]: {}", type_index, type2str(ty)),
+                _ => format!(
+                    "rec group type[{}..{}]",
+                    type_index,
+                    type_index + types.len()
+                ),
+            };
+            type_index += types.len();
             items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
         }
         Ok(())
@@ -620,10 +1219,11 @@ impl<'a> Parse<'a> for wasmparser::ImportSectionReader<'a> {
         items: &mut ir::ItemsBuilder,
         idx: usize,
     ) -> Result<(), traits::Error> {
-        for (i, imp) in iterate_with_size(self).enumerate() {
-            let (imp, size) = imp?;
+        let end = self.range().end;
+        let imports = with_sizes(self.clone().into_imports_with_offsets(), end)?;
+        for (i, (imp, size)) in imports.into_iter().enumerate() {
             let id = Id::entry(idx, i);
-            let name = format!("import {}::{}", imp.module, imp.field);
+            let name = format!("import {}::{}", imp.module, imp.name);
             items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
         }
         Ok(())
@@ -644,8 +1244,7 @@ impl<'a> Parse<'a> for wasmparser::TableSectionReader<'a> {
         items: &mut ir::ItemsBuilder,
         idx: usize,
     ) -> Result<(), traits::Error> {
-        for (i, entry) in iterate_with_size(self).enumerate() {
-            let (_entry, size) = entry?;
+        for (i, (_entry, size)) in iterate_with_size(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
             let name = format!("table[{}]", i);
             items.add_root(ir::Item::new(id, name, size, ir::Misc::new()));
@@ -653,9 +1252,22 @@ impl<'a> Parse<'a> for wasmparser::TableSectionReader<'a> {
         Ok(())
     }
 
-    type EdgesExtra = ();
+    type EdgesExtra = (&'a SectionIndices, usize);
 
-    fn parse_edges(&mut self, _: &mut ir::ItemsBuilder, _: ()) -> Result<(), traits::Error> {
+    fn parse_edges(
+        &mut self,
+        items: &mut ir::ItemsBuilder,
+        (indices, idx): Self::EdgesExtra,
+    ) -> Result<(), traits::Error> {
+        // A table may be initialized with an expression, such as a `ref.func`,
+        // that every one of its elements starts out as.
+        for (i, table) in self.clone().into_iter().enumerate() {
+            if let wasmparser::TableInit::Expr(expr) = table?.init {
+                for to in const_expr_refs(&expr, indices)? {
+                    items.add_edge(Id::entry(idx, i), to);
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -668,8 +1280,7 @@ impl<'a> Parse<'a> for wasmparser::MemorySectionReader<'a> {
         items: &mut ir::ItemsBuilder,
         idx: usize,
     ) -> Result<(), traits::Error> {
-        for (i, mem) in iterate_with_size(self).enumerate() {
-            let (_mem, size) = mem?;
+        for (i, (_mem, size)) in iterate_with_size(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
             let name = format!("memory[{}]", i);
             items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
@@ -684,6 +1295,38 @@ impl<'a> Parse<'a> for wasmparser::MemorySectionReader<'a> {
     }
 }
 
+impl<'a> Parse<'a> for wasmparser::TagSectionReader<'a> {
+    type ItemsExtra = usize;
+
+    fn parse_items(
+        &mut self,
+        items: &mut ir::ItemsBuilder,
+        idx: usize,
+    ) -> Result<(), traits::Error> {
+        for (i, (_tag, size)) in iterate_with_size(self)?.into_iter().enumerate() {
+            let id = Id::entry(idx, i);
+            let name = format!("tag[{}]", i);
+            items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
+        }
+        Ok(())
+    }
+
+    type EdgesExtra = (&'a SectionIndices, usize);
+
+    fn parse_edges(
+        &mut self,
+        items: &mut ir::ItemsBuilder,
+        (indices, idx): Self::EdgesExtra,
+    ) -> Result<(), traits::Error> {
+        for (i, tag) in self.clone().into_iter().enumerate() {
+            if let Some(&type_id) = indices.types.get(tag?.func_type_idx as usize) {
+                items.add_edge(Id::entry(idx, i), type_id);
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<'a> Parse<'a> for wasmparser::GlobalSectionReader<'a> {
     type ItemsExtra = usize;
 
@@ -692,19 +1335,27 @@ impl<'a> Parse<'a> for wasmparser::GlobalSectionReader<'a> {
         items: &mut ir::ItemsBuilder,
         idx: usize,
     ) -> Result<(), traits::Error> {
-        for (i, g) in iterate_with_size(self).enumerate() {
-            let (g, size) = g?;
+        for (i, (g, size)) in iterate_with_size(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
             let name = format!("global[{}]", i);
-            let ty = ty2str(g.ty.content_type).to_string();
+            let ty = g.ty.content_type.to_string();
             items.add_item(ir::Item::new(id, name, size, ir::Data::new(Some(ty))));
         }
         Ok(())
     }
 
-    type EdgesExtra = ();
+    type EdgesExtra = (&'a SectionIndices, usize);
 
-    fn parse_edges(&mut self, _: &mut ir::ItemsBuilder, _: ()) -> Result<(), traits::Error> {
+    fn parse_edges(
+        &mut self,
+        items: &mut ir::ItemsBuilder,
+        (indices, idx): Self::EdgesExtra,
+    ) -> Result<(), traits::Error> {
+        for (i, global) in self.clone().into_iter().enumerate() {
+            for to in const_expr_refs(&global?.init_expr, indices)? {
+                items.add_edge(Id::entry(idx, i), to);
+            }
+        }
         Ok(())
     }
 }
@@ -717,10 +1368,9 @@ impl<'a> Parse<'a> for wasmparser::ExportSectionReader<'a> {
         items: &mut ir::ItemsBuilder,
         idx: usize,
     ) -> Result<(), traits::Error> {
-        for (i, exp) in iterate_with_size(self).enumerate() {
-            let (exp, size) = exp?;
+        for (i, (exp, size)) in iterate_with_size(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
-            let name = format!("export \"{}\"", exp.field);
+            let name = format!("export \"{}\"", exp.name);
             items.add_root(ir::Item::new(id, name, size, ir::Misc::new()));
         }
         Ok(())
@@ -733,11 +1383,11 @@ impl<'a> Parse<'a> for wasmparser::ExportSectionReader<'a> {
         items: &mut ir::ItemsBuilder,
         (indices, idx): Self::EdgesExtra,
     ) -> Result<(), traits::Error> {
-        for (i, exp) in iterate_with_size(self).enumerate() {
-            let (exp, _) = exp?;
+        for (i, exp) in self.clone().into_iter().enumerate() {
+            let exp = exp?;
             let exp_id = Id::entry(idx, i);
             match exp.kind {
-                wasmparser::ExternalKind::Function => {
+                wasmparser::ExternalKind::Func | wasmparser::ExternalKind::FuncExact => {
                     items.add_edge(exp_id, indices.functions[exp.index as usize]);
                 }
                 wasmparser::ExternalKind::Table => {
@@ -749,6 +1399,11 @@ impl<'a> Parse<'a> for wasmparser::ExportSectionReader<'a> {
                 wasmparser::ExternalKind::Global => {
                     items.add_edge(exp_id, indices.globals[exp.index as usize]);
                 }
+                wasmparser::ExternalKind::Tag => {
+                    if let Some(&tag_id) = indices.tags.get(exp.index as usize) {
+                        items.add_edge(exp_id, tag_id);
+                    }
+                }
             }
         }
 
@@ -756,9 +1411,10 @@ impl<'a> Parse<'a> for wasmparser::ExportSectionReader<'a> {
     }
 }
 
-struct StartSection<'a>(wasmparser::Section<'a>);
+/// The start section's function index, and the range of its contents.
+struct StartSection(u32, Range<u64>);
 
-impl<'a> Parse<'a> for StartSection<'a> {
+impl<'a> Parse<'a> for StartSection {
     type ItemsExtra = usize;
 
     fn parse_items(
@@ -766,9 +1422,9 @@ impl<'a> Parse<'a> for StartSection<'a> {
         items: &mut ir::ItemsBuilder,
         idx: usize,
     ) -> Result<(), traits::Error> {
-        let range = self.0.range();
+        let range = &self.1;
         let size = (range.end - range.start) as u32;
-        let id = Id::section(idx);
+        let id = Id::entry(idx, 0);
         let name = "\"start\" section";
         items.add_root(ir::Item::new(id, name, size, ir::Misc::new()));
         Ok(())
@@ -781,15 +1437,16 @@ impl<'a> Parse<'a> for StartSection<'a> {
         items: &mut ir::ItemsBuilder,
         (indices, idx): Self::EdgesExtra,
     ) -> Result<(), traits::Error> {
-        let f_i = self.0.get_start_section_content()?;
-        items.add_edge(Id::section(idx), indices.functions[f_i as usize]);
+        let f_i = self.0;
+        items.add_edge(Id::entry(idx, 0), indices.functions[f_i as usize]);
         Ok(())
     }
 }
 
-struct DataCountSection<'a>(wasmparser::Section<'a>);
+/// The range of the data count section's contents.
+struct DataCountSection(Range<u64>);
 
-impl<'a> Parse<'a> for DataCountSection<'a> {
+impl<'a> Parse<'a> for DataCountSection {
     type ItemsExtra = usize;
 
     fn parse_items(
@@ -797,9 +1454,9 @@ impl<'a> Parse<'a> for DataCountSection<'a> {
         items: &mut ir::ItemsBuilder,
         idx: usize,
     ) -> Result<(), traits::Error> {
-        let range = self.0.range();
+        let range = &self.0;
         let size = (range.end - range.start) as u32;
-        let id = Id::section(idx);
+        let id = Id::entry(idx, 0);
         let name = "\"data count\" section";
         items.add_root(ir::Item::new(id, name, size, ir::Misc::new()));
         Ok(())
@@ -820,8 +1477,7 @@ impl<'a> Parse<'a> for wasmparser::ElementSectionReader<'a> {
         items: &mut ir::ItemsBuilder,
         idx: usize,
     ) -> Result<(), traits::Error> {
-        for (i, elem) in iterate_with_size(self).enumerate() {
-            let (_elem, size) = elem?;
+        for (i, (_elem, size)) in iterate_with_size(self)?.into_iter().enumerate() {
             let id = Id::entry(idx, i);
             let name = format!("elem[{}]", i);
             items.add_item(ir::Item::new(id, name, size, ir::Misc::new()));
@@ -836,18 +1492,33 @@ impl<'a> Parse<'a> for wasmparser::ElementSectionReader<'a> {
         items: &mut ir::ItemsBuilder,
         (indices, idx): Self::EdgesExtra,
     ) -> Result<(), traits::Error> {
-        for (i, elem) in iterate_with_size(self).enumerate() {
-            let (elem, _size) = elem?;
+        for (i, elem) in self.clone().into_iter().enumerate() {
+            let elem = elem?;
             let elem_id = Id::entry(idx, i);
 
-            match elem.kind {
-                wasmparser::ElementKind::Active { table_index, .. } => {
+            let table_index = match elem.kind {
+                wasmparser::ElementKind::Active {
+                    table_index,
+                    offset_expr,
+                } => {
+                    let table_index = table_index.unwrap_or(0);
                     items.add_edge(indices.tables[table_index as usize], elem_id);
+                    for to in const_expr_refs(&offset_expr, indices)? {
+                        items.add_edge(elem_id, to);
+                    }
+                    Some(table_index)
+                }
+                wasmparser::ElementKind::Passive | wasmparser::ElementKind::Declared => None,
+            };
+            for func_idx in element_functions(&elem.items)?.into_iter().flatten() {
+                // Functions in a private table that are the target of some
+                // `call_indirect` are retained by their callers (see the code
+                // section's edges) rather than by the element segment.
+                if let Some(table_index) = table_index {
+                    if indices.is_retained_by_indirect_calls(table_index, func_idx) {
+                        continue;
+                    }
                 }
-                wasmparser::ElementKind::Passive(_ty) => {}
-            }
-            for func_idx in elem.items.get_items_reader()? {
-                let func_idx = func_idx?;
                 items.add_edge(elem_id, indices.functions[func_idx as usize]);
             }
         }
@@ -857,69 +1528,151 @@ impl<'a> Parse<'a> for wasmparser::ElementSectionReader<'a> {
 }
 
 impl<'a> Parse<'a> for wasmparser::DataSectionReader<'a> {
-    type ItemsExtra = usize;
+    /// The section's index, and the symbols of each segment.
+    type ItemsExtra = (usize, &'a HashMap<u32, Vec<DataSymbol>>);
 
     fn parse_items(
         &mut self,
         items: &mut ir::ItemsBuilder,
-        idx: usize,
+        (idx, symbols): Self::ItemsExtra,
     ) -> Result<(), traits::Error> {
-        for (i, d) in iterate_with_size(self).enumerate() {
-            let (d, size) = d?;
+        let segments = iterate_with_size(self)?;
+        // The symbols' items are numbered after those of the segments.
+        let mut symbol_index = segments.len();
+        for (i, (d, size)) in segments.into_iter().enumerate() {
             let id = Id::entry(idx, i);
+            let length = d.data.len() as u32;
+
+            // Symbols are ordered by offset, so that we can skip aliases and
+            // symbols that overlap one another.
+            let mut segment_symbols: Vec<&DataSymbol> = symbols
+                .get(&(i as u32))
+                .map_or(vec![], |symbols| symbols.iter().collect());
+            segment_symbols.sort_by_key(|symbol| (symbol.offset, !symbol.size));
+            let mut pieces = vec![];
+            let mut end = 0;
+            for symbol in segment_symbols {
+                let fits = u64::from(symbol.offset) + u64::from(symbol.size) <= u64::from(length);
+                if symbol.size == 0 || symbol.offset < end || !fits {
+                    continue;
+                }
+                pieces.push((symbol, Id::entry(idx, symbol_index)));
+                symbol_index += 1;
+                end = symbol.offset + symbol.size;
+            }
+
+            // The segment's own item is whatever its symbols don't cover, such
+            // as its header and the padding between symbols. Each symbol
+            // needs the segment it's in.
+            let covered: u32 = pieces.iter().map(|(symbol, _)| symbol.size).sum();
             let name = format!("data[{}]", i);
-            items.add_item(ir::Item::new(id, name, size, ir::Data::new(None)));
-
-            // Get the constant address (if any) from the initialization
-            // expression.
-            if let wasmparser::DataKind::Active { init_expr, .. } = d.kind {
-                let mut iter = init_expr.get_operators_reader();
-                let offset = match iter.read()? {
-                    Operator::I32Const { value } => Some(i64::from(value)),
-                    Operator::I64Const { value } => Some(value),
-                    _ => None,
-                };
-
-                if let Some(off) = offset {
-                    let length = d.data.len(); // size of data
-                    items.link_data(off, length, id);
+            items.add_item(ir::Item::new(id, name, size - covered, ir::Data::new(None)));
+            for &(symbol, symbol_id) in &pieces {
+                let data = ir::Data::new(symbol.ty.clone());
+                items.add_item(ir::Item::new(symbol_id, &symbol.name, symbol.size, data));
+                items.add_edge(symbol_id, id);
+            }
+
+            if let Some(address) = data_address(&d)? {
+                let address = address as i64;
+                let mut start = 0;
+                for &(symbol, symbol_id) in &pieces {
+                    if symbol.offset > start {
+                        let gap = (symbol.offset - start) as usize;
+                        items.link_data(address + i64::from(start), gap, id);
+                    }
+                    let offset = address + i64::from(symbol.offset);
+                    items.link_data(offset, symbol.size as usize, symbol_id);
+                    start = symbol.offset + symbol.size;
+                }
+                if length > start {
+                    let rest = (length - start) as usize;
+                    items.link_data(address + i64::from(start), rest, id);
                 }
             }
         }
         Ok(())
     }
 
-    type EdgesExtra = ();
+    type EdgesExtra = (&'a SectionIndices, usize);
 
-    fn parse_edges(&mut self, _: &mut ir::ItemsBuilder, _: ()) -> Result<(), traits::Error> {
+    fn parse_edges(
+        &mut self,
+        items: &mut ir::ItemsBuilder,
+        (indices, idx): Self::EdgesExtra,
+    ) -> Result<(), traits::Error> {
+        // An active segment's offset may be relative to an imported global,
+        // such as the `__memory_base` of a dynamically linked module.
+        for (i, d) in self.clone().into_iter().enumerate() {
+            if let wasmparser::DataKind::Active { offset_expr, .. } = d?.kind {
+                for to in const_expr_refs(&offset_expr, indices)? {
+                    items.add_edge(Id::entry(idx, i), to);
+                }
+            }
+        }
         Ok(())
     }
 }
 
-fn iterate_with_size<'a, S: SectionWithLimitedItems + SectionReader>(
-    s: &'a mut S,
-) -> impl Iterator<Item = Result<(S::Item, u32), traits::Error>> + 'a {
-    let count = s.get_count();
-    (0..count).map(move |i| {
-        let start = s.original_position();
-        let item = s.read()?;
-        let size = (s.original_position() - start) as u32;
-        if i == count - 1 {
-            s.ensure_end()?;
-        }
-        Ok((item, size))
-    })
+/// Read every entry of a section, along with the number of bytes it occupies.
+fn iterate_with_size<'a, T>(
+    s: &wasmparser::SectionLimited<'a, T>,
+) -> Result<Vec<(T, u32)>, traits::Error>
+where
+    T: wasmparser::FromReader<'a>,
+{
+    with_sizes(s.clone().into_iter_with_offsets(), s.range().end)
+}
+
+/// Given the entries of a section and the offsets that they start at, get the
+/// number of bytes that each entry occupies. The last entry extends to the end
+/// of the section.
+fn with_sizes<T, I>(entries: I, end: u64) -> Result<Vec<(T, u32)>, traits::Error>
+where
+    I: Iterator<Item = Result<(u64, T), wasmparser::BinaryReaderError>>,
+{
+    let entries = entries.collect::<Result<Vec<_>, _>>()?;
+    let ends: Vec<u64> = entries
+        .iter()
+        .skip(1)
+        .map(|&(start, _)| start)
+        .chain(Some(end))
+        .collect();
+    Ok(entries
+        .into_iter()
+        .zip(ends)
+        .map(|((start, entry), end)| (entry, (end - start) as u32))
+        .collect())
 }
 
-fn ty2str(t: Type) -> &'static str {
-    match t {
-        Type::I32 => "i32",
-        Type::I64 => "i64",
-        Type::F32 => "f32",
-        Type::F64 => "f64",
-        Type::V128 => "v128",
-        Type::AnyFunc => "anyfunc",
-        Type::AnyRef => "anyref",
-        Type::Func | Type::EmptyBlockType => "?",
+fn type2str(ty: &wasmparser::SubType) -> String {
+    let func = match ty.composite_type.inner {
+        wasmparser::CompositeInnerType::Func(ref func) => func,
+        ref other => return other.to_string(),
+    };
+
+    let mut name = "(".to_string();
+    for (i, param) in func.params().iter().enumerate() {
+        if i != 0 {
+            name.push_str(", ");
+        }
+        name.push_str(&param.to_string());
+    }
+    name.push_str(") -> ");
+
+    match func.results() {
+        [] => name.push_str("nil"),
+        [result] => name.push_str(&result.to_string()),
+        results => {
+            name.push('(');
+            for (i, result) in results.iter().enumerate() {
+                if i != 0 {
+                    name.push_str(", ");
+                }
+                name.push_str(&result.to_string());
+            }
+            name.push(')');
+        }
     }
+    name
 }
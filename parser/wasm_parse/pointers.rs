@@ -0,0 +1,524 @@
+use super::SectionIndices;
+use std::collections::BTreeSet;
+use twiggy_ir::{self as ir, Id};
+use twiggy_traits as traits;
+use wasmparser::{self, BlockType, FrameKind, Operator};
+
+/// The most locals that a function body may declare for its constants to be
+/// tracked. Anything larger is almost certainly not worth the memory.
+const MAX_LOCALS: u64 = 1 << 16;
+
+/// A value on the operand stack or in a local, as far as it is known.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Value {
+    /// Nothing is known about the value.
+    Unknown,
+    /// A known constant. `i32` constants are stored zero-extended.
+    Const(u64),
+    /// A known constant plus some unknown amount, such as the address of a
+    /// static array plus an index into it. This still points into the data at
+    /// the constant.
+    Offset(u64),
+}
+
+impl Value {
+    /// The address of the data that the value may point into, if it is known.
+    fn address(self) -> Option<u64> {
+        match self {
+            Value::Unknown => None,
+            Value::Const(address) | Value::Offset(address) => Some(address),
+        }
+    }
+}
+
+/// A block, loop, `if`, or `try_table` that the interpreter is inside of.
+struct Frame {
+    ty: BlockType,
+    kind: FrameKind,
+    /// The height of the operand stack when the frame was entered, after its
+    /// parameters were popped.
+    height: usize,
+    /// The locals when an `if` frame was entered, which its `else` arm starts
+    /// from.
+    entry: Option<Vec<Value>>,
+    /// The locals of every branch to the end of the frame, merged together.
+    exits: Option<Vec<Value>>,
+    has_else: bool,
+}
+
+/// An abstract interpreter that runs a function body over constants, to find
+/// the addresses of static data that the body computes.
+///
+/// Constants are tracked through locals and through `add` and `sub`. When a
+/// constant is used as a pointer, as the address of a load or store, a value
+/// stored to memory, an operand of a `memory.*` instruction, or an argument to
+/// a call, or when the interpreter can no longer tell whether it will be, such
+/// as where two control flow paths that disagree on a local's value meet, the
+/// data segment that it points into (if any) is referenced by the body.
+/// Constants that are only compared or used in other arithmetic are numbers
+/// rather than pointers, so they don't reference anything.
+struct Interpreter<'a> {
+    indices: &'a SectionIndices,
+    items: &'a ir::ItemsBuilder,
+    stack: Vec<Value>,
+    locals: Vec<Value>,
+    frames: Vec<Frame>,
+    reachable: bool,
+    references: BTreeSet<Id>,
+}
+
+/// Get the data segments that a function body with the given type refers to
+/// by address.
+pub(super) fn data_references(
+    items: &ir::ItemsBuilder,
+    indices: &SectionIndices,
+    body: &wasmparser::FunctionBody<'_>,
+    type_index: u32,
+) -> Result<BTreeSet<Id>, traits::Error> {
+    let params = match indices.sub_types.get(type_index as usize) {
+        Some(ty) => match ty.composite_type.inner {
+            wasmparser::CompositeInnerType::Func(ref ty) => ty.params().len(),
+            _ => return Ok(BTreeSet::new()),
+        },
+        None => return Ok(BTreeSet::new()),
+    };
+
+    // Parameters aren't known, but declared locals start out as zero.
+    let mut locals = vec![Value::Unknown; params];
+    for local in body.get_locals_reader()? {
+        let (count, ty) = local?;
+        if locals.len() as u64 + u64::from(count) > MAX_LOCALS {
+            return Ok(BTreeSet::new());
+        }
+        let zero = match ty {
+            wasmparser::ValType::I32 | wasmparser::ValType::I64 => Value::Const(0),
+            _ => Value::Unknown,
+        };
+        locals.extend((0..count).map(|_| zero));
+    }
+
+    let mut interpreter = Interpreter {
+        indices,
+        items,
+        stack: vec![],
+        locals,
+        frames: vec![Frame {
+            ty: BlockType::FuncType(type_index),
+            kind: FrameKind::Block,
+            height: 0,
+            entry: None,
+            exits: None,
+            has_else: false,
+        }],
+        reachable: true,
+        references: BTreeSet::new(),
+    };
+    for op in body.get_operators_reader()? {
+        if !interpreter.step(op?) {
+            break;
+        }
+    }
+    Ok(interpreter.references)
+}
+
+impl<'a> Interpreter<'a> {
+    /// Interpret a single operator. Returns `false` if the rest of the body
+    /// can't be interpreted, in which case whatever was found so far is kept.
+    fn step(&mut self, op: Operator<'_>) -> bool {
+        let (pops, pushes) = match op.operator_arity(self) {
+            Some((pops, pushes)) => (pops as usize, pushes as usize),
+            None => return false,
+        };
+
+        match op {
+            Operator::I32Const { value } => self.push(Value::Const(u64::from(value as u32))),
+            Operator::I64Const { value } => self.push(Value::Const(value as u64)),
+
+            Operator::I32Add | Operator::I32Sub | Operator::I64Add | Operator::I64Sub => {
+                let rhs = self.pop();
+                let lhs = self.pop();
+                let is_add = matches!(op, Operator::I32Add | Operator::I64Add);
+                let result = match (lhs, rhs) {
+                    (Value::Const(lhs), Value::Const(rhs)) => Value::Const(match op {
+                        Operator::I32Add => u64::from((lhs as u32).wrapping_add(rhs as u32)),
+                        Operator::I32Sub => u64::from((lhs as u32).wrapping_sub(rhs as u32)),
+                        Operator::I64Add => lhs.wrapping_add(rhs),
+                        _ => lhs.wrapping_sub(rhs),
+                    }),
+                    // A constant base plus or minus some unknown index still
+                    // points into the data at the base.
+                    (Value::Const(base), _) | (Value::Offset(base), _) => Value::Offset(base),
+                    (_, Value::Const(base)) | (_, Value::Offset(base)) if is_add => {
+                        Value::Offset(base)
+                    }
+                    _ => Value::Unknown,
+                };
+                self.push(result);
+            }
+
+            Operator::LocalGet { local_index } => {
+                let value = self.locals.get(local_index as usize).cloned();
+                self.push(value.unwrap_or(Value::Unknown));
+            }
+            Operator::LocalSet { local_index } => {
+                let value = self.pop();
+                self.set_local(local_index, value);
+            }
+            Operator::LocalTee { local_index } => {
+                let value = self.pop();
+                self.set_local(local_index, value);
+                self.push(value);
+            }
+
+            Operator::I32Load { memarg }
+            | Operator::I32Load8S { memarg }
+            | Operator::I32Load8U { memarg }
+            | Operator::I32Load16S { memarg }
+            | Operator::I32Load16U { memarg }
+            | Operator::I64Load { memarg }
+            | Operator::I64Load8S { memarg }
+            | Operator::I64Load8U { memarg }
+            | Operator::I64Load16S { memarg }
+            | Operator::I64Load16U { memarg }
+            | Operator::I64Load32S { memarg }
+            | Operator::I64Load32U { memarg }
+            | Operator::F32Load { memarg }
+            | Operator::F64Load { memarg }
+            | Operator::V128Load { memarg } => {
+                let address = self.pop();
+                self.address(address, memarg);
+                self.push(Value::Unknown);
+            }
+            Operator::I32Store { memarg }
+            | Operator::I32Store8 { memarg }
+            | Operator::I32Store16 { memarg }
+            | Operator::I64Store { memarg }
+            | Operator::I64Store8 { memarg }
+            | Operator::I64Store16 { memarg }
+            | Operator::I64Store32 { memarg }
+            | Operator::F32Store { memarg }
+            | Operator::F64Store { memarg }
+            | Operator::V128Store { memarg } => {
+                let value = self.pop();
+                self.reference(value);
+                let address = self.pop();
+                self.address(address, memarg);
+            }
+
+            // Every operand of these is either an address or a length, and a
+            // length that happens to look like an address is rare enough.
+            Operator::MemoryCopy { dst_mem, src_mem } if dst_mem == 0 || src_mem == 0 => {
+                self.pointers(pops, pushes)
+            }
+            Operator::MemoryFill { mem } | Operator::MemoryInit { mem, .. } if mem == 0 => {
+                self.pointers(pops, pushes)
+            }
+
+            Operator::Call { .. } | Operator::CallIndirect { .. } | Operator::CallRef { .. } => {
+                self.pointers(pops, pushes)
+            }
+
+            Operator::Block { blockty } => {
+                self.generic(pops, pushes);
+                self.enter(blockty, FrameKind::Block, pushes);
+            }
+            Operator::Loop { blockty } => {
+                // Branches back to the start of the loop may change any local,
+                // so none of them are known inside of it.
+                self.generic(pops, pushes);
+                self.forget_locals();
+                self.enter(blockty, FrameKind::Loop, pushes);
+            }
+            Operator::If { blockty } => {
+                // The arity of an `if` is that of its whole frame, rather than
+                // the parameters that its arms start with.
+                let params = pops - 1;
+                self.generic(pops, params);
+                self.enter(blockty, FrameKind::If, params);
+                let entry = self.locals.clone();
+                self.top().entry = Some(entry);
+            }
+            Operator::TryTable { try_table } => {
+                // An exception may be caught anywhere in the body, so nothing
+                // is known about the locals where it's caught.
+                self.generic(pops, pushes);
+                for catch in try_table.catches.iter() {
+                    let label = match *catch {
+                        wasmparser::Catch::One { label, .. }
+                        | wasmparser::Catch::OneRef { label, .. }
+                        | wasmparser::Catch::All { label }
+                        | wasmparser::Catch::AllRef { label } => label,
+                    };
+                    let unknown = vec![Value::Unknown; self.locals.len()];
+                    self.branch_with(label, &unknown);
+                }
+                self.enter(try_table.ty, FrameKind::TryTable, pushes);
+            }
+            Operator::Else => {
+                self.truncate();
+                let locals = self.locals.clone();
+                if self.reachable {
+                    self.merge_exit(0, &locals);
+                }
+                let frame = self.top();
+                frame.has_else = true;
+                let entry = frame.entry.clone();
+                self.locals = entry.unwrap_or_else(|| vec![Value::Unknown; locals.len()]);
+                self.reachable = true;
+                self.stack.extend((0..pushes).map(|_| Value::Unknown));
+            }
+            Operator::End => {
+                self.truncate();
+                if self.reachable {
+                    let locals = self.locals.clone();
+                    self.merge_exit(0, &locals);
+                }
+                let frame = match self.frames.pop() {
+                    Some(frame) => frame,
+                    None => return false,
+                };
+                let mut exits = frame.exits;
+                if let (FrameKind::If, false, Some(entry)) =
+                    (frame.kind, frame.has_else, frame.entry)
+                {
+                    self.merge(&mut exits, &entry);
+                }
+                self.reachable = exits.is_some();
+                self.locals = exits.unwrap_or_else(|| vec![Value::Unknown; self.locals.len()]);
+                self.stack.extend((0..pushes).map(|_| Value::Unknown));
+            }
+
+            Operator::Br { relative_depth } => {
+                self.generic(pops, pushes);
+                self.branch(relative_depth);
+                self.unreachable();
+            }
+            Operator::BrIf { relative_depth }
+            | Operator::BrOnNull { relative_depth }
+            | Operator::BrOnNonNull { relative_depth } => {
+                self.generic(pops, pushes);
+                self.branch(relative_depth);
+            }
+            Operator::BrTable { targets } => {
+                self.generic(pops, pushes);
+                for target in targets.targets() {
+                    match target {
+                        Ok(target) => self.branch(target),
+                        Err(_) => return false,
+                    }
+                }
+                self.branch(targets.default());
+                self.unreachable();
+            }
+            Operator::Return
+            | Operator::Unreachable
+            | Operator::Throw { .. }
+            | Operator::ThrowRef
+            | Operator::ReturnCall { .. }
+            | Operator::ReturnCallIndirect { .. }
+            | Operator::ReturnCallRef { .. } => {
+                self.pointers(pops, pushes);
+                self.unreachable();
+            }
+
+            // Legacy exception handling and other control flow that isn't
+            // interpreted.
+            Operator::Try { .. }
+            | Operator::Catch { .. }
+            | Operator::CatchAll
+            | Operator::Delegate { .. }
+            | Operator::Rethrow { .. } => return false,
+
+            _ => self.generic(pops, pushes),
+        }
+        true
+    }
+
+    fn push(&mut self, value: Value) {
+        self.stack.push(value);
+    }
+
+    /// Pop a value from the operand stack. Below the current frame's values,
+    /// such as in unreachable code, nothing is known.
+    fn pop(&mut self) -> Value {
+        let height = self.frames.last().map_or(0, |frame| frame.height);
+        if self.stack.len() > height {
+            self.stack.pop().unwrap_or(Value::Unknown)
+        } else {
+            Value::Unknown
+        }
+    }
+
+    /// Interpret an operator that doesn't use its operands as pointers, and
+    /// whose results aren't known.
+    fn generic(&mut self, pops: usize, pushes: usize) {
+        for _ in 0..pops {
+            self.pop();
+        }
+        self.stack.extend((0..pushes).map(|_| Value::Unknown));
+    }
+
+    /// Interpret an operator that may use any of its operands as pointers, such
+    /// as a call, and whose results aren't known.
+    fn pointers(&mut self, pops: usize, pushes: usize) {
+        for _ in 0..pops {
+            let value = self.pop();
+            self.reference(value);
+        }
+        self.stack.extend((0..pushes).map(|_| Value::Unknown));
+    }
+
+    /// Reference the data segment that a value points into, if any, since the
+    /// value is either used as a pointer or might be.
+    fn reference(&mut self, value: Value) {
+        if let Some(address) = value.address() {
+            if address <= u64::from(u32::MAX) {
+                if let Some(id) = self.items.get_data(address as u32) {
+                    self.references.insert(id);
+                }
+            }
+        }
+    }
+
+    /// Reference the data segment that a load or store accesses, if its
+    /// address is known. Only the data segments of the first memory are
+    /// linked to their addresses.
+    fn address(&mut self, address: Value, memarg: wasmparser::MemArg) {
+        if memarg.memory == 0 {
+            let address = match address {
+                Value::Const(address) => address.checked_add(memarg.offset).map(Value::Const),
+                address => Some(address),
+            };
+            self.reference(address.unwrap_or(Value::Unknown));
+        }
+    }
+
+    fn set_local(&mut self, index: u32, value: Value) {
+        match self.locals.get_mut(index as usize) {
+            Some(local) => *local = value,
+            None => self.reference(value),
+        }
+    }
+
+    /// Forget the values of all locals. Any of them may have been pointers
+    /// that are used later, so they are referenced.
+    fn forget_locals(&mut self) {
+        for i in 0..self.locals.len() {
+            let value = std::mem::replace(&mut self.locals[i], Value::Unknown);
+            self.reference(value);
+        }
+    }
+
+    fn top(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("inside the function's frame")
+    }
+
+    fn enter(&mut self, ty: BlockType, kind: FrameKind, params: usize) {
+        let outer = self.frames.last().map_or(0, |frame| frame.height);
+        let height = self.stack.len().saturating_sub(params).max(outer);
+        self.frames.push(Frame {
+            ty,
+            kind,
+            height,
+            entry: None,
+            exits: None,
+            has_else: false,
+        });
+    }
+
+    /// Drop whatever is on the operand stack above the current frame's
+    /// height, at the end of the frame or one of its arms.
+    fn truncate(&mut self) {
+        let height = self.frames.last().map_or(0, |frame| frame.height);
+        while self.stack.len() > height {
+            self.stack.pop();
+        }
+    }
+
+    /// Nothing after a branch, return, or throw is reached until the end of
+    /// the current frame or arm.
+    fn unreachable(&mut self) {
+        self.truncate();
+        self.reachable = false;
+    }
+
+    fn branch(&mut self, depth: u32) {
+        let locals = self.locals.clone();
+        self.branch_with(depth, &locals);
+    }
+
+    /// Record that control may flow to the label at the given depth with the
+    /// given locals. Loops don't need to know, since nothing is known about
+    /// the locals at their starts anyway.
+    fn branch_with(&mut self, depth: u32, locals: &[Value]) {
+        let is_loop = match self.frames.iter().rev().nth(depth as usize) {
+            Some(frame) => frame.kind == FrameKind::Loop,
+            None => return,
+        };
+        if !is_loop {
+            self.merge_exit(depth, locals);
+        }
+    }
+
+    fn merge_exit(&mut self, depth: u32, locals: &[Value]) {
+        let index = self.frames.len() - 1 - depth as usize;
+        let mut exits = self.frames[index].exits.take();
+        self.merge(&mut exits, locals);
+        self.frames[index].exits = exits;
+    }
+
+    /// Merge the locals of one control flow path into those of the others
+    /// that meet at the same point. Locals whose values disagree become
+    /// unknown.
+    fn merge(&mut self, into: &mut Option<Vec<Value>>, locals: &[Value]) {
+        let into = match into {
+            Some(into) => into,
+            None => {
+                *into = Some(locals.to_vec());
+                return;
+            }
+        };
+        for (a, &b) in into.iter_mut().zip(locals) {
+            if *a != b {
+                let a = std::mem::replace(a, Value::Unknown);
+                self.reference(a);
+                self.reference(b);
+            }
+        }
+    }
+}
+
+impl<'a> wasmparser::ModuleArity for Interpreter<'a> {
+    fn sub_type_at(&self, type_idx: u32) -> Option<&wasmparser::SubType> {
+        self.indices.sub_types.get(type_idx as usize)
+    }
+
+    fn tag_type_arity(&self, at: u32) -> Option<(u32, u32)> {
+        let ty = self.indices.tag_types.get(at as usize)?;
+        self.sub_type_arity(self.sub_type_at(*ty)?)
+    }
+
+    fn type_index_of_function(&self, function_idx: u32) -> Option<u32> {
+        self.indices
+            .function_types
+            .get(function_idx as usize)
+            .cloned()
+    }
+
+    fn func_type_of_cont_type(&self, _c: &wasmparser::ContType) -> Option<&wasmparser::FuncType> {
+        None
+    }
+
+    fn sub_type_of_ref_type(&self, _rt: &wasmparser::RefType) -> Option<&wasmparser::SubType> {
+        None
+    }
+
+    fn control_stack_height(&self) -> u32 {
+        self.frames.len() as u32
+    }
+
+    fn label_block(&self, depth: u32) -> Option<(BlockType, FrameKind)> {
+        let frame = self.frames.iter().rev().nth(depth as usize)?;
+        Some((frame.ty, frame.kind))
+    }
+}
@@ -39,6 +39,9 @@ enum ErrorInner {
     #[fail(display = "{}", _0)]
     Msg(String),
 
+    #[fail(display = "{}", _0)]
+    Context(String, #[cause] Error),
+
     #[fail(display = "I/O error: {}", _0)]
     Io(#[cause] io::Error),
 
@@ -54,6 +57,9 @@ enum ErrorInner {
     #[fail(display = "Regex error: {}", _0)]
     Regex(#[cause] regex::Error),
 
+    #[fail(display = "snapshot error: {}", _0)]
+    Snapshot(#[cause] bincode::Error),
+
     #[cfg(feature = "dwarf")]
     #[fail(display = "Gimli error: {}", _0)]
     Gimli(#[cause] gimli::Error),
@@ -105,6 +111,14 @@ impl From<regex::Error> for Error {
     }
 }
 
+impl From<bincode::Error> for Error {
+    fn from(e: bincode::Error) -> Error {
+        Error {
+            inner: Box::new(ErrorInner::Snapshot(e)),
+        }
+    }
+}
+
 #[cfg(feature = "dwarf")]
 impl From<gimli::Error> for Error {
     fn from(e: gimli::Error) -> Error {
@@ -121,6 +135,14 @@ impl Error {
             inner: Box::new(ErrorInner::Msg(msg.into())),
         }
     }
+
+    /// Wrap this error in another with the given message, which explains what
+    /// was being done when this error occurred.
+    pub fn context<S: Into<String>>(self, msg: S) -> Error {
+        Error {
+            inner: Box::new(ErrorInner::Context(msg.into(), self)),
+        }
+    }
 }
 
 #[test]
@@ -140,17 +162,45 @@ pub trait Analyze {
 }
 
 /// Selects the parse mode for the input data.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ParseMode {
     /// WebAssembly file parse mode.
     Wasm,
-    /// DWARF sections parse mode.
+    /// DWARF sections parse mode, for any of the native binary formats.
     #[cfg(feature = "dwarf")]
     Dwarf,
-    /// Automatically determined mode of parsing, e.g. based on file extension.
+    /// ELF binary parse mode.
+    Elf,
+    /// Mach-O binary parse mode, including fat (universal) binaries.
+    MachO,
+    /// PE/COFF binary parse mode.
+    Pe,
+    /// Static archive (`.a`, `.lib`, or `.rlib`) parse mode.
+    Archive,
+    /// Parse mode for snapshots of previously parsed items.
+    Snapshot,
+    /// Automatically determined mode of parsing, based on the data's magic
+    /// bytes, or failing that, on its file extension.
     Auto,
 }
 
+impl fmt::Display for ParseMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            ParseMode::Wasm => "wasm",
+            #[cfg(feature = "dwarf")]
+            ParseMode::Dwarf => "dwarf",
+            ParseMode::Elf => "elf",
+            ParseMode::MachO => "macho",
+            ParseMode::Pe => "pe",
+            ParseMode::Archive => "archive",
+            ParseMode::Snapshot => "snapshot",
+            ParseMode::Auto => "auto",
+        };
+        f.write_str(name)
+    }
+}
+
 impl Default for ParseMode {
     fn default() -> ParseMode {
         ParseMode::Auto
@@ -165,6 +215,11 @@ impl FromStr for ParseMode {
             "wasm" => Ok(ParseMode::Wasm),
             #[cfg(feature = "dwarf")]
             "dwarf" => Ok(ParseMode::Dwarf),
+            "elf" => Ok(ParseMode::Elf),
+            "macho" => Ok(ParseMode::MachO),
+            "pe" => Ok(ParseMode::Pe),
+            "archive" => Ok(ParseMode::Archive),
+            "snapshot" => Ok(ParseMode::Snapshot),
             "auto" => Ok(ParseMode::Auto),
             _ => Err(Error::with_msg(format!("Unknown parse mode: {}", s))),
         }
@@ -177,11 +232,15 @@ pub enum OutputFormat {
     /// Human readable text.
     #[cfg(feature = "emit_text")]
     Text,
-    // /// Hyper Text Markup Language.
-    // Html,
-
-    // /// Graphviz dot format.
-    // Dot,
+    /// Hyper Text Markup Language.
+    #[cfg(feature = "emit_html")]
+    Html,
+    /// Graphviz dot format.
+    #[cfg(feature = "emit_dot")]
+    Dot,
+    /// Scalable Vector Graphics image.
+    #[cfg(feature = "emit_svg")]
+    Svg,
     /// Comma-separated values (CSV) format.
     #[cfg(feature = "emit_csv")]
     Csv,
@@ -210,6 +269,12 @@ impl FromStr for OutputFormat {
             "json" => Ok(OutputFormat::Json),
             #[cfg(feature = "emit_csv")]
             "csv" => Ok(OutputFormat::Csv),
+            #[cfg(feature = "emit_html")]
+            "html" => Ok(OutputFormat::Html),
+            #[cfg(feature = "emit_dot")]
+            "dot" => Ok(OutputFormat::Dot),
+            #[cfg(feature = "emit_svg")]
+            "svg" => Ok(OutputFormat::Svg),
             _ => Err(Error::with_msg(format!("Unknown output format: {}", s))),
         }
     }
@@ -228,8 +293,12 @@ pub trait Emit {
         match format {
             #[cfg(feature = "emit_text")]
             OutputFormat::Text => self.emit_text(items, destination),
-            // OutputFormat::Html => self.emit_html(destination),
-            // OutputFormat::Dot => self.emit_dot(destination),
+            #[cfg(feature = "emit_html")]
+            OutputFormat::Html => self.emit_html(items, destination),
+            #[cfg(feature = "emit_dot")]
+            OutputFormat::Dot => self.emit_dot(items, destination),
+            #[cfg(feature = "emit_svg")]
+            OutputFormat::Svg => self.emit_svg(items, destination),
             #[cfg(feature = "emit_csv")]
             OutputFormat::Csv => self.emit_csv(items, destination),
             #[cfg(feature = "emit_json")]
@@ -241,11 +310,22 @@ pub trait Emit {
     #[cfg(feature = "emit_text")]
     fn emit_text(&self, items: &ir::Items, destination: &mut dyn io::Write) -> Result<(), Error>;
 
-    // /// Emit HTML.
-    // fn emit_html(&self, destination: &mut dyn io::Write) -> Result<(), Error>;
-
-    // /// Emit Graphviz's dot format.
-    // fn emit_dot(&self, destination: &mut dyn io::Write) -> Result<(), Error>;
+    /// Emit a self-contained HTML page.
+    #[cfg(feature = "emit_html")]
+    fn emit_html(&self, items: &ir::Items, destination: &mut dyn io::Write) -> Result<(), Error>;
+
+    /// Emit Graphviz's dot format.
+    #[cfg(feature = "emit_dot")]
+    fn emit_dot(&self, items: &ir::Items, destination: &mut dyn io::Write) -> Result<(), Error>;
+
+    /// Emit an SVG image. Only some analyses have a useful picture to draw,
+    /// so the rest report an error.
+    #[cfg(feature = "emit_svg")]
+    fn emit_svg(&self, _items: &ir::Items, _destination: &mut dyn io::Write) -> Result<(), Error> {
+        Err(Error::with_msg(
+            "the `svg` output format is not supported by this analysis",
+        ))
+    }
 
     /// Emit CSV.
     #[cfg(feature = "emit_csv")]
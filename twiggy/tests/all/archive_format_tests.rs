@@ -0,0 +1,19 @@
+test!(archive_top_libhello, "top", "./fixtures/libhello.a");
+
+test!(
+    archive_top_libhello_explicit_mode,
+    "top",
+    "--mode",
+    "archive",
+    "-n",
+    "5",
+    "./fixtures/libhello.a"
+);
+
+test!(
+    archive_dominators_libhello,
+    "dominators",
+    "-d",
+    "1",
+    "./fixtures/libhello.a"
+);
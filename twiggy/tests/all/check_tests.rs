@@ -0,0 +1,70 @@
+test!(
+    check_within_budgets,
+    "check",
+    "./fixtures/wee_alloc.2.wasm",
+    "-c",
+    "./fixtures/budgets.txt",
+    "--baseline",
+    "./fixtures/wee_alloc.wasm"
+);
+
+test!(
+    check_snapshot_baseline_json,
+    "check",
+    "./fixtures/wee_alloc.2.wasm",
+    "-c",
+    "./fixtures/budgets.txt",
+    "--baseline",
+    "./fixtures/wee_alloc.twiggy",
+    "-f",
+    "json"
+);
+
+test!(
+    check_within_budgets_csv,
+    "check",
+    "./fixtures/wee_alloc.2.wasm",
+    "-c",
+    "./fixtures/budgets.txt",
+    "--baseline",
+    "./fixtures/wee_alloc.wasm",
+    "-f",
+    "csv"
+);
+
+#[test]
+fn check_exceeded_budgets() {
+    use std::process::Command;
+
+    let output = Command::new("cargo")
+        .arg("run")
+        .arg("--")
+        .arg("check")
+        .arg("./fixtures/wee_alloc.wasm")
+        .arg("-c")
+        .arg("./fixtures/budgets.txt")
+        .arg("--baseline")
+        .arg("./fixtures/wee_alloc.2.wasm")
+        .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/all/"))
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("  2817 ┊    2048 ┊ exceeded ┊ total"));
+    assert!(stdout.contains(" +1476 ┊ +33.525 ┊ exceeded ┊ growth"));
+    assert!(stdout.contains("  1097 ┊     100 ┊ exceeded ┊ section data"));
+    assert!(stdout.contains("   379 ┊    1000 ┊ ok       ┊ items /^wee_alloc::/"));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("error: 5 of the size budgets were exceeded"));
+}
+
+test!(
+    check_elf_sections,
+    "check",
+    "./fixtures/hello_elf",
+    "-c",
+    "./fixtures/budgets_elf.txt"
+);
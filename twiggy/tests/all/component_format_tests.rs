@@ -0,0 +1,17 @@
+test!(component_top, "top", "./fixtures/component.wasm");
+
+test!(
+    component_dominators,
+    "dominators",
+    "./fixtures/component.wasm"
+);
+
+test!(
+    component_paths,
+    "paths",
+    "./fixtures/component.wasm",
+    "module \"main\"",
+    "module \"libc\" / memory[0]"
+);
+
+test!(component_garbage, "garbage", "./fixtures/component.wasm");
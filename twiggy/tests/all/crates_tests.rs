@@ -0,0 +1,30 @@
+test!(crates_wee_alloc, "crates", "./fixtures/wee_alloc.wasm");
+
+test!(crates_monos, "crates", "./fixtures/monos.wasm");
+
+test!(
+    crates_mappings_retained,
+    "crates",
+    "./fixtures/mappings.wasm",
+    "--retained"
+);
+
+test!(
+    crates_max_items,
+    "crates",
+    "./fixtures/monos.wasm",
+    "-n",
+    "3"
+);
+
+test!(
+    crates_json,
+    "crates",
+    "./fixtures/wee_alloc.wasm",
+    "-f",
+    "json"
+);
+
+test!(crates_dot, "crates", "./fixtures/monos.wasm", "-f", "dot");
+
+test!(crates_elf_hello_world, "crates", "./fixtures/hello_elf");
@@ -14,6 +14,28 @@ test!(
     "5"
 );
 
+test!(
+    diff_wee_alloc_top_5_dot,
+    "diff",
+    "./fixtures/wee_alloc.wasm",
+    "./fixtures/wee_alloc.2.wasm",
+    "-n",
+    "5",
+    "-f",
+    "dot"
+);
+
+test!(
+    diff_wee_alloc_top_5_html,
+    "diff",
+    "./fixtures/wee_alloc.wasm",
+    "./fixtures/wee_alloc.2.wasm",
+    "-n",
+    "5",
+    "-f",
+    "html"
+);
+
 test!(
     diff_wee_alloc_all,
     "diff",
@@ -89,3 +111,64 @@ test!(
     "hello",
     "goodbye"
 );
+
+test!(
+    diff_retained,
+    "diff",
+    "./fixtures/retained_old.wasm",
+    "./fixtures/retained_new.wasm",
+    "--retained"
+);
+
+test!(
+    diff_retained_csv,
+    "diff",
+    "./fixtures/retained_old.wasm",
+    "./fixtures/retained_new.wasm",
+    "--retained",
+    "-f",
+    "csv"
+);
+
+test!(
+    diff_retained_json,
+    "diff",
+    "./fixtures/retained_old.wasm",
+    "./fixtures/retained_new.wasm",
+    "--retained",
+    "-f",
+    "json"
+);
+
+test!(
+    diff_wee_alloc_retained_top_5,
+    "diff",
+    "./fixtures/wee_alloc.wasm",
+    "./fixtures/wee_alloc.2.wasm",
+    "--retained",
+    "-n",
+    "5"
+);
+
+test!(
+    diff_renames,
+    "diff",
+    "./fixtures/renames_old.wasm",
+    "./fixtures/renames_new.wasm"
+);
+
+test!(
+    diff_renames_json,
+    "diff",
+    "./fixtures/renames_old.wasm",
+    "./fixtures/renames_new.wasm",
+    "-f",
+    "json"
+);
+
+test!(
+    diff_renames_stripped,
+    "diff",
+    "./fixtures/renames_old_stripped.wasm",
+    "./fixtures/renames_new_stripped.wasm"
+);
@@ -20,6 +20,43 @@ test!(
     "csv"
 );
 
+test!(
+    dominators_wee_alloc_dot,
+    "dominators",
+    "./fixtures/wee_alloc.wasm",
+    "-d",
+    "3",
+    "-f",
+    "dot"
+);
+
+test!(
+    dominators_wee_alloc_html,
+    "dominators",
+    "./fixtures/wee_alloc.wasm",
+    "-f",
+    "html"
+);
+
+test!(
+    dominators_wee_alloc_svg,
+    "dominators",
+    "./fixtures/wee_alloc.wasm",
+    "-f",
+    "svg"
+);
+
+test!(
+    dominators_wee_alloc_subtree_svg,
+    "dominators",
+    "./fixtures/wee_alloc.wasm",
+    "hello",
+    "-d",
+    "2",
+    "-f",
+    "svg"
+);
+
 test!(
     dominators_wee_alloc_with_depth_and_row,
     "dominators",
@@ -79,3 +116,21 @@ test!(
     "-d",
     "1"
 );
+
+test!(
+    dominators_call_indirect,
+    "dominators",
+    "./fixtures/call_indirect.wasm"
+);
+
+test!(
+    dominators_call_indirect_slots,
+    "dominators",
+    "./fixtures/call_indirect_slots.wasm"
+);
+
+test!(
+    dominators_wasm_proposals,
+    "dominators",
+    "./fixtures/proposals.wasm"
+);
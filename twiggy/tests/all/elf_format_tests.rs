@@ -7,3 +7,66 @@ test!(
 );
 
 test!(elf_top_hello_world_rs, "top", "./fixtures/hello_elf");
+
+test!(
+    elf_dominators_hello_world_rs,
+    "dominators",
+    "-d",
+    "2",
+    "-r",
+    "20",
+    "./fixtures/hello_elf"
+);
+
+test!(
+    elf_garbage_hello_world_rs,
+    "garbage",
+    "-n",
+    "10",
+    "./fixtures/hello_elf"
+);
+
+test!(
+    elf_top_stripped_hello_world_rs,
+    "top",
+    "-n",
+    "15",
+    "./fixtures/hello_elf_stripped"
+);
+
+test!(
+    elf_dominators_stripped_hello_world_rs,
+    "dominators",
+    "-d",
+    "2",
+    "-r",
+    "15",
+    "./fixtures/hello_elf_stripped"
+);
+
+test!(
+    elf_paths_debug_info_hello_world_rs,
+    "paths",
+    "--debug-info",
+    "./fixtures/hello_elf",
+    "debug info for hello_world::main::h605ec64d7369fb87"
+);
+
+test!(
+    elf_top_25_debug_info_hello_world_rs,
+    "top",
+    "--debug-info",
+    "-n",
+    "25",
+    "./fixtures/hello_elf"
+);
+
+test!(
+    elf_top_explicit_mode_hello_world_rs,
+    "top",
+    "--mode",
+    "elf",
+    "-n",
+    "10",
+    "./fixtures/hello_elf"
+);
@@ -0,0 +1,20 @@
+;; A malformed module that throws, catches, and exports tags that don't exist.
+;; These references are skipped rather than followed.
+;;
+;; Compile this file using the following command:
+;;
+;; wasm-tools parse bad_tag_indices.wat -o bad_tag_indices.wasm
+(module
+  (tag $oops)
+
+  (func $throw
+    throw 3)
+
+  (func $catch
+    (block $caught
+      (try_table (catch 5 $caught)
+        throw $oops)))
+
+  (export "throw" (func $throw))
+  (export "catch" (func $catch))
+  (export "missing" (tag 7)))
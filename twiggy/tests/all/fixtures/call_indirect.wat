@@ -0,0 +1,49 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test that `call_indirect` instructions are resolved through the element
+    ;; segments of a table, like a vtable for trait objects in Rust.
+    ;;
+    ;; The table holds `$speakDog`, `$speakCat`, and `$add`. The exported
+    ;; function `speak` calls through the table with type 0, so it should retain
+    ;; both `$speakDog` and `$speakCat`, but not `$add`, which has a different
+    ;; signature and is only retained by the element segment.
+    ;; -------------------------------------------------------------------------
+    ;; NOTE: The test cases expect that this module is compiled with debug
+    ;; names written to the binary file, which affects the size percentages.
+    ;; Compile this file using the following command:
+    ;;
+    ;; wat2wasm --debug-names call_indirect.wat -o call_indirect.wasm
+    ;; -------------------------------------------------------------------------
+
+    ;; Types are referenced by index, since older parsers do not understand
+    ;; the type names subsection.
+    (type (func (param i32) (result i32)))
+    (type (func (param i32 i32) (result i32)))
+
+    (table 3 funcref)
+    (elem (i32.const 0) $speakDog $speakCat $add)
+
+    (func $speakDog (type 0) (param $times i32) (result i32)
+        local.get $times
+        i32.const 1
+        i32.add)
+
+    (func $speakCat (type 0) (param $times i32) (result i32)
+        local.get $times
+        i32.const 2
+        i32.mul)
+
+    (func $add (type 1) (param $lhs i32) (param $rhs i32) (result i32)
+        local.get $lhs
+        local.get $rhs
+        i32.add)
+
+    ;; Dispatch to the `$speak` implementation at the given table index.
+    (func $speak (param $animal i32) (param $times i32) (result i32)
+        local.get $times
+        local.get $animal
+        call_indirect (type 0))
+
+    (export "speak" (func $speak))
+)
@@ -0,0 +1,49 @@
+;; Indirect calls whose targets are limited to the table slots that they can
+;; actually reach.
+;;
+;; `$speakFish` is placed in the first table, but a later segment overwrites its
+;; slot with `$speakCat`, so no `call_indirect` can reach it. `$echo` dispatches
+;; through the table that it is in, but doesn't retain itself by doing so.
+;; `$other` has the right signature, but lives in a table that `speak` doesn't
+;; call through.
+;;
+;; Compile this file using the following command:
+;;
+;; wasm-tools parse call_indirect_slots.wat -o call_indirect_slots.wasm
+(module
+  (type $speech (func (param i32) (result i32)))
+
+  (table $animals 3 funcref)
+  (table $others 1 funcref)
+  (elem (table $animals) (i32.const 0) func $speakDog $speakFish $echo)
+  (elem (table $animals) (i32.const 1) func $speakCat)
+  (elem (table $others) (i32.const 0) func $other)
+
+  (func $speakDog (type $speech)
+    local.get 0
+    i32.const 1
+    i32.add)
+
+  (func $speakCat (type $speech)
+    local.get 0
+    i32.const 2
+    i32.mul)
+
+  (func $speakFish (type $speech)
+    i32.const 0)
+
+  (func $other (type $speech)
+    local.get 0)
+
+  ;; Speak through the animal in the first slot.
+  (func $echo (type $speech)
+    local.get 0
+    i32.const 0
+    call_indirect $animals (type $speech))
+
+  (func $speak (param $animal i32) (param $times i32) (result i32)
+    local.get $times
+    local.get $animal
+    call_indirect $animals (type $speech))
+
+  (export "speak" (func $speak)))
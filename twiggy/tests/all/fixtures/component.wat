@@ -0,0 +1,67 @@
+;; A component that nests two core modules, and a component that nests a third,
+;; lowering a host import into one module and lifting its exports.
+;;
+;; Compile this file using the following command:
+;;
+;; wasm-tools parse component.wat -o component.wasm
+(component $greeter
+  (import "host" (instance $host
+    (export "log" (func (param "msg" string)))
+  ))
+  (alias export $host "log" (func $log))
+
+  (core module $libc
+    (memory (export "memory") 1)
+    (global $heap (mut i32) (i32.const 1024))
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $ptr i32)
+      global.get $heap
+      local.set $ptr
+      global.get $heap
+      local.get 3
+      i32.add
+      global.set $heap
+      local.get $ptr)
+  )
+  (core instance $libc (instantiate $libc))
+  (alias core export $libc "memory" (core memory $memory))
+  (alias core export $libc "realloc" (core func $realloc))
+
+  (core func $log_lowered (canon lower (func $log) (memory $memory) (realloc $realloc)))
+
+  (core module $main
+    (import "host" "log" (func $log (param i32 i32)))
+    (import "libc" "memory" (memory 1))
+    (data (i32.const 16) "hello, world!")
+    (func $unused (result i32)
+      i32.const 42)
+    (func (export "greet") (result i32)
+      i32.const 16
+      i32.const 13
+      call $log
+      i32.const 0)
+    (func (export "add") (param i32 i32) (result i32)
+      local.get 0
+      local.get 1
+      i32.add)
+  )
+  (core instance $host_imports (export "log" (func $log_lowered)))
+  (core instance $main (instantiate $main
+    (with "host" (instance $host_imports))
+    (with "libc" (instance $libc))
+  ))
+
+  (type $greet_ty (func (result u32)))
+  (func $greet (type $greet_ty) (canon lift (core func $main "greet")))
+  (func $add (param "a" u32) (param "b" u32) (result u32)
+    (canon lift (core func $main "add")))
+
+  (component $inner
+    (core module $empty)
+    (core instance (instantiate $empty))
+  )
+  (instance (instantiate $inner))
+
+  (export "greet" (func $greet))
+  (export "add" (func $add))
+)
@@ -0,0 +1,25 @@
+;; Loads from addresses in the middle of a data segment, and in the gap between
+;; two segments. Only the segment that contains an address is referenced by a
+;; load from it, rather than the next segment after the address.
+;;
+;; Compile this file using the following command:
+;;
+;; wasm-tools parse data_lookup.wat -o data_lookup.wasm
+(module
+  (memory (export "memory") 1)
+
+  (data (i32.const 1024) "read from the middle")
+  (data (i32.const 2048) "after a gap that is read from")
+
+  (func $middle (result i32)
+    i32.const 1032
+    i32.load)
+
+  (func $gap (result i32)
+    i32.const 1536
+    i32.load)
+
+  (func (export "run") (result i32)
+    call $middle
+    call $gap
+    i32.add))
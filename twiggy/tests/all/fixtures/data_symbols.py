@@ -0,0 +1,135 @@
+#!/usr/bin/env python3
+# Generates `data_symbols.wasm`: a module with two data segments, the first
+# described by the data symbols of a `linking` custom section, and the second
+# by the static variables of its `.debug_*` custom sections.
+
+import struct
+
+from wasm_encode import abbrev_entry, custom, i32_const, section, string, uleb
+
+GREETING = 1024
+TABLE = 2048
+
+# main reads GREETING, COUNTER, and TABLE; UNUSED and FLAG are never read.
+main = bytearray([0x00])
+main += i32_const(GREETING) + bytes([0x2D, 0x00, 0x00])  # i32.load8_u
+main += i32_const(0) + bytes([0x28, 0x02]) + uleb(GREETING + 8)  # i32.load
+main += bytes([0x6A])  # i32.add
+main += i32_const(TABLE) + bytes([0x28, 0x02, 0x04])  # i32.load offset=4
+main += bytes([0x6A, 0x0B])  # i32.add, end
+code = uleb(1) + uleb(len(main)) + bytes(main)
+
+# GREETING, two bytes of padding, COUNTER, and UNUSED.
+first = b"hello\0" + b"\0\0" + struct.pack("<I", 7) + struct.pack("<I", 9)
+# TABLE, FLAG, and four bytes that no variable covers.
+second = struct.pack("<4I", 1, 2, 3, 4) + struct.pack("<I", 1) + b"\xff" * 4
+data = uleb(2)
+for address, contents in [(GREETING, first), (TABLE, second)]:
+    data += bytes([0x00]) + i32_const(address) + bytes([0x0B])
+    data += uleb(len(contents)) + contents
+
+# The `linking` section's symbol table, with a data symbol for each of the
+# first segment's objects.
+symbols = bytearray(uleb(3))
+for name, offset, size in [("GREETING", 0, 6), ("COUNTER", 8, 4), ("UNUSED", 12, 4)]:
+    symbols += bytes([1]) + uleb(0) + string(name)
+    symbols += uleb(0) + uleb(offset) + uleb(size)
+linking = uleb(2) + bytes([8]) + uleb(len(symbols)) + bytes(symbols)
+
+# .debug_str
+strings = bytearray()
+offsets = {}
+for s in ["src/lib.rs", "/home/me/data", "int", "TABLE", "FLAG", "greeting"]:
+    offsets[s] = len(strings)
+    strings += s.encode() + b"\0"
+
+DW_TAG_array_type = 0x01
+DW_TAG_compile_unit = 0x11
+DW_TAG_subrange_type = 0x21
+DW_TAG_base_type = 0x24
+DW_TAG_variable = 0x34
+DW_AT_location = 0x02
+DW_AT_name = 0x03
+DW_AT_byte_size = 0x0B
+DW_AT_comp_dir = 0x1B
+DW_AT_count = 0x37
+DW_AT_encoding = 0x3E
+DW_AT_type = 0x49
+DW_FORM_data1 = 0x0B
+DW_FORM_strp = 0x0E
+DW_FORM_ref4 = 0x13
+DW_FORM_exprloc = 0x18
+DW_OP_addr = 0x03
+
+abbrev = bytearray()
+abbrev += abbrev_entry(1, DW_TAG_compile_unit, True, [
+    (DW_AT_name, DW_FORM_strp),
+    (DW_AT_comp_dir, DW_FORM_strp),
+])
+abbrev += abbrev_entry(2, DW_TAG_base_type, False, [
+    (DW_AT_name, DW_FORM_strp),
+    (DW_AT_encoding, DW_FORM_data1),
+    (DW_AT_byte_size, DW_FORM_data1),
+])
+abbrev += abbrev_entry(3, DW_TAG_array_type, True, [
+    (DW_AT_type, DW_FORM_ref4),
+])
+abbrev += abbrev_entry(4, DW_TAG_subrange_type, False, [
+    (DW_AT_count, DW_FORM_data1),
+])
+abbrev += abbrev_entry(5, DW_TAG_variable, False, [
+    (DW_AT_name, DW_FORM_strp),
+    (DW_AT_type, DW_FORM_ref4),
+    (DW_AT_location, DW_FORM_exprloc),
+])
+abbrev += b"\0"
+
+
+def variable(name, ty, address):
+    location = bytes([DW_OP_addr]) + struct.pack("<I", address)
+    return (uleb(5) + struct.pack("<I", offsets[name]) + struct.pack("<I", ty)
+            + uleb(len(location)) + location)
+
+
+# .debug_info. The variable in the first segment is ignored, since that
+# segment has symbols in the `linking` section.
+header_size = 11
+die = bytearray()
+die += uleb(1)
+die += struct.pack("<I", offsets["src/lib.rs"])
+die += struct.pack("<I", offsets["/home/me/data"])
+int_offset = header_size + len(die)
+die += uleb(2) + struct.pack("<I", offsets["int"]) + bytes([0x05, 4])
+array_offset = header_size + len(die)
+die += uleb(3) + struct.pack("<I", int_offset)
+die += uleb(4) + bytes([4])
+die += b"\0"
+die += variable("TABLE", array_offset, TABLE)
+die += variable("FLAG", int_offset, TABLE + 16)
+die += variable("greeting", array_offset, GREETING)
+die += b"\0"
+info = struct.pack("<H", 4) + struct.pack("<I", 0) + bytes([4]) + die
+info = struct.pack("<I", len(info)) + info
+
+types = uleb(1) + bytes([0x60, 0x00, 0x01, 0x7F])
+funcs = uleb(1) + uleb(0)
+memory = uleb(1) + bytes([0x00]) + uleb(1)
+exports = uleb(1) + string("main") + bytes([0x00]) + uleb(0)
+names = uleb(1) + uleb(0) + string("main")
+names = bytes([1]) + uleb(len(names)) + names
+
+module = b"\0asm" + struct.pack("<I", 1)
+module += section(1, types)
+module += section(3, funcs)
+module += section(5, memory)
+module += section(7, exports)
+module += section(10, bytes(code))
+module += section(11, bytes(data))
+module += custom("linking", bytes(linking))
+module += custom(".debug_info", bytes(info))
+module += custom(".debug_abbrev", bytes(abbrev))
+module += custom(".debug_str", bytes(strings))
+module += custom("name", bytes(names))
+
+with open("data_symbols.wasm", "wb") as f:
+    f.write(module)
@@ -0,0 +1,155 @@
+#!/usr/bin/env python3
+# Generates `dwarf.wasm`: a tiny module whose `.debug_*` custom sections
+# describe its functions, like those emitted by LLVM with `-g`.
+
+import struct
+
+from wasm_encode import abbrev_entry, custom, section, string, uleb
+
+# main calls helper twice; unused is never called.
+bodies = [
+    ("main", bytes([0x00, 0x10, 0x01, 0x10, 0x01, 0x6A, 0x0B])),
+    ("helper", bytes([0x00, 0x41, 0x2A, 0x0B])),
+    ("unused", bytes([0x00, 0x41, 0x07, 0x41, 0x07, 0x6A, 0x0B])),
+]
+
+code = bytearray(uleb(len(bodies)))
+ranges = []
+for _, body in bodies:
+    code += uleb(len(body))
+    ranges.append((len(code), len(body)))
+    code += body
+
+# .debug_str
+strings = bytearray()
+offsets = {}
+# rustc names each compilation unit after the crate's root source file and its
+# codegen unit.
+unit_name = "src/lib.rs/@/dwarf.3f5a9c1e-cgu.0"
+for s in [unit_name, "/home/me/dwarf", "main", "helper", "unused", "int", "x", "y"]:
+    offsets[s] = len(strings)
+    strings += s.encode() + b"\0"
+
+DW_TAG_compile_unit = 0x11
+DW_TAG_subprogram = 0x2E
+DW_TAG_base_type = 0x24
+DW_TAG_variable = 0x34
+DW_AT_name = 0x03
+DW_AT_stmt_list = 0x10
+DW_AT_low_pc = 0x11
+DW_AT_high_pc = 0x12
+DW_AT_language = 0x13
+DW_AT_comp_dir = 0x1B
+DW_AT_encoding = 0x3E
+DW_AT_byte_size = 0x0B
+DW_AT_type = 0x49
+DW_FORM_addr = 0x01
+DW_FORM_data1 = 0x0B
+DW_FORM_data2 = 0x05
+DW_FORM_data4 = 0x06
+DW_FORM_strp = 0x0E
+DW_FORM_sec_offset = 0x17
+DW_FORM_ref4 = 0x13
+DW_LANG_Rust = 0x1C
+
+abbrev = bytearray()
+abbrev += abbrev_entry(1, DW_TAG_compile_unit, True, [
+    (DW_AT_name, DW_FORM_strp),
+    (DW_AT_language, DW_FORM_data2),
+    (DW_AT_comp_dir, DW_FORM_strp),
+    (DW_AT_stmt_list, DW_FORM_sec_offset),
+    (DW_AT_low_pc, DW_FORM_addr),
+    (DW_AT_high_pc, DW_FORM_data4),
+])
+abbrev += abbrev_entry(2, DW_TAG_subprogram, True, [
+    (DW_AT_name, DW_FORM_strp),
+    (DW_AT_low_pc, DW_FORM_addr),
+    (DW_AT_high_pc, DW_FORM_data4),
+    (DW_AT_type, DW_FORM_ref4),
+])
+abbrev += abbrev_entry(3, DW_TAG_base_type, False, [
+    (DW_AT_name, DW_FORM_strp),
+    (DW_AT_encoding, DW_FORM_data1),
+    (DW_AT_byte_size, DW_FORM_data1),
+])
+abbrev += abbrev_entry(4, DW_TAG_variable, False, [
+    (DW_AT_name, DW_FORM_strp),
+    (DW_AT_type, DW_FORM_ref4),
+])
+abbrev += b"\0"
+
+# .debug_line, with a single sequence covering the whole code section. helper
+# is inlined from another crate, so its code comes from that crate's file.
+helper_low = ranges[1][0]
+unused_low = ranges[2][0]
+program = bytearray()
+program += bytes([0x00, 5, 0x02]) + struct.pack("<I", 0)  # DW_LNE_set_address
+program += bytes([0x01])  # DW_LNS_copy
+program += bytes([0x04]) + uleb(2)  # DW_LNS_set_file
+program += bytes([0x02]) + uleb(helper_low)  # DW_LNS_advance_pc
+program += bytes([0x01])  # DW_LNS_copy
+program += bytes([0x04]) + uleb(1)  # DW_LNS_set_file
+program += bytes([0x02]) + uleb(unused_low - helper_low)  # DW_LNS_advance_pc
+program += bytes([0x01])  # DW_LNS_copy
+program += bytes([0x02]) + uleb(len(code) - unused_low)  # DW_LNS_advance_pc
+program += bytes([0x00, 1, 0x01])  # DW_LNE_end_sequence
+header = bytearray()
+header += bytes([1, 1, 1, 0xFB, 14, 13])
+header += bytes([0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1])
+header += b"/home/me/.cargo/registry/src/github.com-1ecc6299db9ec823/helper-0.1.0/src\0"
+header += b"\0"
+header += b"src/lib.rs\0" + uleb(0) + uleb(0) + uleb(0)
+header += b"lib.rs\0" + uleb(1) + uleb(0) + uleb(0)
+header += b"\0"
+line = struct.pack("<H", 4) + struct.pack("<I", len(header)) + header + program
+line = struct.pack("<I", len(line)) + line
+
+# .debug_info
+die = bytearray()
+header_size = 11
+die += uleb(1)
+die += struct.pack("<I", offsets[unit_name])
+die += struct.pack("<H", DW_LANG_Rust)
+die += struct.pack("<I", offsets["/home/me/dwarf"])
+die += struct.pack("<I", 0) + struct.pack("<I", 0) + struct.pack("<I", len(code))
+int_refs = []
+for i, (name, _) in enumerate(bodies):
+    low, size = ranges[i]
+    die += uleb(2) + struct.pack("<I", offsets[name]) + struct.pack("<I", low)
+    die += struct.pack("<I", size)
+    int_refs.append(len(die))
+    die += struct.pack("<I", 0)
+    for var in ["x", "y"][: 2 - i % 2]:
+        die += uleb(4) + struct.pack("<I", offsets[var])
+        int_refs.append(len(die))
+        die += struct.pack("<I", 0)
+    die += b"\0"
+int_offset = header_size + len(die)
+die += uleb(3) + struct.pack("<I", offsets["int"]) + bytes([0x05, 4])
+die += b"\0"
+for p in int_refs:
+    die[p:p + 4] = struct.pack("<I", int_offset)
+info = struct.pack("<H", 4) + struct.pack("<I", 0) + bytes([4]) + die
+info = struct.pack("<I", len(info)) + info
+
+types = uleb(1) + bytes([0x60, 0x00, 0x01, 0x7F])
+funcs = uleb(len(bodies)) + bytes([0] * len(bodies))
+exports = uleb(1) + string("main") + bytes([0x00]) + uleb(0)
+names = bytearray(uleb(len(bodies)))
+for i, (name, _) in enumerate(bodies):
+    names += uleb(i) + string(name)
+names = bytes([1]) + uleb(len(names)) + names
+
+module = b"\0asm" + struct.pack("<I", 1)
+module += section(1, types)
+module += section(3, funcs)
+module += section(7, exports)
+module += section(10, bytes(code))
+module += custom(".debug_info", bytes(info))
+module += custom(".debug_abbrev", bytes(abbrev))
+module += custom(".debug_line", bytes(line))
+module += custom(".debug_str", bytes(strings))
+module += custom("name", bytes(names))
+
+with open("dwarf.wasm", "wb") as f:
+    f.write(module)
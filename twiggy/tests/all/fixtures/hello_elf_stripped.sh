@@ -0,0 +1,7 @@
+#!/usr/bin/env sh
+# Generates `hello_elf_stripped`: `hello_elf` without its debug info and static
+# symbol table, leaving only its dynamic symbols.
+
+set -eu
+
+strip -o hello_elf_stripped hello_elf
@@ -0,0 +1,46 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test `twiggy history`. `history_1.wat`, `history_2.wat` and
+    ;; `history_3.wat` are three builds of the same module, in which `grows`
+    ;; gets larger in every build, `bounces` gets larger and then smaller
+    ;; again, `removed` is removed in the third build, and `added` is added
+    ;; in it.
+    ;; -------------------------------------------------------------------------
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse history_3.wat -o history_3.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $main (export "main") (param $x i32) (result i32)
+        local.get $x
+        call $grows
+        call $bounces
+        call $stable)
+
+    (func $grows (param $x i32) (result i32)
+        local.get $x
+        i32.const 1
+        i32.add
+        i32.const 2
+        i32.add
+        i32.const 3
+        i32.add)
+
+    (func $bounces (param $x i32) (result i32)
+        local.get $x
+        i32.const 1
+        i32.add
+        i32.const 2
+        i32.add)
+
+    (func $stable (param $x i32) (result i32)
+        local.get $x
+        i32.const 7
+        i32.mul)
+
+    (func $added (export "added") (param $x i32) (result i32)
+        local.get $x
+        i32.const 5
+        i32.xor)
+)
@@ -0,0 +1,34 @@
+;; References that are made from constant expressions and from passive element
+;; segments, rather than directly by code.
+(module
+  (import "env" "memory_base" (global $memory_base i32))
+  (import "env" "table_base" (global $table_base i32))
+  (import "env" "memory" (memory 1))
+
+  (table $indirect 4 funcref)
+  (table $defaults 2 funcref (ref.func $default_entry))
+
+  (global $callback funcref (ref.func $callback))
+  (global $data_end i32 (global.get $memory_base))
+
+  (elem $passive func $passive_entry)
+  (elem (table $indirect) (global.get $table_base) func $active_entry)
+
+  (data (global.get $memory_base) "relocatable data")
+
+  (func $callback)
+  (func $default_entry)
+  (func $passive_entry)
+  (func $active_entry)
+  (func $unused)
+
+  (func (export "init") (result i32)
+    global.get $callback
+    drop
+    i32.const 0
+    i32.const 0
+    i32.const 1
+    table.init $indirect $passive
+    elem.drop $passive
+    global.get $data_end)
+)
@@ -0,0 +1,18 @@
+#!/usr/bin/env sh
+# Generates `libhello.a`: a static archive of two tiny ELF object files.
+
+set -eu
+
+dir=$(mktemp -d)
+trap 'rm -rf "$dir"' EXIT
+
+cat > "$dir/add.c" <<'SRC'
+int add(int a, int b) { return a + b; }
+SRC
+cat > "$dir/greet.c" <<'SRC'
+static const char greeting[] = "hello, world";
+const char *greet(void) { return greeting; }
+SRC
+
+(cd "$dir" && gcc -Os -c add.c greet.c && ar rcs libhello.a add.o greet.o)
+cp "$dir/libhello.a" libhello.a
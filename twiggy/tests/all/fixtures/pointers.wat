@@ -0,0 +1,107 @@
+;; Static data that is referenced by addresses computed in a few different
+;; ways, rather than by an `i32.const` followed directly by a load. Constants
+;; that are only compared or multiplied aren't pointers, so they don't reference
+;; the data that they happen to point into.
+;;
+;; Compile this file using the following command:
+;;
+;; wasm-tools parse pointers.wat -o pointers.wasm
+(module
+  (import "env" "print" (func $print (param i32 i32)))
+  (memory (export "memory") 1)
+
+  (data (i32.const 1024) "message passed to a call")
+  (data (i32.const 2048) "field read at an offset from a base")
+  (data (i32.const 3072) "pointer stored into memory")
+  (data (i32.const 4096) "address kept in a local")
+  (data (i32.const 5120) "one arm of an if")
+  (data (i32.const 6144) "the other arm of an if")
+  (data (i32.const 7168) "table indexed by a parameter")
+  (data (i32.const 8192) "never referenced")
+  (data (i32.const 9216) "only compared against")
+  (data (i32.const 10240) "only multiplied")
+  (data (i32.const 11264) "copied by memory.copy")
+
+  (func $call_argument
+    i32.const 1024
+    i32.const 24
+    call $print)
+
+  (func $base_plus_offset (result i32)
+    i32.const 2000
+    i32.const 48
+    i32.add
+    i32.load offset=4)
+
+  (func $stored_pointer
+    i32.const 16
+    i32.const 3072
+    i32.store)
+
+  (func $through_local (result i32)
+    (local $ptr i32)
+    i32.const 4096
+    local.set $ptr
+    block
+      local.get $ptr
+      i32.load8_u
+      drop
+    end
+    local.get $ptr
+    i32.load)
+
+  (func $if_else (param $flag i32) (result i32)
+    (local $ptr i32)
+    local.get $flag
+    if
+      i32.const 5120
+      local.set $ptr
+    else
+      i32.const 6144
+      local.set $ptr
+    end
+    local.get $ptr
+    i32.load)
+
+  (func $indexed (param $index i32) (result i32)
+    i32.const 7168
+    local.get $index
+    i32.add
+    i32.load8_u)
+
+  (func $compared (param $len i32) (result i32)
+    local.get $len
+    i32.const 9216
+    i32.lt_u)
+
+  (func $multiplied (param $n i32) (result i32)
+    local.get $n
+    i32.const 10240
+    i32.mul)
+
+  (func $copied
+    i32.const 16
+    i32.const 11264
+    i32.const 21
+    memory.copy)
+
+  (func (export "run") (param i32) (result i32)
+    call $copied
+    call $call_argument
+    call $stored_pointer
+    call $base_plus_offset
+    call $through_local
+    i32.add
+    local.get 0
+    call $if_else
+    i32.add
+    local.get 0
+    call $indexed
+    i32.add
+    local.get 0
+    call $compared
+    i32.add
+    local.get 0
+    call $multiplied
+    i32.add)
+)
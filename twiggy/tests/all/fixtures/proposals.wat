@@ -0,0 +1,76 @@
+;; Exercises wasm proposals beyond the MVP: multi-value, reference types, bulk
+;; memory, multiple memories and tables, SIMD, and exception handling.
+;;
+;; NOTE: The test cases expect that this module is compiled with debug names
+;; written to the binary file, which affects the size percentages. Compile this
+;; file using the following command, with a version of wabt that supports
+;; `try_table`. Multi-value, reference types, bulk memory, and SIMD are enabled
+;; by default:
+;;
+;; wat2wasm --debug-names --enable-multi-memory --enable-exceptions proposals.wat -o proposals.wasm
+;;
+;; The checked-in binary was compiled with wasm-tools instead, which enables all
+;; of these proposals and writes debug names by default:
+;;
+;; wasm-tools parse proposals.wat -o proposals.wasm
+(module
+  (type $pair (func (param i32) (result i32 i64)))
+  (type $v (func (param v128) (result v128)))
+
+  (tag $oops (param i32))
+
+  (memory $mem 1)
+  (memory $scratch 1)
+  (table $funcs 2 funcref)
+  (table $refs 1 externref)
+
+  (data $active (memory $mem) (i32.const 16) "active data")
+  (data $passive "passive data that is copied into memory")
+
+  (elem $by_expr (table $funcs) (i32.const 0) funcref (ref.func $split) (ref.null func))
+  (elem declare func $callback)
+
+  (func $split (type $pair) (param i32) (result i32 i64)
+    local.get 0
+    i64.const 1)
+
+  (func $double (type $v) (param v128) (result v128)
+    local.get 0
+    local.get 0
+    i32x4.add)
+
+  (func $load_simd (result v128)
+    i32.const 16
+    v128.load)
+
+  (func $callback)
+
+  (func $get_callback (result funcref)
+    ref.func $callback)
+
+  (func $copy_passive
+    i32.const 0
+    i32.const 0
+    i32.const 8
+    memory.init $scratch $passive
+    data.drop $passive)
+
+  (func $throws (param i32)
+    local.get 0
+    throw $oops)
+
+  (func $catches (result i32)
+    (block $handler (result i32)
+      (try_table (catch $oops $handler)
+        i32.const 7
+        call $throws)
+      i32.const 0))
+
+  (export "split" (func $split))
+  (export "double" (func $double))
+  (export "load_simd" (func $load_simd))
+  (export "get_callback" (func $get_callback))
+  (export "copy_passive" (func $copy_passive))
+  (export "catches" (func $catches))
+  (export "oops" (tag $oops))
+  (export "refs" (table $refs)))
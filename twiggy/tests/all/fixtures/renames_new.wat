@@ -0,0 +1,59 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test how `twiggy diff` matches up items whose names changed. Compared to
+    ;; `renames_old.wat`, `renames_new.wat` changes the hashes of the Rust
+    ;; symbols, gives `app::parse` a suffix like the ones LLVM gives duplicated
+    ;; symbols, and changes the generic arguments of `Vec::push`, which stays
+    ;; about the same size, and of `Vec::clear`, which grows a lot. It also
+    ;; replaces `app::unused` with `app::added`, which comes first so that the
+    ;; indices of all the other functions change.
+    ;; -------------------------------------------------------------------------
+    ;; Compile this file using the following commands:
+    ;;
+    ;; wasm-tools parse renames_new.wat -o renames_new.wasm
+    ;; wasm-tools strip --all renames_new.wasm -o renames_new_stripped.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $app::added (export "added") (param $x i32) (result i32)
+        local.get $x
+        i32.const 3
+        i32.shl)
+
+    (func $app::main::h5555555555555555 (export "main") (param $x i32) (result i32)
+        local.get $x
+        call $app::helper::h6666666666666666
+        call $alloc::vec::Vec<u16>::push::h7777777777777777
+        call $alloc::vec::Vec<alloc::string::String>::clear::h9999999999999999
+        call $app::parse::h4444444444444444.12
+        i32.const 1
+        i32.add
+    )
+
+    (func $app::helper::h6666666666666666 (param $x i32) (result i32)
+        local.get $x
+        i32.const 1
+        i32.add)
+
+    (func $alloc::vec::Vec<u16>::push::h7777777777777777 (param $x i32) (result i32)
+        local.get $x
+        i32.const 200
+        i32.mul
+    )
+
+    (func $alloc::vec::Vec<alloc::string::String>::clear::h9999999999999999 (param $x i32) (result i32)
+        local.get $x
+        i32.const 24
+        i32.mul
+        i32.const 8
+        i32.add
+        i32.const 4
+        i32.rem_u
+        i32.const 1
+        i32.xor)
+
+    (func $app::parse::h4444444444444444.12 (param $x i32) (result i32)
+        local.get $x
+        i32.const 10
+        i32.rem_u)
+)
@@ -0,0 +1,49 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test how `twiggy diff` matches up items whose names changed. Compared to
+    ;; `renames_old.wat`, `renames_new.wat` changes the hashes of the Rust
+    ;; symbols, gives `app::parse` a suffix like the ones LLVM gives duplicated
+    ;; symbols, and changes the generic arguments of `Vec::push`, which stays
+    ;; about the same size, and of `Vec::clear`, which grows a lot. It also
+    ;; replaces `app::unused` with `app::added`, which comes first so that the
+    ;; indices of all the other functions change.
+    ;; -------------------------------------------------------------------------
+    ;; Compile this file using the following commands:
+    ;;
+    ;; wasm-tools parse renames_old.wat -o renames_old.wasm
+    ;; wasm-tools strip --all renames_old.wasm -o renames_old_stripped.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $app::main::h1111111111111111 (export "main") (param $x i32) (result i32)
+        local.get $x
+        call $app::helper::h2222222222222222
+        call $alloc::vec::Vec<u8>::push::h3333333333333333
+        call $alloc::vec::Vec<u8>::clear::h8888888888888888
+        call $app::parse::h4444444444444444
+    )
+
+    (func $app::helper::h2222222222222222 (param $x i32) (result i32)
+        local.get $x
+        i32.const 1
+        i32.add)
+
+    (func $alloc::vec::Vec<u8>::push::h3333333333333333 (param $x i32) (result i32)
+        local.get $x
+        i32.const 2
+        i32.mul
+    )
+
+    (func $alloc::vec::Vec<u8>::clear::h8888888888888888 (param $x i32) (result i32)
+        i32.const 0)
+
+    (func $app::parse::h4444444444444444 (param $x i32) (result i32)
+        local.get $x
+        i32.const 10
+        i32.rem_u)
+
+    (func $app::unused (export "unused") (param $x i32) (result i32)
+        local.get $x
+        i32.const 3
+        i32.shr_u)
+)
@@ -0,0 +1,45 @@
+(module
+    ;; -------------------------------------------------------------------------
+    ;; This is a WebAssembly text file that can be compiled in a wasm module to
+    ;; test `twiggy diff --retained`. `retained_old.wat` and `retained_new.wat`
+    ;; only differ in whether `main` calls `heavy`, which makes `heavy` and
+    ;; everything that only it calls reachable in the new version.
+    ;; -------------------------------------------------------------------------
+    ;; Compile this file using the following command:
+    ;;
+    ;; wasm-tools parse retained_new.wat -o retained_new.wasm
+    ;; -------------------------------------------------------------------------
+
+    (func $main (export "main") (param $x i32) (result i32)
+        local.get $x
+        call $light
+        call $heavy
+    )
+
+    (func $light (param $x i32) (result i32)
+        local.get $x
+        i32.const 1
+        i32.add)
+
+    (func $heavy (param $x i32) (result i32)
+        local.get $x
+        call $heavy_child
+        local.get $x
+        call $heavy_child
+        i32.mul
+        i32.const 1000
+        i32.div_s)
+
+    (func $heavy_child (param $x i32) (result i32)
+        local.get $x
+        local.get $x
+        i32.mul
+        local.get $x
+        i32.mul
+        i32.const 12345
+        i32.add
+        i32.const 6789
+        i32.rem_s
+        local.get $x
+        i32.xor)
+)
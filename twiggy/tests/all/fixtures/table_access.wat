@@ -0,0 +1,76 @@
+;; Functions in private tables that are read or written by instructions other
+;; than `call_indirect`.
+;;
+;; `$indirect` makes an indirect call through every table, but is never called
+;; itself. The functions in `$loaded`, `$copied`, `$grown`, and `$set` escape
+;; through `table.get`, `table.copy`, `table.grow`, and `table.set`, so they are
+;; retained by their tables. `$private` is only ever reached through
+;; `call_indirect`, so it is garbage along with `$indirect`.
+;;
+;; Compile this file using the following command:
+;;
+;; wasm-tools parse table_access.wat -o table_access.wasm
+(module
+  (type $thunk (func (result i32)))
+
+  (table $loaded 1 funcref)
+  (table $copied 1 funcref)
+  (table $grown 1 funcref)
+  (table $set 1 funcref)
+  (table $private 1 funcref)
+  (table $scratch 1 funcref)
+  (elem (table $loaded) (i32.const 0) func $loadedFn)
+  (elem (table $copied) (i32.const 0) func $copiedFn)
+  (elem (table $grown) (i32.const 0) func $grownFn)
+  (elem (table $set) (i32.const 0) func $setFn)
+  (elem (table $private) (i32.const 0) func $privateFn)
+
+  (func $loadedFn (type $thunk) i32.const 1)
+  (func $copiedFn (type $thunk) i32.const 2)
+  (func $grownFn (type $thunk) i32.const 3)
+  (func $setFn (type $thunk) i32.const 4)
+  (func $privateFn (type $thunk) i32.const 5)
+
+  (func $indirect (param $i i32) (result i32)
+    local.get $i
+    call_indirect $loaded (type $thunk)
+    local.get $i
+    call_indirect $copied (type $thunk)
+    i32.add
+    local.get $i
+    call_indirect $grown (type $thunk)
+    i32.add
+    local.get $i
+    call_indirect $set (type $thunk)
+    i32.add
+    local.get $i
+    call_indirect $private (type $thunk)
+    i32.add)
+
+  ;; Call whatever is in the first slot of `$loaded` through a reference.
+  (func $load (result i32)
+    i32.const 0
+    table.get $loaded
+    ref.cast (ref $thunk)
+    call_ref $thunk)
+
+  (func $copy
+    i32.const 0
+    i32.const 0
+    i32.const 1
+    table.copy $scratch $copied)
+
+  (func $grow (result i32)
+    ref.null func
+    i32.const 1
+    table.grow $grown)
+
+  (func $clear
+    i32.const 0
+    ref.null func
+    table.set $set)
+
+  (export "load" (func $load))
+  (export "copy" (func $copy))
+  (export "grow" (func $grow))
+  (export "clear" (func $clear)))
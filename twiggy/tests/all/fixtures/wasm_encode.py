@@ -0,0 +1,51 @@
+# Helpers for encoding wasm modules and their DWARF custom sections, shared by
+# the scripts that generate fixtures, like `dwarf.py` and `data_symbols.py`.
+
+
+def uleb(n):
+    out = bytearray()
+    while True:
+        b = n & 0x7F
+        n >>= 7
+        if n:
+            out.append(b | 0x80)
+        else:
+            out.append(b)
+            return bytes(out)
+
+
+def sleb(n):
+    out = bytearray()
+    while True:
+        b = n & 0x7F
+        n >>= 7
+        if (n == 0 and not b & 0x40) or (n == -1 and b & 0x40):
+            out.append(b)
+            return bytes(out)
+        out.append(b | 0x80)
+
+
+def section(id, payload):
+    return bytes([id]) + uleb(len(payload)) + payload
+
+
+def custom(name, payload):
+    name = name.encode()
+    return section(0, uleb(len(name)) + name + payload)
+
+
+def string(s):
+    s = s.encode()
+    return uleb(len(s)) + s
+
+
+def i32_const(n):
+    return bytes([0x41]) + sleb(n)
+
+
+def abbrev_entry(code, tag, children, attrs):
+    """A `.debug_abbrev` entry, given its `(attribute, form)` pairs."""
+    out = uleb(code) + uleb(tag) + bytes([1 if children else 0])
+    for at, form in attrs:
+        out += uleb(at) + uleb(form)
+    return out + b"\0\0"
@@ -16,6 +16,24 @@ test!(
     "json"
 );
 
+test!(
+    garbage_dot,
+    "garbage",
+    "./fixtures/garbage.wasm",
+    "-f",
+    "dot"
+);
+
+test!(
+    garbage_top_2_html,
+    "garbage",
+    "./fixtures/garbage.wasm",
+    "-n",
+    "2",
+    "-f",
+    "html"
+);
+
 test!(
     garbage_top_2_json,
     "garbage",
@@ -91,3 +109,32 @@ test!(
     "-n",
     "2"
 );
+
+test!(garbage_init_exprs, "garbage", "./fixtures/init_exprs.wasm");
+
+test!(
+    garbage_pointers_show_data_segments,
+    "garbage",
+    "./fixtures/pointers.wasm",
+    "--show-data-segments"
+);
+
+test!(
+    garbage_data_lookup_show_data_segments,
+    "garbage",
+    "./fixtures/data_lookup.wasm",
+    "--show-data-segments"
+);
+
+test!(
+    garbage_data_symbols_show_data_segments,
+    "garbage",
+    "./fixtures/data_symbols.wasm",
+    "--show-data-segments"
+);
+
+test!(
+    garbage_table_access,
+    "garbage",
+    "./fixtures/table_access.wasm"
+);
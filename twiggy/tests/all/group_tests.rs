@@ -0,0 +1,37 @@
+test!(
+    group_monos,
+    "group",
+    "./fixtures/monos.wasm",
+    "-c",
+    "./fixtures/groups.txt"
+);
+
+test!(
+    group_max_items,
+    "group",
+    "./fixtures/monos.wasm",
+    "-c",
+    "./fixtures/groups.txt",
+    "-n",
+    "2"
+);
+
+test!(
+    group_json,
+    "group",
+    "./fixtures/monos.wasm",
+    "-c",
+    "./fixtures/groups.txt",
+    "-f",
+    "json"
+);
+
+test!(
+    group_csv,
+    "group",
+    "./fixtures/monos.wasm",
+    "-c",
+    "./fixtures/groups.txt",
+    "-f",
+    "csv"
+);
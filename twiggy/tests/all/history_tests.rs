@@ -0,0 +1,55 @@
+test!(
+    history,
+    "history",
+    "./fixtures/history_1.wasm",
+    "./fixtures/history_2.wasm",
+    "./fixtures/history_3.wasm"
+);
+
+test!(
+    history_growing,
+    "history",
+    "./fixtures/history_1.wasm",
+    "./fixtures/history_2.wasm",
+    "./fixtures/history_3.wasm",
+    "--growing"
+);
+
+test!(
+    history_top_3,
+    "history",
+    "./fixtures/history_1.wasm",
+    "./fixtures/history_2.wasm",
+    "./fixtures/history_3.wasm",
+    "-n",
+    "3"
+);
+
+test!(
+    history_json,
+    "history",
+    "./fixtures/history_1.wasm",
+    "./fixtures/history_2.wasm",
+    "./fixtures/history_3.wasm",
+    "-f",
+    "json"
+);
+
+test!(
+    history_csv,
+    "history",
+    "./fixtures/history_1.wasm",
+    "./fixtures/history_2.wasm",
+    "./fixtures/history_3.wasm",
+    "-f",
+    "csv"
+);
+
+test!(
+    history_wee_alloc_snapshot,
+    "history",
+    "./fixtures/wee_alloc.twiggy",
+    "./fixtures/wee_alloc.2.wasm",
+    "-n",
+    "5"
+);
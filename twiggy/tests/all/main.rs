@@ -95,10 +95,18 @@ macro_rules! test {
     }
 }
 
+mod archive_format_tests;
+mod check_tests;
+mod component_format_tests;
+mod crates_tests;
 mod diff_tests;
 mod dominators_tests;
 mod elf_format_tests;
 mod garbage_tests;
+mod group_tests;
+mod history_tests;
 mod monos_tests;
 mod paths_tests;
+mod snapshot_tests;
+mod sources_tests;
 mod top_tests;
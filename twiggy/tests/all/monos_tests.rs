@@ -22,6 +22,30 @@ test!(
     "csv"
 );
 
+test!(
+    monos_wasm_dot,
+    "monos",
+    "./fixtures/monos.wasm",
+    "-m",
+    "2",
+    "-n",
+    "2",
+    "-f",
+    "dot"
+);
+
+test!(
+    monos_wasm_html,
+    "monos",
+    "./fixtures/monos.wasm",
+    "-m",
+    "2",
+    "-n",
+    "2",
+    "-f",
+    "html"
+);
+
 test!(monos_all, "monos", "./fixtures/monos.wasm", "-a");
 
 test!(
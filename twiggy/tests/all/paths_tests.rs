@@ -23,6 +23,24 @@ test!(
     "json"
 );
 
+test!(
+    paths_test_called_twice_dot,
+    "paths",
+    "./fixtures/paths_test.wasm",
+    "calledTwice",
+    "-f",
+    "dot"
+);
+
+test!(
+    paths_test_called_twice_html,
+    "paths",
+    "./fixtures/paths_test.wasm",
+    "calledTwice",
+    "-f",
+    "html"
+);
+
 test!(
     paths_test_called_twice,
     "paths",
@@ -198,7 +216,9 @@ test!(
     paths_error_test_no_max_paths,
     "paths",
     "./fixtures/mappings.wasm",
-    "std::io::error::Error::new::h8c006d5367bc92ed"
+    "std::io::error::Error::new::h8c006d5367bc92ed",
+    "-d",
+    "3"
 );
 
 test!(
@@ -207,7 +227,9 @@ test!(
     "-f",
     "csv",
     "./fixtures/mappings.wasm",
-    "std::io::error::Error::new::h8c006d5367bc92ed"
+    "std::io::error::Error::new::h8c006d5367bc92ed",
+    "-d",
+    "3"
 );
 
 test!(
@@ -216,7 +238,9 @@ test!(
     "-f",
     "json",
     "./fixtures/mappings.wasm",
-    "std::io::error::Error::new::h8c006d5367bc92ed"
+    "std::io::error::Error::new::h8c006d5367bc92ed",
+    "-d",
+    "3"
 );
 
 test!(
@@ -249,3 +273,87 @@ test!(
     "./fixtures/mappings.wasm",
     "std::io::error::Error::new::h8c006d5367bc92ed"
 );
+
+test!(
+    paths_test_call_indirect,
+    "paths",
+    "./fixtures/call_indirect.wasm",
+    "speakDog",
+    "speakCat",
+    "add"
+);
+
+test!(
+    paths_call_indirect_slots,
+    "paths",
+    "./fixtures/call_indirect_slots.wasm",
+    "speakDog",
+    "speakCat",
+    "speakFish",
+    "echo",
+    "other"
+);
+
+test!(
+    paths_table_access,
+    "paths",
+    "./fixtures/table_access.wasm",
+    "loadedFn",
+    "copiedFn",
+    "grownFn",
+    "setFn",
+    "privateFn"
+);
+
+test!(
+    paths_wasm_dwarf_debug_info,
+    "paths",
+    "--debug-info",
+    "./fixtures/dwarf.wasm",
+    "debug info for helper",
+    "debug info for unused"
+);
+
+test!(
+    paths_wasm_proposals,
+    "paths",
+    "./fixtures/proposals.wasm",
+    "tag[0]",
+    "data[1]",
+    "callback"
+);
+
+test!(
+    paths_init_exprs,
+    "paths",
+    "./fixtures/init_exprs.wasm",
+    "callback",
+    "default_entry",
+    "passive_entry",
+    "import env::memory_base"
+);
+
+test!(
+    paths_pointers,
+    "paths",
+    "./fixtures/pointers.wasm",
+    "data[0]",
+    "data[1]",
+    "data[2]",
+    "data[3]",
+    "data[4]",
+    "data[5]",
+    "data[6]",
+    "data[10]"
+);
+
+test!(
+    paths_data_symbols,
+    "paths",
+    "./fixtures/data_symbols.wasm",
+    "GREETING",
+    "COUNTER",
+    "TABLE",
+    "data[0]",
+    "data[1]"
+);
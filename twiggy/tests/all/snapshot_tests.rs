@@ -0,0 +1,18 @@
+// `wee_alloc.twiggy` is a snapshot of `wee_alloc.wasm`, taken with
+// `twiggy snapshot ./fixtures/wee_alloc.wasm -o ./fixtures/wee_alloc.twiggy`.
+// It must be taken again whenever the snapshot format's version changes.
+
+test!(snapshot_top, "top", "./fixtures/wee_alloc.twiggy");
+
+test!(
+    snapshot_dominators,
+    "dominators",
+    "./fixtures/wee_alloc.twiggy"
+);
+
+test!(
+    snapshot_diff,
+    "diff",
+    "./fixtures/wee_alloc.twiggy",
+    "./fixtures/wee_alloc.2.wasm"
+);
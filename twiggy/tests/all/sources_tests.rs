@@ -0,0 +1,36 @@
+test!(sources_wasm_dwarf, "sources", "./fixtures/dwarf.wasm");
+
+test!(
+    sources_wasm_dwarf_by_file,
+    "sources",
+    "./fixtures/dwarf.wasm",
+    "--by",
+    "file"
+);
+
+test!(
+    sources_wasm_dwarf_json,
+    "sources",
+    "./fixtures/dwarf.wasm",
+    "-f",
+    "json"
+);
+
+test!(sources_elf_hello_world, "sources", "./fixtures/hello_elf");
+
+test!(
+    sources_elf_hello_world_by_directory,
+    "sources",
+    "./fixtures/hello_elf",
+    "--by",
+    "directory",
+    "-n",
+    "10"
+);
+
+// Without any debug info, we can't tell where any of the code came from.
+test!(
+    sources_stripped_hello_world,
+    "sources",
+    "./fixtures/hello_elf_stripped"
+);
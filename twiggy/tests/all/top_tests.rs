@@ -26,6 +26,27 @@ test!(
     "./fixtures/mappings.wasm"
 );
 
+test!(
+    top_retaining_paths_dot,
+    "top",
+    "-n",
+    "3",
+    "--retaining-paths",
+    "./fixtures/paths_test.wasm",
+    "-f",
+    "dot"
+);
+
+test!(
+    top_wee_alloc_html,
+    "top",
+    "-n",
+    "5",
+    "./fixtures/wee_alloc.wasm",
+    "-f",
+    "html"
+);
+
 test!(
     top_2_json,
     "top",
@@ -79,3 +100,22 @@ test!(
 
 // Regression test for https://github.com/rustwasm/twiggy/issues/151
 test!(top_mono, "top", "./fixtures/mono.wasm", "-n", "10");
+
+test!(top_wasm_dwarf, "top", "./fixtures/dwarf.wasm");
+
+test!(
+    top_wasm_dwarf_debug_info,
+    "top",
+    "--debug-info",
+    "./fixtures/dwarf.wasm"
+);
+
+test!(top_wasm_proposals, "top", "./fixtures/proposals.wasm");
+
+test!(
+    top_bad_tag_indices,
+    "top",
+    "./fixtures/bad_tag_indices.wasm"
+);
+
+test!(top_data_symbols, "top", "./fixtures/data_symbols.wasm");
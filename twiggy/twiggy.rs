@@ -3,6 +3,8 @@
 #![deny(missing_docs)]
 #![deny(missing_debug_implementations)]
 
+use std::fs;
+use std::iter;
 use std::process;
 
 use failure::Fail;
@@ -25,18 +27,68 @@ fn main() {
 }
 
 fn run(opts: &opt::Options) -> Result<(), traits::Error> {
-    let mut items = parser::read_and_parse(opts.input(), opts.parse_mode())?;
+    let parse_options = parser::ParseOptions::default().debug_info(opts.debug_info());
+    let mut items =
+        parser::read_and_parse_with_options(opts.input(), opts.parse_mode(), parse_options)?;
 
     let data = match opts {
         opt::Options::Top(ref top) => analyze::top(&mut items, top)?,
         opt::Options::Dominators(ref doms) => analyze::dominators(&mut items, doms)?,
         opt::Options::Paths(ref paths) => analyze::paths(&mut items, paths)?,
         opt::Options::Monos(ref monos) => analyze::monos(&mut items, monos)?,
+        opt::Options::History(ref history) => {
+            let mut builds = vec![];
+            for input in &history.inputs()[1..] {
+                builds.push(parser::read_and_parse_with_options(
+                    input,
+                    opts.parse_mode(),
+                    parse_options,
+                )?);
+            }
+            let builds: Vec<_> = iter::once(&items).chain(&builds).collect();
+            analyze::history(&builds, history)?
+        }
         opt::Options::Garbage(ref garbo) => analyze::garbage(&items, garbo)?,
+        opt::Options::Sources(ref sources) => analyze::sources(&items, sources)?,
+        opt::Options::Crates(ref crates) => analyze::crates(&items, crates)?,
+        opt::Options::Group(ref group) => {
+            let config = fs::read_to_string(group.config())?;
+            analyze::group(&items, group, &config)?
+        }
         opt::Options::Diff(ref diff) => {
-            let mut new_items = parser::read_and_parse(diff.new_input(), opts.parse_mode())?;
+            let mut new_items = parser::read_and_parse_with_options(
+                diff.new_input(),
+                opts.parse_mode(),
+                parse_options,
+            )?;
             analyze::diff(&mut items, &mut new_items, diff)?
         }
+        opt::Options::Check(ref check) => {
+            let budgets = fs::read_to_string(check.budget())?;
+            let baseline = match check.baseline() {
+                Some(baseline) => Some(parser::read_and_parse_with_options(
+                    baseline,
+                    opts.parse_mode(),
+                    parse_options,
+                )?),
+                None => None,
+            };
+            let (report, exceeded) = analyze::check(&items, baseline.as_ref(), check, &budgets)?;
+
+            let mut dest = opts.output_destination().open()?;
+            report.emit(&items, &mut *dest, opts.output_format())?;
+            if exceeded > 0 {
+                return Err(traits::Error::with_msg(format!(
+                    "{} of the size budgets were exceeded",
+                    exceeded
+                )));
+            }
+            return Ok(());
+        }
+        opt::Options::Snapshot(_) => {
+            let mut dest = opts.output_destination().open()?;
+            return parser::write_snapshot(&items, &mut *dest);
+        }
     };
 
     let mut dest = opts.output_destination().open()?;